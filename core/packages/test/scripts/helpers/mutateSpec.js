@@ -28,7 +28,12 @@ function run() {
     data['genesis']['runtime']['parachainInfo']['parachainId'] = 1000;
     data['para_id'] = 1000;
 
-    data['genesis']['runtime']['basicInboundChannel']['sourceChannel'] = contracts['contracts']['BasicOutboundChannel']['address'];
+    data['genesis']['runtime']['basicInboundChannel']['sourceChannels'] = [contracts['contracts']['BasicOutboundChannel']['address']];
+    data['genesis']['runtime']['basicOutboundChannel']['inboundChannel'] = contracts['contracts']['BasicInboundChannel']['address'];
+
+    // Messages from the NativeTokens app may only call ERC20App.mint (pallet 20, call 0)
+    data['genesis']['runtime']['erc20App']['address'] = contracts['contracts']['NativeTokens']['address'];
+    data['genesis']['runtime']['dispatch']['allowedCalls'] = [[20, 0]];
 
     console.log(JSON.stringify(
       data,
@@ -287,6 +287,197 @@ where
 	root == &computed
 }
 
+/// A generated merkle proof for multiple leaves.
+///
+/// Sibling hashes shared by the leaves are only included once. All fields are flat lists of
+/// fixed-size items, so the proof maps directly onto the ABI types
+/// `(bytes32 root, bytes32[] proof, uint256 numberOfLeaves, uint256[] leafIndices, bytes[]
+/// leaves)`.
+#[derive(Encode, Decode, Debug, PartialEq, Eq)]
+pub struct MerkleMultiProof<T> {
+	/// Root hash of generated merkle tree.
+	pub root: H256,
+	/// Proof items, in the order they are consumed by [`verify_multiproof`].
+	///
+	/// The tree is processed bottom-up, one layer at a time. Within a layer, the nodes known to
+	/// the verifier are processed in ascending order, and the sibling of each node is taken from
+	/// the proof unless it is known as well.
+	pub proof: Vec<H256>,
+	/// Number of leaves in the original tree.
+	pub number_of_leaves: u64,
+	/// Indices of the leaves the proof is for (0-based), in ascending order.
+	pub leaf_indices: Vec<u64>,
+	/// Leaf contents, in the same order as `leaf_indices`.
+	pub leaves: Vec<T>,
+}
+
+/// Construct a Merkle Proof for multiple leaves given by indices.
+///
+/// The indices are sorted and deduplicated, so the returned leaves are in ascending order of
+/// their index.
+///
+/// # Panic
+///
+/// The function will panic if any of the given `leaf_indices` is greater than the number of
+/// leaves.
+pub fn merkle_multiproof<H, I, T>(leaves: I, leaf_indices: &[u64]) -> MerkleMultiProof<T>
+where
+	H: Hash<Output = H256>,
+	I: IntoIterator<Item = T>,
+	I::IntoIter: ExactSizeIterator,
+	T: AsRef<[u8]>,
+{
+	let mut leaf_indices = leaf_indices.to_vec();
+	leaf_indices.sort_unstable();
+	leaf_indices.dedup();
+
+	let mut proof_leaves = Vec::with_capacity(leaf_indices.len());
+	let mut hashes = vec![];
+	let mut number_of_leaves = 0;
+	for (idx, l) in (0u64..).zip(leaves) {
+		number_of_leaves = idx + 1;
+		hashes.push(<H as Hash>::hash(l.as_ref()));
+		if leaf_indices.binary_search(&idx).is_ok() {
+			proof_leaves.push(l);
+		}
+	}
+	assert!(
+		proof_leaves.len() == leaf_indices.len(),
+		"Requested `leaf_indices` contain an index greater than number of leaves."
+	);
+
+	/// The struct collects a proof for a set of leaves.
+	struct MultiProofCollection {
+		proof: Vec<H256>,
+		positions: Vec<u64>,
+	}
+
+	impl Visitor for MultiProofCollection {
+		fn move_up(&mut self) {
+			for position in self.positions.iter_mut() {
+				*position /= 2;
+			}
+			self.positions.dedup();
+		}
+
+		fn visit(&mut self, index: u64, left: &Option<H256>, right: &Option<H256>) {
+			let left_known = self.positions.binary_search(&index).is_ok();
+			let right_known = self.positions.binary_search(&(index + 1)).is_ok();
+			// we are at left branch only - right goes to the proof.
+			if left_known && !right_known {
+				if let Some(right) = right {
+					self.proof.push(*right);
+				}
+			}
+			// we are at right branch only - left goes to the proof.
+			if right_known && !left_known {
+				if let Some(left) = left {
+					self.proof.push(*left);
+				}
+			}
+		}
+	}
+
+	let mut collect_proof =
+		MultiProofCollection { proof: Default::default(), positions: leaf_indices.clone() };
+
+	let root = merkelize::<H, _, _>(hashes.into_iter(), &mut collect_proof);
+
+	MerkleMultiProof {
+		root,
+		proof: collect_proof.proof,
+		number_of_leaves,
+		leaf_indices,
+		leaves: proof_leaves,
+	}
+}
+
+/// Verify Merkle Multi Proof correctness versus given root hash.
+///
+/// The `leaf_indices` must be strictly ascending and `leaves` must be given in the same order.
+/// The proof must contain exactly the items generated by [`merkle_multiproof`], see
+/// [`MerkleMultiProof::proof`] for their order.
+pub fn verify_multiproof<'a, H, P, I, L>(
+	root: &H256,
+	proof: P,
+	number_of_leaves: u64,
+	leaf_indices: &[u64],
+	leaves: I,
+) -> bool
+where
+	H: Hash<Output = H256>,
+	P: IntoIterator<Item = H256>,
+	I: IntoIterator<Item = L>,
+	L: Into<Leaf<'a>>,
+{
+	let is_ascending = leaf_indices.windows(2).all(|pair| pair[0] < pair[1]);
+	match leaf_indices.last() {
+		Some(last) if is_ascending && *last < number_of_leaves => (),
+		_ => return false,
+	}
+
+	let leaf_hashes: Vec<H256> = leaves
+		.into_iter()
+		.map(|leaf| match leaf.into() {
+			Leaf::Value(content) => <H as Hash>::hash(content),
+			Leaf::Hash(hash) => hash,
+		})
+		.collect();
+	if leaf_hashes.len() != leaf_indices.len() {
+		return false
+	}
+	let mut nodes: Vec<(u64, H256)> = leaf_indices.iter().copied().zip(leaf_hashes).collect();
+
+	let mut proof = proof.into_iter();
+	let mut width = number_of_leaves;
+	while width > 1 {
+		let mut next = Vec::with_capacity(nodes.len());
+		let mut i = 0;
+		while i < nodes.len() {
+			let (index, hash) = nodes[i];
+			let parent = if index % 2 == 1 {
+				match proof.next() {
+					Some(sibling) => hash_pair::<H>(&sibling, &hash),
+					None => return false,
+				}
+			} else if index + 1 == width {
+				// Odd number of items. The item is promoted to the upper layer.
+				hash
+			} else if nodes.get(i + 1).map_or(false, |(sibling, _)| *sibling == index + 1) {
+				i += 1;
+				hash_pair::<H>(&hash, &nodes[i].1)
+			} else {
+				match proof.next() {
+					Some(sibling) => hash_pair::<H>(&hash, &sibling),
+					None => return false,
+				}
+			};
+			next.push((index / 2, parent));
+			i += 1;
+		}
+		nodes = next;
+		width = (width + 1) / 2;
+	}
+
+	proof.next().is_none() && nodes.first().map(|(_, hash)| hash) == Some(root)
+}
+
+/// Hash a pair of nodes, ordering them by value first.
+fn hash_pair<H>(a: &H256, b: &H256) -> H256
+where
+	H: Hash<Output = H256>,
+{
+	let mut combined = [0_u8; 64];
+	if a < b {
+		combined[..32].copy_from_slice(a.as_ref());
+		combined[32..].copy_from_slice(b.as_ref());
+	} else {
+		combined[..32].copy_from_slice(b.as_ref());
+		combined[32..].copy_from_slice(a.as_ref());
+	}
+	<H as Hash>::hash(&combined)
+}
+
 /// Processes a single row (layer) of a tree by taking pairs of elements,
 /// concatenating them, hashing and placing into resulting vector.
 ///
@@ -566,6 +757,112 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn should_generate_and_verify_multiproof() {
+		let _ = env_logger::try_init();
+		for n in 1..=10u64 {
+			let data: Vec<Vec<u8>> = (0..n).map(|i| vec![i as u8; 3]).collect();
+
+			// every subset of the leaves
+			for subset in 1u64..(1 << n) {
+				let indices: Vec<u64> = (0..n).filter(|i| subset & (1 << i) != 0).collect();
+				let proof = merkle_multiproof::<Keccak256, _, _>(data.clone(), &indices);
+
+				assert_eq!(proof.root, merkle_root::<Keccak256, _, _>(data.clone()));
+				assert_eq!(proof.leaf_indices, indices);
+				assert!(verify_multiproof::<Keccak256, _, _, _>(
+					&proof.root,
+					proof.proof.clone(),
+					n,
+					&proof.leaf_indices,
+					&proof.leaves,
+				));
+			}
+		}
+	}
+
+	#[test]
+	fn should_generate_multiproof_equal_to_single_proof() {
+		let _ = env_logger::try_init();
+		let data = vec!["a", "b", "c", "d", "e", "f", "g", "h", "i", "j"];
+
+		for l in 0..data.len() as u64 {
+			let proof = merkle_proof::<Keccak256, _, _>(data.clone(), l);
+			let multiproof = merkle_multiproof::<Keccak256, _, _>(data.clone(), &[l]);
+
+			assert_eq!(multiproof.root, proof.root);
+			assert_eq!(multiproof.proof, proof.proof);
+			assert_eq!(multiproof.leaves, vec![proof.leaf]);
+		}
+	}
+
+	#[test]
+	fn should_share_sibling_hashes_in_multiproof() {
+		let _ = env_logger::try_init();
+		let data: Vec<Vec<u8>> = (0..20u8).map(|i| vec![i]).collect();
+		let indices: Vec<u64> = (0..20).collect();
+
+		let proof = merkle_multiproof::<Keccak256, _, _>(data.clone(), &indices);
+
+		assert!(proof.proof.is_empty());
+		assert!(verify_multiproof::<Keccak256, _, _, _>(
+			&proof.root,
+			proof.proof,
+			20,
+			&indices,
+			&data,
+		));
+	}
+
+	#[test]
+	fn should_sort_and_dedup_multiproof_indices() {
+		let _ = env_logger::try_init();
+		let data = vec!["a", "b", "c", "d", "e"];
+
+		let proof = merkle_multiproof::<Keccak256, _, _>(data.clone(), &[4, 1, 4]);
+
+		assert_eq!(proof.leaf_indices, vec![1, 4]);
+		assert_eq!(proof.leaves, vec!["b", "e"]);
+	}
+
+	#[test]
+	fn should_reject_invalid_multiproof() {
+		let _ = env_logger::try_init();
+		let data = vec!["a", "b", "c", "d", "e"];
+		let proof = merkle_multiproof::<Keccak256, _, _>(data.clone(), &[1, 3]);
+		let verify = |root: &H256, proof: Vec<H256>, indices: &[u64], leaves: &[&str]| {
+			verify_multiproof::<Keccak256, _, _, _>(root, proof, 5, indices, leaves)
+		};
+
+		assert!(verify(&proof.root, proof.proof.clone(), &[1, 3], &["b", "d"]));
+		// wrong root
+		assert!(!verify(&H256::repeat_byte(1), proof.proof.clone(), &[1, 3], &["b", "d"]));
+		// wrong leaf
+		assert!(!verify(&proof.root, proof.proof.clone(), &[1, 3], &["b", "e"]));
+		// missing proof item
+		assert!(!verify(&proof.root, proof.proof[1..].to_vec(), &[1, 3], &["b", "d"]));
+		// superfluous proof item
+		let mut extended = proof.proof.clone();
+		extended.push(H256::zero());
+		assert!(!verify(&proof.root, extended, &[1, 3], &["b", "d"]));
+		// indices not ascending
+		assert!(!verify(&proof.root, proof.proof.clone(), &[3, 1], &["d", "b"]));
+		// index out of range
+		assert!(!verify(&proof.root, proof.proof.clone(), &[1, 5], &["b", "d"]));
+		// number of leaves does not match indices
+		assert!(!verify(&proof.root, proof.proof.clone(), &[1, 3], &["b"]));
+		assert!(!verify(&proof.root, proof.proof.clone(), &[1, 3], &["b", "d", "e"]));
+		// no leaves
+		assert!(!verify(&proof.root, vec![], &[], &[]));
+	}
+
+	#[test]
+	#[should_panic]
+	fn should_panic_on_invalid_multiproof_leaf_index() {
+		let _ = env_logger::try_init();
+		merkle_multiproof::<Keccak256, _, _>(vec!["a"], &[0, 5]);
+	}
+
 	#[test]
 	#[should_panic]
 	fn should_panic_on_invalid_leaf_index() {
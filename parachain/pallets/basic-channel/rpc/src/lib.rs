@@ -4,14 +4,17 @@ use jsonrpsee::{
 	types::error::{CallError, ErrorCode, ErrorObject},
 };
 
-use codec::{Decode, Encode};
+use codec::{Codec, Decode, Encode};
 use parking_lot::RwLock;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
 use sp_core::{offchain::OffchainStorage, Bytes, H256};
-use sp_runtime::traits::Keccak256;
+use sp_runtime::traits::{Block as BlockT, Keccak256};
 
-use std::sync::Arc;
+use std::{marker::PhantomData, sync::Arc};
 
-use snowbridge_basic_channel_merkle_proof::merkle_proof;
+use snowbridge_basic_channel_merkle_proof::{merkle_multiproof, merkle_proof};
+use snowbridge_basic_channel_runtime_api::BasicOutboundChannelApi;
 
 pub struct BasicChannel<T: OffchainStorage> {
 	storage: Arc<RwLock<T>>,
@@ -30,6 +33,13 @@ struct Leaves(pub Vec<Vec<u8>>);
 pub trait BasicChannelApi {
 	#[method(name = "basicOutboundChannel_getMerkleProof")]
 	fn get_merkle_proof(&self, commitment_hash: H256, leaf_index: u64) -> Result<Bytes>;
+
+	#[method(name = "basicOutboundChannel_getMerkleMultiProof")]
+	fn get_merkle_multi_proof(
+		&self,
+		commitment_hash: H256,
+		leaf_indices: Vec<u64>,
+	) -> Result<Bytes>;
 }
 
 impl<T> BasicChannelApiServer for BasicChannel<T>
@@ -37,6 +47,53 @@ where
 	T: OffchainStorage + 'static,
 {
 	fn get_merkle_proof(&self, commitment_hash: H256, leaf_index: u64) -> Result<Bytes> {
+		let leaves = self.leaves(commitment_hash)?;
+
+		if (leaf_index as usize) >= Vec::len(&leaves.0) {
+			return Err(Error::Call(CallError::Custom(ErrorObject::owned(
+				ErrorCode::InvalidParams.code(),
+				"leaf_index out of range",
+				None::<()>,
+			))))
+		}
+
+		let proof = merkle_proof::<Keccak256, Vec<Vec<u8>>, Vec<u8>>(leaves.0, leaf_index);
+		Ok(proof.encode().into())
+	}
+
+	fn get_merkle_multi_proof(
+		&self,
+		commitment_hash: H256,
+		leaf_indices: Vec<u64>,
+	) -> Result<Bytes> {
+		let leaves = self.leaves(commitment_hash)?;
+
+		if leaf_indices.is_empty() {
+			return Err(Error::Call(CallError::Custom(ErrorObject::owned(
+				ErrorCode::InvalidParams.code(),
+				"leaf_indices is empty",
+				None::<()>,
+			))))
+		}
+
+		if leaf_indices.iter().any(|index| (*index as usize) >= Vec::len(&leaves.0)) {
+			return Err(Error::Call(CallError::Custom(ErrorObject::owned(
+				ErrorCode::InvalidParams.code(),
+				"leaf_indices out of range",
+				None::<()>,
+			))))
+		}
+
+		let proof = merkle_multiproof::<Keccak256, Vec<Vec<u8>>, Vec<u8>>(leaves.0, &leaf_indices);
+		Ok(proof.encode().into())
+	}
+}
+
+impl<T> BasicChannel<T>
+where
+	T: OffchainStorage + 'static,
+{
+	fn leaves(&self, commitment_hash: H256) -> Result<Leaves> {
 		let encoded_leaves = match self
 			.storage
 			.read()
@@ -51,38 +108,68 @@ where
 				)))),
 		};
 
-		let leaves = match Leaves::decode(&mut encoded_leaves.as_ref()) {
-			Ok(leaves) => leaves,
-			Err(_) =>
-				return Err(Error::Call(CallError::Custom(ErrorObject::owned(
-					ErrorCode::InternalError.code(),
-					"could not decode leaves from storage",
-					None::<()>,
-				)))),
-		};
-
-		if (leaf_index as usize) >= Vec::len(&leaves.0) {
-			return Err(Error::Call(CallError::Custom(ErrorObject::owned(
-				ErrorCode::InvalidParams.code(),
-				"leaf_index out of range",
+		Leaves::decode(&mut encoded_leaves.as_ref()).map_err(|_| {
+			Error::Call(CallError::Custom(ErrorObject::owned(
+				ErrorCode::InternalError.code(),
+				"could not decode leaves from storage",
 				None::<()>,
-			))))
-		}
+			)))
+		})
+	}
+}
 
-		let proof = merkle_proof::<Keccak256, Vec<Vec<u8>>, Vec<u8>>(leaves.0, leaf_index);
-		Ok(proof.encode().into())
+pub struct BasicChannelCommitments<C, Block, SourceId, BlockNumber> {
+	client: Arc<C>,
+	_marker: PhantomData<(Block, SourceId, BlockNumber)>,
+}
+
+impl<C, Block, SourceId, BlockNumber> BasicChannelCommitments<C, Block, SourceId, BlockNumber> {
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+#[rpc(server)]
+pub trait BasicChannelCommitmentApi<BlockHash> {
+	/// Hashes of the commitments emitted in the given block, or in the best block if no block
+	/// is given.
+	#[method(name = "basicOutboundChannel_getCommitmentHashes")]
+	fn get_commitment_hashes(&self, at: Option<BlockHash>) -> Result<Vec<H256>>;
+}
+
+impl<C, Block, SourceId, BlockNumber> BasicChannelCommitmentApiServer<<Block as BlockT>::Hash>
+	for BasicChannelCommitments<C, Block, SourceId, BlockNumber>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: BasicOutboundChannelApi<Block, SourceId, BlockNumber>,
+	SourceId: Codec + Send + Sync + 'static,
+	BlockNumber: Codec + Send + Sync + 'static,
+{
+	fn get_commitment_hashes(&self, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<H256>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		self.client.runtime_api().block_commitments(at).map_err(|e| {
+			Error::Call(CallError::Custom(ErrorObject::owned(
+				ErrorCode::InternalError.code(),
+				"unable to query commitments",
+				Some(e.to_string()),
+			)))
+		})
 	}
 }
 
 #[cfg(test)]
 mod tests {
 	use crate::{BasicChannel, BasicChannelApiServer};
-	use codec::Encode;
+	use codec::{Decode, Encode};
 	use jsonrpsee::{
 		core::Error,
 		types::error::{CallError, ErrorCode},
 	};
+	use snowbridge_basic_channel_merkle_proof::{verify_multiproof, MerkleMultiProof};
 	use sp_core::offchain::OffchainStorage;
+	use sp_runtime::traits::Keccak256;
 
 	#[derive(Clone)]
 	struct MockOffchainStorage<'a> {
@@ -173,6 +260,46 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn basic_channel_rpc_should_create_multiproof_for_existing_commitment() {
+		let leaves: Vec<Vec<u8>> = vec![vec![1, 2], vec![3, 4], vec![5, 6], vec![7, 8]];
+		let rpc_handler =
+			create_rpc_handler(sp_offchain::STORAGE_PREFIX, TEST_HASH, Some(leaves.encode()));
+
+		let result = rpc_handler
+			.get_merkle_multi_proof(TEST_HASH.into(), vec![3, 0])
+			.expect("test input should have a Merkle proof")
+			.to_vec();
+		let proof = MerkleMultiProof::<Vec<u8>>::decode(&mut result.as_ref())
+			.expect("proof should decode successfully");
+
+		assert_eq!(proof.leaf_indices, vec![0, 3]);
+		assert!(verify_multiproof::<Keccak256, _, _, _>(
+			&proof.root,
+			proof.proof,
+			proof.number_of_leaves,
+			&proof.leaf_indices,
+			&proof.leaves,
+		));
+	}
+
+	#[test]
+	fn basic_channel_rpc_should_handle_multiproof_leaf_indices_out_of_bounds() {
+		let leaves: Vec<Vec<u8>> = vec![vec![1, 2], vec![3, 4]];
+		let rpc_handler =
+			create_rpc_handler(sp_offchain::STORAGE_PREFIX, TEST_HASH, Some(leaves.encode()));
+
+		let result = rpc_handler.get_merkle_multi_proof(TEST_HASH.into(), vec![0, 2]);
+
+		match result {
+			Err(Error::Call(CallError::Custom(errobj))) => {
+				assert_eq!(errobj.code(), ErrorCode::InvalidParams.code());
+				assert_eq!(errobj.message(), "leaf_indices out of range");
+			},
+			_ => assert!(false),
+		}
+	}
+
 	#[ignore]
 	#[test]
 	fn basic_channel_rpc_should_handle_leaf_index_out_of_bounds() {
@@ -0,0 +1,32 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use snowbridge_core::DeliveryStatus;
+use sp_core::{H160, H256};
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+	pub trait BasicOutboundChannelApi<SourceId, BlockNumber>
+	where
+		SourceId: Codec,
+		BlockNumber: Codec,
+	{
+		/// Number of messages waiting to be committed, including those in the backlog.
+		fn pending_messages() -> u64;
+
+		/// Nonce which will be assigned to the next message from `source_id`.
+		fn next_nonce(source_id: SourceId) -> u64;
+
+		/// Hash of the latest commitment and the block in which it was emitted.
+		fn latest_commitment() -> Option<(H256, BlockNumber)>;
+
+		/// Hashes of the commitments emitted in the block, in order.
+		fn block_commitments() -> Vec<H256>;
+	}
+
+	pub trait BasicInboundChannelApi {
+		/// Whether the message with nonce `nonce` from the Ethereum account `account` has been
+		/// delivered, and the outcome of its dispatch.
+		fn delivery_status(account: H160, nonce: u64) -> DeliveryStatus;
+	}
+}
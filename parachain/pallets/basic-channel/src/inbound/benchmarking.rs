@@ -1 +1,190 @@
+//! BasicInboundChannel pallet benchmarking
+use super::*;
 
+use frame_benchmarking::{benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_system::RawOrigin;
+use rlp::RlpStream;
+use sp_io::hashing::keccak_256;
+use sp_runtime::traits::Bounded;
+
+#[allow(unused_imports)]
+use crate::inbound::Pallet as BasicInboundChannel;
+
+// Upper bound of the proof size used to benchmark `submit`.
+const MAX_PROOF_SIZE: u32 = 16 * 1024;
+
+// Encode a value as a 32-byte ABI word.
+fn abi_word(value: &[u8]) -> [u8; 32] {
+	let mut word = [0u8; 32];
+	word[32 - value.len()..].copy_from_slice(value);
+	word
+}
+
+// Build the RLP-encoded log emitted by the outbound channel `channel` on Ethereum for a
+// message with the given payload.
+fn make_log(channel: H160, source: H160, account: H160, nonce: u64, payload: &[u8]) -> Vec<u8> {
+	let mut data = Vec::new();
+	data.extend_from_slice(&abi_word(source.as_bytes()));
+	data.extend_from_slice(&abi_word(account.as_bytes()));
+	data.extend_from_slice(&abi_word(&nonce.to_be_bytes()));
+	data.extend_from_slice(&abi_word(&128u64.to_be_bytes()));
+	data.extend_from_slice(&abi_word(&(payload.len() as u64).to_be_bytes()));
+	data.extend_from_slice(payload);
+	data.resize(data.len() + (32 - payload.len() % 32) % 32, 0);
+
+	let mut log = RlpStream::new_list(3);
+	log.append(&channel.as_bytes().to_vec());
+	log.begin_list(1);
+	log.append(&keccak_256(b"Message(address,address,uint64,bytes)").to_vec());
+	log.append(&data);
+	log.out().to_vec()
+}
+
+benchmarks! {
+	// Benchmark `submit` extrinsic with a payload of `p` bytes and a proof of `q` bytes under
+	// worst case conditions, i.e. the oldest delivery receipt is pruned, an acknowledgement is
+	// sent and the relayer is rewarded.
+	submit {
+		let p in 0 .. T::MaxInboundPayloadSize::get();
+		let q in 0 .. MAX_PROOF_SIZE;
+
+		let caller: T::AccountId = whitelisted_caller();
+		let channel = H160::repeat_byte(1);
+		let account = H160::repeat_byte(2);
+
+		<SourceChannels<T>>::insert(channel, SourceChannelInfo { sunset: Some(u64::MAX) });
+		<Acknowledgements<T>>::insert(H160::repeat_byte(4), true);
+		T::Currency::make_free_balance_be(
+			&T::AccountIdConverter::convert(H160::repeat_byte(4)),
+			BalanceOf::<T>::max_value() / 2u32.into(),
+		);
+
+		let pruned = MessageId::new(H160::repeat_byte(3), 1);
+		<DeliveryReceiptIds<T>>::insert(0, pruned);
+		<DeliveryReceipts<T>>::insert(pruned, DispatchOutcome::Succeeded);
+
+		let reward = T::Currency::minimum_balance();
+		<Reward<T>>::put(reward);
+		T::Currency::make_free_balance_be(
+			&BasicInboundChannel::<T>::account_id(),
+			reward * 100u32.into(),
+		);
+
+		let payload = vec![0u8; p as usize];
+		let log = make_log(channel, H160::repeat_byte(4), account, 1, &payload);
+		let message = T::Verifier::initialize_benchmark_messages(vec![log], q).remove(0);
+
+	}: _(RawOrigin::Signed(caller), message)
+	verify {
+		assert_eq!(<Nonce<T>>::get(account), 1);
+		assert!(!<DeliveryReceipts<T>>::contains_key(pruned));
+	}
+
+	// Benchmark `submit_batch` extrinsic with `n` messages included in the same block, each
+	// with a payload of `p` bytes and a proof of `q` bytes, under the same worst case
+	// conditions as `submit`.
+	submit_batch {
+		let n in 1 .. T::MaxMessagesPerBatch::get();
+		let p in 0 .. T::MaxInboundPayloadSize::get();
+		let q in 0 .. MAX_PROOF_SIZE;
+
+		let caller: T::AccountId = whitelisted_caller();
+		let channel = H160::repeat_byte(1);
+		let account = H160::repeat_byte(2);
+
+		<SourceChannels<T>>::insert(channel, SourceChannelInfo { sunset: Some(u64::MAX) });
+		<Acknowledgements<T>>::insert(H160::repeat_byte(4), true);
+		T::Currency::make_free_balance_be(
+			&T::AccountIdConverter::convert(H160::repeat_byte(4)),
+			BalanceOf::<T>::max_value() / 2u32.into(),
+		);
+
+		for i in 0 .. n {
+			let pruned = MessageId::new(H160::repeat_byte(3), i as u64 + 1);
+			<DeliveryReceiptIds<T>>::insert(i, pruned);
+			<DeliveryReceipts<T>>::insert(pruned, DispatchOutcome::Succeeded);
+		}
+
+		let reward = T::Currency::minimum_balance();
+		<Reward<T>>::put(reward);
+		T::Currency::make_free_balance_be(
+			&BasicInboundChannel::<T>::account_id(),
+			reward * (100 * n).into(),
+		);
+
+		let payload = vec![0u8; p as usize];
+		let logs = (1 ..= n as u64)
+			.map(|nonce| make_log(channel, H160::repeat_byte(4), account, nonce, &payload))
+			.collect();
+		let messages = T::Verifier::initialize_benchmark_messages(logs, q);
+
+	}: _(RawOrigin::Signed(caller), messages)
+	verify {
+		assert_eq!(<Nonce<T>>::get(account), n as u64);
+	}
+
+	set_delivery_mode {
+		let source = H160::repeat_byte(1);
+	}: _(RawOrigin::Root, source, DeliveryMode::Unordered)
+	verify {
+		assert_eq!(<DeliveryModes<T>>::get(source), DeliveryMode::Unordered);
+	}
+
+	add_source_channel {
+		let channel = H160::repeat_byte(1);
+	}: _(RawOrigin::Root, channel, Some(100))
+	verify {
+		assert!(<SourceChannels<T>>::contains_key(channel));
+	}
+
+	remove_source_channel {
+		let channel = H160::repeat_byte(1);
+		<SourceChannels<T>>::insert(channel, SourceChannelInfo::default());
+	}: _(RawOrigin::Root, channel)
+	verify {
+		assert!(!<SourceChannels<T>>::contains_key(channel));
+	}
+
+	set_reward {
+		let reward = T::Currency::minimum_balance();
+	}: _(RawOrigin::Root, reward)
+	verify {
+		assert_eq!(<Reward<T>>::get(), reward);
+	}
+
+	set_acknowledgement {
+		let source = H160::repeat_byte(1);
+	}: _(RawOrigin::Root, source, true)
+	verify {
+		assert!(<Acknowledgements<T>>::get(source));
+	}
+
+	// Benchmark the handling of an executed overweight message whose receipt has not been
+	// pruned yet and whose outcome is acknowledged.
+	overweight_message_executed {
+		let source = H160::repeat_byte(4);
+		let id = MessageId::new(H160::repeat_byte(2), 1);
+
+		<DeliveryReceipts<T>>::insert(id, DispatchOutcome::Overweight);
+		<Acknowledgements<T>>::insert(source, true);
+		T::Currency::make_free_balance_be(
+			&T::AccountIdConverter::convert(source),
+			BalanceOf::<T>::max_value() / 2u32.into(),
+		);
+	}: {
+		BasicInboundChannel::<T>::overweight_message_executed(
+			&id,
+			source,
+			DispatchOutcome::Succeeded,
+		);
+	}
+	verify {
+		assert_eq!(<DeliveryReceipts<T>>::get(id), Some(DispatchOutcome::Succeeded));
+	}
+}
+
+impl_benchmark_test_suite!(
+	BasicInboundChannel,
+	crate::inbound::test::new_tester(Default::default()),
+	crate::inbound::test::Test,
+);
@@ -1,11 +1,12 @@
 use ethabi::{Event, Param, ParamKind, Token};
-use snowbridge_ethereum::{log::Log, H160};
+use snowbridge_ethereum::{log::Log, H160, H256, U256};
 
 use sp_core::RuntimeDebug;
-use sp_std::{convert::TryFrom, prelude::*};
+use sp_io::hashing::keccak_256;
+use sp_std::prelude::*;
 
-// Used to decode a raw Ethereum log into an [`Envelope`].
-static EVENT_ABI: &Event = &Event {
+// Used to decode a raw Ethereum log into a version 1 [`Envelope`].
+static EVENT_ABI_V1: &Event = &Event {
 	signature: "Message(address,address,uint64,bytes)",
 	inputs: &[
 		Param { kind: ParamKind::Address, indexed: false },
@@ -16,9 +17,58 @@ static EVENT_ABI: &Event = &Event {
 	anonymous: false,
 };
 
+// Used to decode a raw Ethereum log into a version 2 [`Envelope`].
+static EVENT_ABI_V2: &Event = &Event {
+	signature: "Message(address,address,uint64,uint32,uint128,uint64,bytes)",
+	inputs: &[
+		Param { kind: ParamKind::Address, indexed: false },
+		Param { kind: ParamKind::Address, indexed: false },
+		Param { kind: ParamKind::Uint(64), indexed: false },
+		Param { kind: ParamKind::Uint(32), indexed: false },
+		Param { kind: ParamKind::Uint(128), indexed: false },
+		Param { kind: ParamKind::Uint(64), indexed: false },
+		Param { kind: ParamKind::Bytes, indexed: false },
+	],
+	anonymous: false,
+};
+
+/// Version of the envelope format, identified by the signature of the event emitted by the
+/// outbound channel on Ethereum.
+#[derive(Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum EnvelopeVersion {
+	/// `Message(address source, address account, uint64 nonce, bytes payload)`
+	V1,
+	/// `Message(address source, address account, uint64 nonce, uint32 destination,
+	/// uint128 fee, uint64 gasHint, bytes payload)`
+	V2,
+}
+
+impl EnvelopeVersion {
+	const ALL: [EnvelopeVersion; 2] = [EnvelopeVersion::V1, EnvelopeVersion::V2];
+
+	fn abi(&self) -> &'static Event<'static> {
+		match self {
+			EnvelopeVersion::V1 => EVENT_ABI_V1,
+			EnvelopeVersion::V2 => EVENT_ABI_V2,
+		}
+	}
+
+	/// The topic (topic0) of logs in this version of the envelope format.
+	pub fn topic(&self) -> H256 {
+		keccak_256(self.abi().signature.as_bytes()).into()
+	}
+
+	/// Find the version of the envelope format with the given topic.
+	pub fn from_topic(topic: &H256) -> Option<Self> {
+		Self::ALL.into_iter().find(|version| version.topic() == *topic)
+	}
+}
+
 /// An inbound message that has had its outer envelope decoded.
 #[derive(Clone, PartialEq, Eq, RuntimeDebug)]
 pub struct Envelope {
+	/// The version of the envelope format.
+	pub version: EnvelopeVersion,
 	/// The address of the outbound channel on Ethereum that forwarded this message.
 	pub channel: H160,
 	/// The application on Ethereum where the message originated from.
@@ -27,42 +77,108 @@ pub struct Envelope {
 	pub account: H160,
 	/// A nonce for enforcing replay protection and ordering.
 	pub nonce: u64,
+	/// The parachain the message is destined for, if specified by the source.
+	pub destination: Option<u32>,
+	/// The gas needed to dispatch the payload, as estimated by the source. It is converted to
+	/// ref time with `Config::WeightPerGas`.
+	pub gas_hint: Option<u64>,
 	/// The inner payload generated from the source application.
 	pub payload: Vec<u8>,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, RuntimeDebug)]
-pub struct EnvelopeDecodeError;
+pub enum EnvelopeDecodeError {
+	/// The log was emitted with an unknown event signature.
+	UnsupportedVersion,
+	/// The log data does not match the event signature.
+	InvalidEnvelope,
+	/// The nonce does not fit in a `uint64`.
+	NonceOutOfRange,
+	/// The destination does not fit in a `uint32`.
+	DestinationOutOfRange,
+	/// The fee does not fit in a `uint128`.
+	FeeOutOfRange,
+	/// The gas hint does not fit in a `uint64`.
+	GasHintOutOfRange,
+	/// The payload is larger than the maximum payload size.
+	PayloadTooLarge,
+}
 
-impl TryFrom<Log> for Envelope {
-	type Error = EnvelopeDecodeError;
+impl Envelope {
+	/// Decode a raw Ethereum log into an envelope.
+	///
+	/// Decoding is strict: integer values which do not fit in the type declared by the event
+	/// signature are rejected rather than truncated, as are payloads larger than
+	/// `max_payload_size` bytes.
+	pub fn decode(log: Log, max_payload_size: u32) -> Result<Self, EnvelopeDecodeError> {
+		let topic = log.topics.first().ok_or(EnvelopeDecodeError::InvalidEnvelope)?;
+		let version =
+			EnvelopeVersion::from_topic(topic).ok_or(EnvelopeDecodeError::UnsupportedVersion)?;
 
-	fn try_from(log: Log) -> Result<Self, Self::Error> {
-		let tokens = EVENT_ABI.decode(log.topics, log.data).map_err(|_| EnvelopeDecodeError)?;
+		let tokens = version
+			.abi()
+			.decode(log.topics, log.data)
+			.map_err(|_| EnvelopeDecodeError::InvalidEnvelope)?;
 
 		let mut iter = tokens.into_iter();
 
-		let source = match iter.next().ok_or(EnvelopeDecodeError)? {
-			Token::Address(source) => source,
-			_ => return Err(EnvelopeDecodeError),
-		};
-
-		let account = match iter.next().ok_or(EnvelopeDecodeError)? {
-			Token::Address(account) => account,
-			_ => return Err(EnvelopeDecodeError),
-		};
+		let source = decode_address(iter.next())?;
+		let account = decode_address(iter.next())?;
+		let nonce = decode_uint(iter.next(), 64, EnvelopeDecodeError::NonceOutOfRange)?.low_u64();
 
-		let nonce = match iter.next().ok_or(EnvelopeDecodeError)? {
-			Token::Uint(nonce) => nonce.low_u64(),
-			_ => return Err(EnvelopeDecodeError),
+		let (destination, gas_hint) = match version {
+			EnvelopeVersion::V1 => (None, None),
+			EnvelopeVersion::V2 => {
+				let destination =
+					decode_uint(iter.next(), 32, EnvelopeDecodeError::DestinationOutOfRange)?
+						.low_u32();
+				// The fee is paid out to relayers on Ethereum, so it is only validated here.
+				decode_uint(iter.next(), 128, EnvelopeDecodeError::FeeOutOfRange)?;
+				let gas_hint =
+					decode_uint(iter.next(), 64, EnvelopeDecodeError::GasHintOutOfRange)?.low_u64();
+				(Some(destination), Some(gas_hint))
+			},
 		};
 
-		let payload = match iter.next().ok_or(EnvelopeDecodeError)? {
+		let payload = match iter.next().ok_or(EnvelopeDecodeError::InvalidEnvelope)? {
 			Token::Bytes(payload) => payload,
-			_ => return Err(EnvelopeDecodeError),
+			_ => return Err(EnvelopeDecodeError::InvalidEnvelope),
 		};
+		if payload.len() > max_payload_size as usize {
+			return Err(EnvelopeDecodeError::PayloadTooLarge)
+		}
+
+		Ok(Self {
+			version,
+			channel: log.address,
+			account,
+			source,
+			nonce,
+			destination,
+			gas_hint,
+			payload,
+		})
+	}
+}
+
+fn decode_address(token: Option<Token>) -> Result<H160, EnvelopeDecodeError> {
+	match token.ok_or(EnvelopeDecodeError::InvalidEnvelope)? {
+		Token::Address(address) => Ok(address),
+		_ => Err(EnvelopeDecodeError::InvalidEnvelope),
+	}
+}
 
-		Ok(Self { channel: log.address, account, source, nonce, payload })
+// Decode an unsigned integer which must fit in `bits` bits, failing with `out_of_range` if it
+// does not.
+fn decode_uint(
+	token: Option<Token>,
+	bits: usize,
+	out_of_range: EnvelopeDecodeError,
+) -> Result<U256, EnvelopeDecodeError> {
+	match token.ok_or(EnvelopeDecodeError::InvalidEnvelope)? {
+		Token::Uint(value) if value.bits() <= bits => Ok(value),
+		Token::Uint(_) => Err(out_of_range),
+		_ => Err(EnvelopeDecodeError::InvalidEnvelope),
 	}
 }
 
@@ -70,6 +186,9 @@ impl TryFrom<Log> for Envelope {
 mod tests {
 	use super::*;
 	use hex_literal::hex;
+	use rand::{rngs::StdRng, Rng, SeedableRng};
+
+	const MAX_PAYLOAD_SIZE: u32 = 256;
 
 	const LOG: [u8; 251] = hex!(
 		"
@@ -85,20 +204,233 @@ mod tests {
 	);
 
 	#[test]
-	fn test_try_from_log() {
+	fn test_decode_log() {
 		let log: Log = rlp::decode(&LOG).unwrap();
-		let envelope = Envelope::try_from(log).unwrap();
+		assert_eq!(log.topics[0], EnvelopeVersion::V1.topic());
+
+		let envelope = Envelope::decode(log, MAX_PAYLOAD_SIZE).unwrap();
 
 		assert_eq!(
 			envelope,
 			Envelope {
+				version: EnvelopeVersion::V1,
 				channel: hex!["86d9ac0bab011917f57b9e9607833b4340f9d4f8"].into(),
 				source: hex!["89b4ab1ef20763630df9743acf155865600daff2"].into(),
 				account: hex!["04e00e6d2e9ea1e2af553de02a5172120bfa5c3e"].into(),
 				nonce: 1,
+				destination: None,
+				gas_hint: None,
 				payload: hex!("6172626974726172792d7061796c6f6164000000000000000000000000000000")
 					.into(),
 			}
 		)
 	}
+
+	#[test]
+	fn test_decode_log_v2() {
+		let log = Log {
+			address: H160::repeat_byte(1),
+			topics: vec![EnvelopeVersion::V2.topic()],
+			data: ethabi::encode(&[
+				Token::Address(H160::repeat_byte(2)),
+				Token::Address(H160::repeat_byte(3)),
+				Token::Uint(U256::from(7)),
+				Token::Uint(U256::from(1000)),
+				Token::Uint(U256::from(5_000_000_000u64)),
+				Token::Uint(U256::from(100_000)),
+				Token::Bytes(vec![1, 2, 3]),
+			]),
+		};
+
+		assert_eq!(
+			Envelope::decode(log, MAX_PAYLOAD_SIZE).unwrap(),
+			Envelope {
+				version: EnvelopeVersion::V2,
+				channel: H160::repeat_byte(1),
+				source: H160::repeat_byte(2),
+				account: H160::repeat_byte(3),
+				nonce: 7,
+				destination: Some(1000),
+				gas_hint: Some(100_000),
+				payload: vec![1, 2, 3],
+			}
+		)
+	}
+
+	#[test]
+	fn test_decode_log_with_unknown_version() {
+		let mut log: Log = rlp::decode(&LOG).unwrap();
+		log.topics[0] = keccak_256(b"Message(address,address,uint64,bytes32,bytes)").into();
+
+		assert_eq!(
+			Envelope::decode(log, MAX_PAYLOAD_SIZE),
+			Err(EnvelopeDecodeError::UnsupportedVersion)
+		);
+	}
+
+	#[test]
+	fn test_decode_log_with_invalid_data() {
+		let mut log: Log = rlp::decode(&LOG).unwrap();
+		log.topics[0] = EnvelopeVersion::V2.topic();
+
+		assert_eq!(
+			Envelope::decode(log, MAX_PAYLOAD_SIZE),
+			Err(EnvelopeDecodeError::InvalidEnvelope)
+		);
+	}
+
+	#[test]
+	fn test_decode_log_with_payload_too_large() {
+		let log: Log = rlp::decode(&LOG).unwrap();
+
+		assert_eq!(Envelope::decode(log.clone(), 32).map(|envelope| envelope.nonce), Ok(1));
+		assert_eq!(Envelope::decode(log, 31), Err(EnvelopeDecodeError::PayloadTooLarge));
+	}
+
+	// Number of random logs generated by each of the property tests below.
+	const ITERATIONS: usize = 1000;
+
+	// Generate a value which fits in `bits` bits.
+	fn random_uint(rng: &mut StdRng, bits: usize) -> U256 {
+		let value = U256::from(rng.gen::<u128>()) << 128 | U256::from(rng.gen::<u128>());
+		value >> rng.gen_range(256 - bits..256)
+	}
+
+	// Generate a value which does not fit in `bits` bits.
+	fn random_uint_out_of_range(rng: &mut StdRng, bits: usize) -> U256 {
+		random_uint(rng, bits) | U256::one() << rng.gen_range(bits..256)
+	}
+
+	fn random_envelope(rng: &mut StdRng) -> Envelope {
+		let version = if rng.gen() { EnvelopeVersion::V1 } else { EnvelopeVersion::V2 };
+		let (destination, gas_hint) = match version {
+			EnvelopeVersion::V1 => (None, None),
+			EnvelopeVersion::V2 => (Some(rng.gen()), Some(rng.gen())),
+		};
+		let payload_size = rng.gen_range(0..=MAX_PAYLOAD_SIZE as usize);
+		Envelope {
+			version,
+			channel: H160(rng.gen()),
+			source: H160(rng.gen()),
+			account: H160(rng.gen()),
+			nonce: rng.gen(),
+			destination,
+			gas_hint,
+			payload: (0..payload_size).map(|_| rng.gen()).collect(),
+		}
+	}
+
+	// ABI-encode the fields of an envelope, as the outbound channel on Ethereum would, with the
+	// given fee.
+	fn encode_tokens(envelope: &Envelope, fee: u128) -> Vec<Token> {
+		let mut tokens = vec![
+			Token::Address(envelope.source),
+			Token::Address(envelope.account),
+			Token::Uint(envelope.nonce.into()),
+		];
+		if envelope.version == EnvelopeVersion::V2 {
+			tokens.push(Token::Uint(envelope.destination.unwrap_or_default().into()));
+			tokens.push(Token::Uint(fee.into()));
+			tokens.push(Token::Uint(envelope.gas_hint.unwrap_or_default().into()));
+		}
+		tokens.push(Token::Bytes(envelope.payload.clone()));
+		tokens
+	}
+
+	fn encode_log(envelope: &Envelope, tokens: &[Token]) -> Log {
+		Log {
+			address: envelope.channel,
+			topics: vec![envelope.version.topic()],
+			data: ethabi::encode(tokens),
+		}
+	}
+
+	#[test]
+	fn test_decode_random_logs() {
+		let mut rng = StdRng::seed_from_u64(0);
+		for _ in 0..ITERATIONS {
+			let envelope = random_envelope(&mut rng);
+			let log = encode_log(&envelope, &encode_tokens(&envelope, rng.gen()));
+
+			assert_eq!(Envelope::decode(log, MAX_PAYLOAD_SIZE), Ok(envelope));
+		}
+	}
+
+	#[test]
+	fn test_decode_random_logs_with_values_out_of_range() {
+		let mut rng = StdRng::seed_from_u64(1);
+		for _ in 0..ITERATIONS {
+			let envelope = random_envelope(&mut rng);
+			let mut tokens = encode_tokens(&envelope, rng.gen());
+
+			// Index of the token, the width of its type and the expected error
+			let mut fields = vec![(2, 64, EnvelopeDecodeError::NonceOutOfRange)];
+			if envelope.version == EnvelopeVersion::V2 {
+				fields.push((3, 32, EnvelopeDecodeError::DestinationOutOfRange));
+				fields.push((4, 128, EnvelopeDecodeError::FeeOutOfRange));
+				fields.push((5, 64, EnvelopeDecodeError::GasHintOutOfRange));
+			}
+			let (index, bits, error) = fields[rng.gen_range(0..fields.len())];
+			tokens[index] = Token::Uint(random_uint_out_of_range(&mut rng, bits));
+
+			assert_eq!(
+				Envelope::decode(encode_log(&envelope, &tokens), MAX_PAYLOAD_SIZE),
+				Err(error)
+			);
+		}
+	}
+
+	#[test]
+	fn test_decode_random_logs_with_payload_too_large() {
+		let mut rng = StdRng::seed_from_u64(2);
+		for _ in 0..ITERATIONS {
+			let mut envelope = random_envelope(&mut rng);
+			let payload_size =
+				rng.gen_range(MAX_PAYLOAD_SIZE as usize + 1..=4 * MAX_PAYLOAD_SIZE as usize);
+			envelope.payload = (0..payload_size).map(|_| rng.gen()).collect();
+			let log = encode_log(&envelope, &encode_tokens(&envelope, rng.gen()));
+
+			assert_eq!(
+				Envelope::decode(log, MAX_PAYLOAD_SIZE),
+				Err(EnvelopeDecodeError::PayloadTooLarge)
+			);
+		}
+	}
+
+	#[test]
+	fn test_decode_random_logs_with_truncated_data() {
+		let mut rng = StdRng::seed_from_u64(3);
+		for _ in 0..ITERATIONS {
+			let envelope = random_envelope(&mut rng);
+			let mut log = encode_log(&envelope, &encode_tokens(&envelope, rng.gen()));
+			// Drop at least one 32-byte word, so that the payload can no longer be decoded
+			let len = rng.gen_range(0..=log.data.len() - 32);
+			log.data.truncate(len);
+
+			assert_eq!(
+				Envelope::decode(log, MAX_PAYLOAD_SIZE),
+				Err(EnvelopeDecodeError::InvalidEnvelope)
+			);
+		}
+	}
+
+	#[test]
+	fn test_decode_random_data() {
+		let mut rng = StdRng::seed_from_u64(4);
+		for _ in 0..ITERATIONS {
+			let version = if rng.gen() { EnvelopeVersion::V1 } else { EnvelopeVersion::V2 };
+			let size = rng.gen_range(0..1024);
+			let log = Log {
+				address: H160(rng.gen()),
+				topics: vec![version.topic()],
+				data: (0..size).map(|_| rng.gen()).collect(),
+			};
+
+			// Arbitrary data must never cause a panic, nor decode into an envelope which violates
+			// the bounds of the envelope format.
+			if let Ok(envelope) = Envelope::decode(log, MAX_PAYLOAD_SIZE) {
+				assert!(envelope.payload.len() <= MAX_PAYLOAD_SIZE as usize);
+			}
+		}
+	}
 }
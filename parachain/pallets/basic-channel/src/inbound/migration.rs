@@ -0,0 +1,35 @@
+//! Storage migrations for the basic inbound channel.
+
+use super::*;
+
+use frame_support::{
+	storage_alias,
+	traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+};
+use sp_std::marker::PhantomData;
+
+pub mod v1 {
+	use super::*;
+
+	/// The single trusted source channel, replaced by [`SourceChannels`] in version 1.
+	#[storage_alias]
+	pub type SourceChannel<T: Config> = StorageValue<Pallet<T>, H160>;
+
+	/// Move the single trusted source channel into [`SourceChannels`], without a sunset.
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 0 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			if let Some(channel) = SourceChannel::<T>::take() {
+				<SourceChannels<T>>::insert(channel, SourceChannelInfo::default());
+			}
+			StorageVersion::new(1).put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(2, 3)
+		}
+	}
+}
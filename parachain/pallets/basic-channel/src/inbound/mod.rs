@@ -1,4 +1,5 @@
-mod envelope;
+pub mod envelope;
+pub mod migration;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
@@ -8,14 +9,86 @@ pub mod weights;
 #[cfg(test)]
 mod test;
 
+use codec::{Decode, Encode, MaxEncodedLen};
+use ethabi::Token;
+use frame_support::{
+	dispatch::{PostDispatchInfo, WithPostDispatchInfo},
+	storage::with_storage_layer,
+	traits::{Currency, ExistenceRequirement, Get},
+	weights::Weight,
+	PalletId,
+};
 use frame_system::ensure_signed;
-use snowbridge_core::{Message, MessageDispatch, MessageId, Verifier};
-use sp_core::H160;
-use sp_std::convert::TryFrom;
+use scale_info::TypeInfo;
+use snowbridge_core::{
+	DeliveryStatus, DispatchOutcome, Message, MessageDispatch, MessageId, OutboundChannel,
+	OverweightMessageExecuted, Proof, Verifier,
+};
+use snowbridge_ethereum::Log;
+use sp_core::{RuntimeDebug, H160};
+use sp_runtime::traits::{AccountIdConversion, Convert, Zero};
+use sp_std::prelude::*;
 
-use envelope::Envelope;
+use envelope::{Envelope, EnvelopeDecodeError};
 pub use weights::WeightInfo;
 
+/// Number of nonces ahead of the last in-order nonce of an account which can be delivered out of
+/// order. This is the number of bits in the replay protection bitmap.
+pub const REPLAY_WINDOW_SIZE: u64 = u128::BITS as u64;
+
+/// How messages from a source application on Ethereum are delivered.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub enum DeliveryMode {
+	/// Messages are only accepted in nonce order.
+	Ordered,
+	/// Messages are accepted in any order, as long as they have not been delivered already and
+	/// their nonce is within [`REPLAY_WINDOW_SIZE`] of the last in-order nonce.
+	Unordered,
+}
+
+impl Default for DeliveryMode {
+	fn default() -> Self {
+		DeliveryMode::Ordered
+	}
+}
+
+/// A trusted outbound channel on the Ethereum side.
+#[derive(
+	Encode, Decode, Copy, Clone, Default, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo,
+)]
+pub struct SourceChannelInfo {
+	/// Number of the first Ethereum block from which messages from the channel are no longer
+	/// accepted. `None` if the channel has no sunset.
+	pub sunset: Option<u64>,
+}
+
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// Total size in bytes of the keys and values in a message proof.
+pub fn proof_size(proof: &Proof) -> u32 {
+	let (keys, values) = &proof.data;
+	keys.iter().chain(values.iter()).map(|item| item.len() as u32).sum()
+}
+
+/// Average of `size` over a batch of messages.
+pub fn average_size<F: Fn(&Message) -> u32>(messages: &[Message], size: F) -> u32 {
+	if messages.is_empty() {
+		return 0
+	}
+	let total: u64 = messages.iter().map(|message| size(message) as u64).sum();
+	(total / messages.len() as u64) as u32
+}
+
+/// Weight of `submit_batch` for a batch of messages, excluding the weight of dispatching them.
+fn batch_weight<T: Config>(messages: &[Message]) -> Weight {
+	T::WeightInfo::submit_batch(
+		messages.len() as u32,
+		average_size(messages, |message| message.data.len() as u32),
+		average_size(messages, |message| proof_size(&message.proof)),
+	)
+}
+
 pub use pallet::*;
 
 #[frame_support::pallet]
@@ -26,8 +99,12 @@ pub mod pallet {
 	use frame_support::pallet_prelude::*;
 	use frame_system::pallet_prelude::*;
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::config]
@@ -40,6 +117,47 @@ pub mod pallet {
 		/// Verifier module for message verification.
 		type MessageDispatch: MessageDispatch<Self, MessageId>;
 
+		/// Max number of delivery receipts to keep. Once reached, the receipt of the oldest
+		/// delivered message is pruned.
+		#[pallet::constant]
+		type MaxDeliveryReceipts: Get<u32>;
+
+		/// Max number of messages in a batch submitted with `submit_batch`
+		#[pallet::constant]
+		type MaxMessagesPerBatch: Get<u32>;
+
+		/// Max size of the payload of an inbound message
+		#[pallet::constant]
+		type MaxInboundPayloadSize: Get<u32>;
+
+		/// Max weight which can be used to dispatch the call of a single message. Messages whose
+		/// calls require more are not dispatched.
+		#[pallet::constant]
+		type MaxDispatchWeight: Get<Weight>;
+
+		/// Ref time granted to the dispatch of a message for each unit of gas in the gas hint
+		/// of its envelope.
+		#[pallet::constant]
+		type WeightPerGas: Get<u64>;
+
+		/// ID of this parachain. Messages destined for another parachain are rejected.
+		#[pallet::constant]
+		type ParachainId: Get<u32>;
+
+		/// Currency used to pay relayer rewards
+		type Currency: Currency<Self::AccountId>;
+
+		/// Pallet ID of the account from which relayers are rewarded for delivering messages
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Outbound channel on which acknowledgements are sent back to Ethereum
+		type OutboundChannel: OutboundChannel<Self::AccountId>;
+
+		/// Derives the Substrate account of a source application, which pays the outbound fee
+		/// of the acknowledgements sent back to it.
+		type AccountIdConverter: Convert<H160, Self::AccountId>;
+
 		/// Weight information for extrinsics in this pallet
 		type WeightInfo: WeightInfo;
 	}
@@ -48,82 +166,495 @@ pub mod pallet {
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
 
 	#[pallet::event]
-	pub enum Event<T> {}
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A message was received from `channel` and dispatched. `block_number` is the number of
+		/// the Ethereum block in which the message was included.
+		MessageReceived {
+			channel: H160,
+			account: H160,
+			nonce: u64,
+			block_number: u64,
+		},
+		DeliveryModeUpdated {
+			source: H160,
+			mode: DeliveryMode,
+		},
+		/// An outbound channel on the Ethereum side was added to the trusted source channels, or
+		/// its sunset was updated.
+		SourceChannelAdded {
+			channel: H160,
+			sunset: Option<u64>,
+		},
+		/// An outbound channel on the Ethereum side was removed from the trusted source channels.
+		SourceChannelRemoved {
+			channel: H160,
+		},
+		/// A relayer was rewarded for delivering a message.
+		RelayerRewarded {
+			relayer: T::AccountId,
+			amount: BalanceOf<T>,
+		},
+		RewardUpdated {
+			reward: BalanceOf<T>,
+		},
+		/// The message at `index` in a batch submitted with `submit_batch` was not delivered.
+		BatchMessageFailed {
+			index: u32,
+			error: DispatchError,
+		},
+		/// Acknowledgements were enabled or disabled for the source application `source`.
+		AcknowledgementUpdated {
+			source: H160,
+			enabled: bool,
+		},
+		/// The acknowledgement of a delivered message could not be submitted to the outbound
+		/// channel.
+		AcknowledgementFailed {
+			account: H160,
+			nonce: u64,
+			error: DispatchError,
+		},
+	}
 
 	#[pallet::error]
 	pub enum Error<T> {
-		/// Message came from an invalid outbound channel on the Ethereum side.
+		/// Message came from an invalid outbound channel on the Ethereum side, or from a channel
+		/// which has been sunset.
 		InvalidSourceChannel,
 		/// Message has an invalid envelope.
 		InvalidEnvelope,
-		/// Message has an unexpected nonce.
+		/// Message has an unexpected nonce. Either it was already delivered, or it is out of
+		/// order for its delivery mode.
 		InvalidNonce,
+		/// The outbound channel is not a trusted source channel.
+		UnknownSourceChannel,
+		/// The batch is empty or has more than `MaxMessagesPerBatch` messages.
+		InvalidBatchSize,
+		/// Message has an envelope in an unsupported version of the envelope format.
+		UnsupportedEnvelopeVersion,
+		/// Message has an envelope with a nonce, destination or gas hint which does not fit in
+		/// its declared type.
+		EnvelopeValueOutOfRange,
+		/// Message has a payload larger than `MaxInboundPayloadSize`.
+		PayloadTooLarge,
+		/// Message is destined for another parachain.
+		InvalidDestination,
 	}
 
-	/// Source channel on the ethereum side
+	/// Trusted source channels on the ethereum side
 	#[pallet::storage]
 	#[pallet::getter(fn source_channel)]
-	pub type SourceChannel<T: Config> = StorageValue<_, H160, ValueQuery>;
+	pub type SourceChannels<T: Config> =
+		StorageMap<_, Twox64Concat, H160, SourceChannelInfo, OptionQuery>;
 
+	/// Nonce of the last message from an account for which all messages up to and including it
+	/// have been delivered.
 	#[pallet::storage]
 	pub type Nonce<T: Config> = StorageMap<_, Twox64Concat, H160, u64, ValueQuery>;
 
+	/// Messages from an account which were delivered out of order. Bit `i` is set if the message
+	/// with nonce `Nonce + 1 + i` has been delivered.
+	#[pallet::storage]
+	pub type ReplayBitmap<T: Config> = StorageMap<_, Twox64Concat, H160, u128, ValueQuery>;
+
+	/// Delivery mode of each source application on Ethereum. Applications default to ordered
+	/// delivery.
+	#[pallet::storage]
+	pub type DeliveryModes<T: Config> = StorageMap<_, Twox64Concat, H160, DeliveryMode, ValueQuery>;
+
+	/// Source applications on Ethereum which are sent an acknowledgement with the dispatch
+	/// outcome of each of their delivered messages.
+	#[pallet::storage]
+	pub type Acknowledgements<T: Config> = StorageMap<_, Twox64Concat, H160, bool, ValueQuery>;
+
 	#[pallet::storage]
 	pub type LatestVerifiedBlockNumber<T: Config> = StorageValue<_, u64, ValueQuery>;
 
+	/// Dispatch outcome of recently delivered messages.
+	#[pallet::storage]
+	pub type DeliveryReceipts<T: Config> =
+		StorageMap<_, Twox64Concat, MessageId, DispatchOutcome, OptionQuery>;
+
+	/// Ring buffer of the messages which have a delivery receipt, used to prune the oldest
+	/// receipt once [`Config::MaxDeliveryReceipts`] is reached.
+	#[pallet::storage]
+	pub type DeliveryReceiptIds<T: Config> =
+		StorageMap<_, Twox64Concat, u32, MessageId, OptionQuery>;
+
+	/// Index of the next slot to use in [`DeliveryReceiptIds`].
+	#[pallet::storage]
+	pub type NextDeliveryReceiptIndex<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// Reward paid to the relayer for each delivered message.
+	#[pallet::storage]
+	#[pallet::getter(fn reward)]
+	pub type Reward<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
 	#[pallet::genesis_config]
-	pub struct GenesisConfig {
-		pub source_channel: H160,
+	pub struct GenesisConfig<T: Config> {
+		pub source_channels: Vec<H160>,
+		pub reward: BalanceOf<T>,
 	}
 
 	#[cfg(feature = "std")]
-	impl Default for GenesisConfig {
+	impl<T: Config> Default for GenesisConfig<T> {
 		fn default() -> Self {
-			Self { source_channel: Default::default() }
+			Self { source_channels: Default::default(), reward: Default::default() }
 		}
 	}
 
 	#[pallet::genesis_build]
-	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
 		fn build(&self) {
-			<SourceChannel<T>>::put(self.source_channel);
+			for channel in &self.source_channels {
+				<SourceChannels<T>>::insert(channel, SourceChannelInfo::default());
+			}
+			<Reward<T>>::put(self.reward);
 		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
+		/// Submit a message relayed from Ethereum. Submitting a message which is successfully
+		/// delivered is free, and the signer is rewarded from the pallet account. Weight reserved
+		/// for dispatching the message but not used is refunded.
 		#[pallet::call_index(0)]
-		#[pallet::weight(100_000_000)]
-		pub fn submit(origin: OriginFor<T>, message: Message) -> DispatchResult {
-			ensure_signed(origin)?;
+		#[pallet::weight(
+			T::WeightInfo::submit(message.data.len() as u32, proof_size(&message.proof))
+				.saturating_add(T::MaxDispatchWeight::get())
+		)]
+		pub fn submit(origin: OriginFor<T>, message: Message) -> DispatchResultWithPostInfo {
+			let relayer = ensure_signed(origin)?;
+			let weight =
+				T::WeightInfo::submit(message.data.len() as u32, proof_size(&message.proof));
+
 			// submit message to verifier for verification
-			let (log, block_number) = T::Verifier::verify(&message)?;
+			let (log, block_number) =
+				T::Verifier::verify(&message).map_err(|err| err.with_weight(weight))?;
 
+			let dispatch_weight =
+				Self::deliver(log, block_number).map_err(|err| err.with_weight(weight))?;
+			Self::pay_reward(&relayer);
+
+			Ok(PostDispatchInfo {
+				actual_weight: Some(weight.saturating_add(dispatch_weight)),
+				pays_fee: Pays::No,
+			})
+		}
+
+		/// Set the delivery mode for messages from the source application `source`.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::set_delivery_mode())]
+		pub fn set_delivery_mode(
+			origin: OriginFor<T>,
+			source: H160,
+			mode: DeliveryMode,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			<DeliveryModes<T>>::insert(source, mode);
+			Self::deposit_event(Event::DeliveryModeUpdated { source, mode });
+			Ok(())
+		}
+
+		/// Trust messages from the outbound channel `channel` on the Ethereum side. Messages
+		/// included in Ethereum blocks from `sunset` onwards are rejected. If the channel is
+		/// already trusted, its sunset is updated.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::add_source_channel())]
+		pub fn add_source_channel(
+			origin: OriginFor<T>,
+			channel: H160,
+			sunset: Option<u64>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			<SourceChannels<T>>::insert(channel, SourceChannelInfo { sunset });
+			Self::deposit_event(Event::SourceChannelAdded { channel, sunset });
+			Ok(())
+		}
+
+		/// Stop trusting messages from the outbound channel `channel` on the Ethereum side.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::remove_source_channel())]
+		pub fn remove_source_channel(origin: OriginFor<T>, channel: H160) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(<SourceChannels<T>>::contains_key(channel), Error::<T>::UnknownSourceChannel);
+			<SourceChannels<T>>::remove(channel);
+			Self::deposit_event(Event::SourceChannelRemoved { channel });
+			Ok(())
+		}
+
+		/// Set the reward paid to the relayer for each delivered message.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::set_reward())]
+		pub fn set_reward(origin: OriginFor<T>, reward: BalanceOf<T>) -> DispatchResult {
+			ensure_root(origin)?;
+			<Reward<T>>::put(reward);
+			Self::deposit_event(Event::RewardUpdated { reward });
+			Ok(())
+		}
+
+		/// Submit a batch of messages relayed from Ethereum. Messages are verified together,
+		/// which is cheaper when they are included in the same block, and delivered in order.
+		/// A message which cannot be delivered does not affect the rest of the batch and is
+		/// reported in a `BatchMessageFailed` event. The batch is free if all of its messages
+		/// are delivered.
+		#[pallet::call_index(5)]
+		#[pallet::weight(
+			batch_weight::<T>(&messages)
+				.saturating_add(T::MaxDispatchWeight::get().saturating_mul(messages.len() as u64))
+		)]
+		pub fn submit_batch(
+			origin: OriginFor<T>,
+			messages: Vec<Message>,
+		) -> DispatchResultWithPostInfo {
+			let relayer = ensure_signed(origin)?;
+			ensure!(
+				!messages.is_empty() && messages.len() <= T::MaxMessagesPerBatch::get() as usize,
+				Error::<T>::InvalidBatchSize
+			);
+
+			let mut weight = batch_weight::<T>(&messages);
+			let mut all_delivered = true;
+			let results = T::Verifier::verify_batch(&messages);
+			for (index, result) in results.into_iter().enumerate() {
+				let result = result.and_then(|(log, block_number)| {
+					with_storage_layer(|| Self::deliver(log, block_number))
+				});
+				match result {
+					Ok(dispatch_weight) => {
+						weight = weight.saturating_add(dispatch_weight);
+						Self::pay_reward(&relayer);
+					},
+					Err(error) => {
+						all_delivered = false;
+						Self::deposit_event(Event::BatchMessageFailed {
+							index: index as u32,
+							error,
+						});
+					},
+				}
+			}
+
+			let pays_fee = if all_delivered { Pays::No } else { Pays::Yes };
+			Ok(PostDispatchInfo { actual_weight: Some(weight), pays_fee })
+		}
+
+		/// Enable or disable acknowledgements for the source application `source`. When
+		/// enabled, the dispatch outcome of each message from the application is sent back to
+		/// it on the outbound channel.
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::set_acknowledgement())]
+		pub fn set_acknowledgement(
+			origin: OriginFor<T>,
+			source: H160,
+			enabled: bool,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			<Acknowledgements<T>>::insert(source, enabled);
+			Self::deposit_event(Event::AcknowledgementUpdated { source, enabled });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Check that the message with nonce `nonce` from `account` can be delivered in the given
+		/// mode, and record its delivery.
+		pub(super) fn check_and_record_nonce(
+			account: H160,
+			nonce: u64,
+			mode: DeliveryMode,
+		) -> DispatchResult {
+			let last = <Nonce<T>>::get(account);
+			let mut bitmap = <ReplayBitmap<T>>::get(account);
+
+			let offset = match nonce.checked_sub(last) {
+				Some(offset) if offset > 0 => offset - 1,
+				_ => return Err(Error::<T>::InvalidNonce.into()),
+			};
+			let in_window = match mode {
+				DeliveryMode::Ordered => offset == 0,
+				DeliveryMode::Unordered => offset < REPLAY_WINDOW_SIZE,
+			};
+			ensure!(in_window && bitmap & (1u128 << offset) == 0, Error::<T>::InvalidNonce);
+			bitmap |= 1u128 << offset;
+
+			// Advance past all messages which have now been delivered in order
+			let delivered = bitmap.trailing_ones();
+			let bitmap = bitmap.checked_shr(delivered).unwrap_or(0);
+			let last = last.checked_add(delivered as u64).ok_or(Error::<T>::InvalidNonce)?;
+
+			<Nonce<T>>::insert(account, last);
+			<ReplayBitmap<T>>::insert(account, bitmap);
+			Ok(())
+		}
+
+		/// Deliver a verified message, included in the Ethereum block `block_number`, to its
+		/// destination. Returns the weight used to dispatch the message.
+		fn deliver(log: Log, block_number: u64) -> Result<Weight, DispatchError> {
 			// Decode log into an Envelope
-			let envelope = Envelope::try_from(log).map_err(|_| Error::<T>::InvalidEnvelope)?;
+			let envelope = Envelope::decode(log, T::MaxInboundPayloadSize::get()).map_err(
+				|err| match err {
+					EnvelopeDecodeError::UnsupportedVersion =>
+						Error::<T>::UnsupportedEnvelopeVersion,
+					EnvelopeDecodeError::InvalidEnvelope => Error::<T>::InvalidEnvelope,
+					EnvelopeDecodeError::NonceOutOfRange |
+					EnvelopeDecodeError::DestinationOutOfRange |
+					EnvelopeDecodeError::FeeOutOfRange |
+					EnvelopeDecodeError::GasHintOutOfRange => Error::<T>::EnvelopeValueOutOfRange,
+					EnvelopeDecodeError::PayloadTooLarge => Error::<T>::PayloadTooLarge,
+				},
+			)?;
 
 			// Verify that the message was submitted to us from a known
-			// outbound channel on the ethereum side
-			if envelope.channel != <SourceChannel<T>>::get() {
-				return Err(Error::<T>::InvalidSourceChannel.into())
+			// outbound channel on the ethereum side, which has not been sunset
+			let channel = <SourceChannels<T>>::get(envelope.channel)
+				.ok_or(Error::<T>::InvalidSourceChannel)?;
+			if let Some(sunset) = channel.sunset {
+				ensure!(block_number < sunset, Error::<T>::InvalidSourceChannel);
+			}
+
+			if let Some(destination) = envelope.destination {
+				ensure!(destination == T::ParachainId::get(), Error::<T>::InvalidDestination);
 			}
 
 			// Verify message nonce
-			<Nonce<T>>::try_mutate(envelope.account, |nonce| -> DispatchResult {
-				if envelope.nonce != *nonce + 1 {
-					Err(Error::<T>::InvalidNonce.into())
-				} else {
-					*nonce += 1;
-					Ok(())
-				}
-			})?;
+			let mode = <DeliveryModes<T>>::get(envelope.source);
+			Self::check_and_record_nonce(envelope.account, envelope.nonce, mode)?;
+
+			// Dispatch with no more ref time than the source expects the call to need
+			let max_weight = T::MaxDispatchWeight::get();
+			let weight_limit = match envelope.gas_hint {
+				Some(gas_hint) => Weight::from_parts(
+					gas_hint.saturating_mul(T::WeightPerGas::get()).min(max_weight.ref_time()),
+					max_weight.proof_size(),
+				),
+				None => max_weight,
+			};
 
 			let message_id = MessageId::new(envelope.account, envelope.nonce);
-			T::MessageDispatch::dispatch(envelope.source, message_id, &envelope.payload);
+			let (outcome, dispatch_weight) = T::MessageDispatch::dispatch(
+				envelope.source,
+				message_id,
+				&envelope.payload,
+				weight_limit,
+			);
+			Self::record_receipt(message_id, outcome);
+			if <Acknowledgements<T>>::get(envelope.source) {
+				Self::acknowledge(envelope.source, message_id, outcome);
+			}
 
 			<LatestVerifiedBlockNumber<T>>::set(block_number);
 
-			Ok(())
+			Self::deposit_event(Event::MessageReceived {
+				channel: envelope.channel,
+				account: envelope.account,
+				nonce: envelope.nonce,
+				block_number,
+			});
+
+			Ok(dispatch_weight)
+		}
+
+		/// Send the dispatch outcome of a message back to its source application on Ethereum,
+		/// as the ABI-encoded tuple `(source, account, nonce, success, errorCode)`. The outbound
+		/// fee is paid by the derived account of the source application. The message is still
+		/// delivered if the acknowledgement cannot be submitted.
+		fn acknowledge(source: H160, message_id: MessageId, outcome: DispatchOutcome) {
+			let payload = ethabi::encode(&[
+				Token::Address(source),
+				Token::Address(message_id.account()),
+				Token::Uint(message_id.nonce().into()),
+				Token::Bool(outcome.is_success()),
+				Token::Uint(outcome.error_code().into()),
+			]);
+			let payer = T::AccountIdConverter::convert(source);
+			let result = with_storage_layer(|| T::OutboundChannel::submit(&payer, &payload));
+			if let Err(error) = result {
+				Self::deposit_event(Event::AcknowledgementFailed {
+					account: message_id.account(),
+					nonce: message_id.nonce(),
+					error,
+				});
+			}
+		}
+
+		/// Pay the reward for a delivered message to `relayer`. The message is still delivered
+		/// if the pallet account cannot cover the reward.
+		fn pay_reward(relayer: &T::AccountId) {
+			let amount = <Reward<T>>::get();
+			if amount.is_zero() {
+				return
+			}
+
+			let paid = T::Currency::transfer(
+				&Self::account_id(),
+				relayer,
+				amount,
+				ExistenceRequirement::KeepAlive,
+			);
+			if paid.is_ok() {
+				Self::deposit_event(Event::RelayerRewarded { relayer: relayer.clone(), amount });
+			}
+		}
+
+		pub fn account_id() -> T::AccountId {
+			T::PalletId::get().into_account_truncating()
+		}
+
+		/// Store the dispatch outcome of a delivered message, pruning the oldest receipt if the
+		/// limit has been reached.
+		pub(super) fn record_receipt(message_id: MessageId, outcome: DispatchOutcome) {
+			let max = T::MaxDeliveryReceipts::get();
+			if max == 0 {
+				return
+			}
+
+			let index = <NextDeliveryReceiptIndex<T>>::get();
+			if let Some(pruned) = <DeliveryReceiptIds<T>>::get(index) {
+				<DeliveryReceipts<T>>::remove(pruned);
+			}
+			<DeliveryReceiptIds<T>>::insert(index, message_id);
+			<DeliveryReceipts<T>>::insert(message_id, outcome);
+			<NextDeliveryReceiptIndex<T>>::put((index + 1) % max);
+		}
+
+		/// Whether the message with nonce `nonce` from `account` has been delivered, and the
+		/// outcome of its dispatch if its receipt has not been pruned yet.
+		pub fn delivery_status(account: H160, nonce: u64) -> DeliveryStatus {
+			let last = <Nonce<T>>::get(account);
+			let delivered = match nonce.checked_sub(last) {
+				Some(0) | None => nonce > 0,
+				Some(offset) =>
+					offset <= REPLAY_WINDOW_SIZE &&
+						<ReplayBitmap<T>>::get(account) & (1u128 << (offset - 1)) != 0,
+			};
+			if !delivered {
+				return DeliveryStatus::NotDelivered
+			}
+
+			let outcome = <DeliveryReceipts<T>>::get(MessageId::new(account, nonce));
+			DeliveryStatus::Delivered { outcome }
+		}
+	}
+
+	impl<T: Config> OverweightMessageExecuted<MessageId> for Pallet<T> {
+		fn weight() -> Weight {
+			T::WeightInfo::overweight_message_executed()
+		}
+
+		/// Replace the outcome in the receipt of an overweight message, unless the receipt has
+		/// been pruned, and acknowledge the outcome if acknowledgements are enabled for its
+		/// source application.
+		fn overweight_message_executed(id: &MessageId, source: H160, outcome: DispatchOutcome) {
+			<DeliveryReceipts<T>>::mutate(id, |receipt| {
+				if let Some(receipt) = receipt {
+					*receipt = outcome;
+				}
+			});
+			if <Acknowledgements<T>>::get(source) {
+				Self::acknowledge(source, *id, outcome);
+			}
 		}
 	}
 }
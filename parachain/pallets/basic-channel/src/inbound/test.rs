@@ -2,27 +2,38 @@ use super::*;
 
 use frame_support::{
 	assert_noop, assert_ok,
-	dispatch::DispatchError,
+	dispatch::{DispatchError, Pays},
 	parameter_types,
-	traits::{Everything, GenesisBuild},
+	traits::{
+		Currency, Everything, ExistenceRequirement, GenesisBuild, GetStorageVersion,
+		OnRuntimeUpgrade, StorageVersion, WithdrawReasons,
+	},
+	weights::Weight,
+	PalletId,
 };
 use sp_core::{H160, H256};
 use sp_keyring::AccountKeyring as Keyring;
 use sp_runtime::{
 	testing::Header,
-	traits::{BlakeTwo256, IdentifyAccount, IdentityLookup, Verify},
+	traits::{BlakeTwo256, Convert, IdentifyAccount, IdentityLookup, Verify},
 	MultiSignature,
 };
 use sp_std::convert::From;
 
-use snowbridge_core::{Message, MessageDispatch, Proof};
+use snowbridge_core::{
+	DeliveryStatus, DispatchOutcome, Message, MessageDispatch, OutboundChannel,
+	OverweightMessageExecuted, Proof,
+};
 use snowbridge_ethereum::{Header as EthereumHeader, Log, U256};
 
 use hex_literal::hex;
 
 use crate::{
 	inbound as basic_inbound_channel,
-	inbound::{envelope::Envelope, Error},
+	inbound::{
+		envelope::{Envelope, EnvelopeVersion},
+		migration, Error,
+	},
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -35,12 +46,14 @@ frame_support::construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Pallet, Call, Storage, Event<T>},
-		BasicInboundChannel: basic_inbound_channel::{Pallet, Call, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		BasicInboundChannel: basic_inbound_channel::{Pallet, Call, Config<T>, Storage, Event<T>},
 	}
 );
 
 pub type Signature = MultiSignature;
 pub type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::AccountId;
+pub type Balance = u128;
 
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
@@ -64,7 +77,7 @@ impl frame_system::Config for Test {
 	type DbWeight = ();
 	type Version = ();
 	type PalletInfo = PalletInfo;
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<Balance>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
@@ -72,6 +85,23 @@ impl frame_system::Config for Test {
 	type OnSetCode = ();
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = Balance;
+	type RuntimeEvent = RuntimeEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
 // Mock verifier
 pub struct MockVerifier;
 
@@ -84,13 +114,38 @@ impl Verifier for MockVerifier {
 	fn initialize_storage(_: Vec<EthereumHeader>, _: U256, _: u8) -> Result<(), &'static str> {
 		Ok(())
 	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn initialize_benchmark_messages(logs: Vec<Vec<u8>>, proof_size: u32) -> Vec<Message> {
+		logs.into_iter()
+			.map(|log| Message {
+				data: log,
+				proof: Proof {
+					block_hash: Default::default(),
+					tx_index: Default::default(),
+					data: (vec![], vec![vec![0u8; proof_size as usize]]),
+				},
+			})
+			.collect()
+	}
 }
 
+// Weight used by the mock dispatch to dispatch any message
+const DISPATCH_WEIGHT: Weight = Weight::from_ref_time(1_000);
+
 // Mock Dispatch
 pub struct MockMessageDispatch;
 
 impl MessageDispatch<Test, MessageId> for MockMessageDispatch {
-	fn dispatch(_: H160, _: MessageId, _: &[u8]) {}
+	fn dispatch(
+		_: H160,
+		_: MessageId,
+		_: &[u8],
+		weight_limit: Weight,
+	) -> (DispatchOutcome, Weight) {
+		DispatchWeightLimit::set(weight_limit);
+		(DispatchOutcome::Succeeded, DISPATCH_WEIGHT)
+	}
 
 	#[cfg(feature = "runtime-benchmarks")]
 	fn successful_dispatch_event(
@@ -100,22 +155,87 @@ impl MessageDispatch<Test, MessageId> for MockMessageDispatch {
 	}
 }
 
+parameter_types! {
+	pub const MaxDeliveryReceipts: u32 = 2;
+	pub const MaxMessagesPerBatch: u32 = 4;
+	pub static MaxInboundPayloadSize: u32 = 256;
+	pub const MaxDispatchWeight: Weight = Weight::from_parts(1_000_000, 4_096);
+	pub const WeightPerGas: u64 = 2;
+	pub static DispatchWeightLimit: Weight = Weight::zero();
+	pub const ParachainId: u32 = 1000;
+	pub static SentMessages: Vec<Vec<u8>> = vec![];
+	pub const InboundChannelPalletId: PalletId = PalletId(*b"s/basinb");
+}
+
 impl basic_inbound_channel::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type Verifier = MockVerifier;
 	type MessageDispatch = MockMessageDispatch;
+	type MaxDeliveryReceipts = MaxDeliveryReceipts;
+	type MaxMessagesPerBatch = MaxMessagesPerBatch;
+	type MaxInboundPayloadSize = MaxInboundPayloadSize;
+	type MaxDispatchWeight = MaxDispatchWeight;
+	type WeightPerGas = WeightPerGas;
+	type ParachainId = ParachainId;
+	type OutboundChannel = MockOutboundChannel;
+	type AccountIdConverter = MockAccountIdConverter;
+	type Currency = Balances;
+	type PalletId = InboundChannelPalletId;
 	type WeightInfo = ();
 }
 
+const OUTBOUND_FEE: Balance = 5;
+
+// Mock outbound channel, which charges a fixed fee and records the payloads of submitted
+// messages
+pub struct MockOutboundChannel;
+
+impl OutboundChannel<AccountId> for MockOutboundChannel {
+	fn submit(who: &AccountId, payload: &[u8]) -> Result<u64, DispatchError> {
+		let _ = Balances::withdraw(
+			who,
+			OUTBOUND_FEE,
+			WithdrawReasons::FEE,
+			ExistenceRequirement::KeepAlive,
+		)?;
+		let mut messages = SentMessages::get();
+		let nonce = messages.len() as u64;
+		messages.push(payload.to_vec());
+		SentMessages::set(messages);
+		Ok(nonce)
+	}
+}
+
+pub struct MockAccountIdConverter;
+
+impl Convert<H160, AccountId> for MockAccountIdConverter {
+	fn convert(address: H160) -> AccountId {
+		let mut account = [0u8; 32];
+		account[12..].copy_from_slice(address.as_bytes());
+		account.into()
+	}
+}
+
+const REWARD: Balance = 10;
+
 pub fn new_tester(source_channel: H160) -> sp_io::TestExternalities {
-	new_tester_with_config(basic_inbound_channel::GenesisConfig { source_channel })
+	new_tester_with_config(basic_inbound_channel::GenesisConfig {
+		source_channels: vec![source_channel],
+		reward: REWARD,
+	})
 }
 
 pub fn new_tester_with_config(
-	config: basic_inbound_channel::GenesisConfig,
+	config: basic_inbound_channel::GenesisConfig<Test>,
 ) -> sp_io::TestExternalities {
 	let mut storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
 
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(BasicInboundChannel::account_id(), 1_000)],
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+
 	GenesisBuild::<Test>::assimilate_storage(&config, &mut storage).unwrap();
 
 	let mut ext: sp_io::TestExternalities = storage.into();
@@ -130,7 +250,7 @@ fn parse_origin(message: Message) -> H160 {
 			err
 		})
 		.unwrap();
-	let envelope = Envelope::try_from(log)
+	let envelope = Envelope::decode(log, MaxInboundPayloadSize::get())
 		.map_err(|err| {
 			println!("envelope: {:?}", err);
 			err
@@ -270,3 +390,860 @@ fn test_submit_with_invalid_nonce() {
 		);
 	});
 }
+
+// The source application and account of the messages above
+const SOURCE_APP_ADDR: [u8; 20] = hex!["89b4ab1ef20763630df9743acf155865600daff2"];
+const ACCOUNT_ADDR: [u8; 20] = hex!["04e00e6d2e9ea1e2af553de02a5172120bfa5c3e"];
+
+#[test]
+fn test_set_delivery_mode() {
+	new_tester(SOURCE_CHANNEL_ADDR.into()).execute_with(|| {
+		let source: H160 = SOURCE_APP_ADDR.into();
+
+		assert_eq!(<DeliveryModes<Test>>::get(source), DeliveryMode::Ordered);
+		assert_ok!(BasicInboundChannel::set_delivery_mode(
+			RuntimeOrigin::root(),
+			source,
+			DeliveryMode::Unordered
+		));
+		assert_eq!(<DeliveryModes<Test>>::get(source), DeliveryMode::Unordered);
+		System::assert_last_event(RuntimeEvent::BasicInboundChannel(
+			crate::inbound::Event::DeliveryModeUpdated { source, mode: DeliveryMode::Unordered },
+		));
+
+		assert_noop!(
+			BasicInboundChannel::set_delivery_mode(
+				RuntimeOrigin::signed(Keyring::Bob.into()),
+				source,
+				DeliveryMode::Ordered
+			),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn test_submit_unordered() {
+	new_tester(SOURCE_CHANNEL_ADDR.into()).execute_with(|| {
+		let relayer: AccountId = Keyring::Bob.into();
+		let origin = RuntimeOrigin::signed(relayer);
+		let account: H160 = ACCOUNT_ADDR.into();
+
+		assert_ok!(BasicInboundChannel::set_delivery_mode(
+			RuntimeOrigin::root(),
+			SOURCE_APP_ADDR.into(),
+			DeliveryMode::Unordered
+		));
+
+		let message_1 = Message {
+			data: MESSAGE_DATA_0.into(),
+			proof: Proof {
+				block_hash: Default::default(),
+				tx_index: Default::default(),
+				data: Default::default(),
+			},
+		};
+		let message_2 = Message {
+			data: MESSAGE_DATA_1.into(),
+			proof: Proof {
+				block_hash: Default::default(),
+				tx_index: Default::default(),
+				data: Default::default(),
+			},
+		};
+
+		// Message 2 is delivered ahead of message 1
+		assert_ok!(BasicInboundChannel::submit(origin.clone(), message_2.clone()));
+		assert_eq!(<Nonce<Test>>::get(account), 0);
+		assert_eq!(<ReplayBitmap<Test>>::get(account), 0b10);
+
+		assert_noop!(
+			BasicInboundChannel::submit(origin.clone(), message_2),
+			Error::<Test>::InvalidNonce
+		);
+
+		assert_ok!(BasicInboundChannel::submit(origin.clone(), message_1.clone()));
+		assert_eq!(<Nonce<Test>>::get(account), 2);
+		assert_eq!(<ReplayBitmap<Test>>::get(account), 0);
+
+		assert_noop!(BasicInboundChannel::submit(origin, message_1), Error::<Test>::InvalidNonce);
+	});
+}
+
+#[test]
+fn test_submit_ordered_rejects_gap() {
+	new_tester(SOURCE_CHANNEL_ADDR.into()).execute_with(|| {
+		let relayer: AccountId = Keyring::Bob.into();
+		let origin = RuntimeOrigin::signed(relayer);
+
+		let message_2 = Message {
+			data: MESSAGE_DATA_1.into(),
+			proof: Proof {
+				block_hash: Default::default(),
+				tx_index: Default::default(),
+				data: Default::default(),
+			},
+		};
+		assert_noop!(BasicInboundChannel::submit(origin, message_2), Error::<Test>::InvalidNonce);
+	});
+}
+
+#[test]
+fn test_replay_window() {
+	new_tester(SOURCE_CHANNEL_ADDR.into()).execute_with(|| {
+		let account: H160 = ACCOUNT_ADDR.into();
+		let check = |nonce| {
+			BasicInboundChannel::check_and_record_nonce(account, nonce, DeliveryMode::Unordered)
+		};
+
+		assert_noop!(check(0), Error::<Test>::InvalidNonce);
+		assert_noop!(check(REPLAY_WINDOW_SIZE + 1), Error::<Test>::InvalidNonce);
+		assert_ok!(check(REPLAY_WINDOW_SIZE));
+		assert_ok!(check(3));
+		assert_noop!(check(3), Error::<Test>::InvalidNonce);
+
+		assert_ok!(check(1));
+		assert_eq!(<Nonce<Test>>::get(account), 1);
+
+		// Ordered delivery continues from the last in-order nonce
+		assert_noop!(
+			BasicInboundChannel::check_and_record_nonce(account, 4, DeliveryMode::Ordered),
+			Error::<Test>::InvalidNonce
+		);
+		assert_ok!(BasicInboundChannel::check_and_record_nonce(account, 2, DeliveryMode::Ordered));
+		assert_eq!(<Nonce<Test>>::get(account), 3);
+
+		// The window has moved along
+		assert_ok!(check(REPLAY_WINDOW_SIZE + 3));
+	});
+}
+
+#[test]
+fn test_submit_records_delivery() {
+	new_tester(SOURCE_CHANNEL_ADDR.into()).execute_with(|| {
+		let relayer: AccountId = Keyring::Bob.into();
+		let origin = RuntimeOrigin::signed(relayer);
+		let account: H160 = ACCOUNT_ADDR.into();
+
+		assert_eq!(BasicInboundChannel::delivery_status(account, 1), DeliveryStatus::NotDelivered);
+
+		let message = Message {
+			data: MESSAGE_DATA_0.into(),
+			proof: Proof {
+				block_hash: Default::default(),
+				tx_index: Default::default(),
+				data: Default::default(),
+			},
+		};
+		assert_ok!(BasicInboundChannel::submit(origin, message));
+
+		System::assert_has_event(RuntimeEvent::BasicInboundChannel(
+			crate::inbound::Event::MessageReceived {
+				channel: SOURCE_CHANNEL_ADDR.into(),
+				account,
+				nonce: 1,
+				block_number: 0,
+			},
+		));
+		assert_eq!(
+			BasicInboundChannel::delivery_status(account, 1),
+			DeliveryStatus::Delivered { outcome: Some(DispatchOutcome::Succeeded) }
+		);
+		assert_eq!(BasicInboundChannel::delivery_status(account, 2), DeliveryStatus::NotDelivered);
+	});
+}
+
+#[test]
+fn test_delivery_receipts_are_pruned() {
+	new_tester(SOURCE_CHANNEL_ADDR.into()).execute_with(|| {
+		let account: H160 = ACCOUNT_ADDR.into();
+
+		for nonce in 1..=3 {
+			assert_ok!(BasicInboundChannel::check_and_record_nonce(
+				account,
+				nonce,
+				DeliveryMode::Ordered
+			));
+			BasicInboundChannel::record_receipt(
+				MessageId::new(account, nonce),
+				DispatchOutcome::Failed,
+			);
+		}
+
+		// Only the receipts of the two most recent messages are kept
+		assert_eq!(
+			BasicInboundChannel::delivery_status(account, 1),
+			DeliveryStatus::Delivered { outcome: None }
+		);
+		assert_eq!(
+			BasicInboundChannel::delivery_status(account, 2),
+			DeliveryStatus::Delivered { outcome: Some(DispatchOutcome::Failed) }
+		);
+		assert_eq!(
+			BasicInboundChannel::delivery_status(account, 3),
+			DeliveryStatus::Delivered { outcome: Some(DispatchOutcome::Failed) }
+		);
+		assert_eq!(<DeliveryReceipts<Test>>::iter().count(), 2);
+	});
+}
+
+#[test]
+fn test_delivery_receipt_updated_after_overweight_message_executed() {
+	new_tester(SOURCE_CHANNEL_ADDR.into()).execute_with(|| {
+		let account: H160 = ACCOUNT_ADDR.into();
+
+		assert_ok!(BasicInboundChannel::check_and_record_nonce(account, 1, DeliveryMode::Ordered));
+		BasicInboundChannel::record_receipt(
+			MessageId::new(account, 1),
+			DispatchOutcome::Overweight,
+		);
+
+		BasicInboundChannel::overweight_message_executed(
+			&MessageId::new(account, 1),
+			SOURCE_APP_ADDR.into(),
+			DispatchOutcome::Succeeded,
+		);
+		assert_eq!(
+			BasicInboundChannel::delivery_status(account, 1),
+			DeliveryStatus::Delivered { outcome: Some(DispatchOutcome::Succeeded) }
+		);
+
+		// No receipt is created for messages whose receipt has been pruned
+		BasicInboundChannel::overweight_message_executed(
+			&MessageId::new(account, 2),
+			SOURCE_APP_ADDR.into(),
+			DispatchOutcome::Succeeded,
+		);
+		assert!(!<DeliveryReceipts<Test>>::contains_key(MessageId::new(account, 2)));
+
+		// Acknowledgements are disabled by default
+		assert!(SentMessages::get().is_empty());
+	});
+}
+
+#[test]
+fn test_overweight_message_executed_is_acknowledged() {
+	new_tester(SOURCE_CHANNEL_ADDR.into()).execute_with(|| {
+		let account: H160 = ACCOUNT_ADDR.into();
+		let source: H160 = SOURCE_APP_ADDR.into();
+
+		assert_ok!(BasicInboundChannel::set_acknowledgement(RuntimeOrigin::root(), source, true));
+		let payer = MockAccountIdConverter::convert(source);
+		Balances::make_free_balance_be(&payer, 100);
+
+		BasicInboundChannel::overweight_message_executed(
+			&MessageId::new(account, 3),
+			source,
+			DispatchOutcome::Failed,
+		);
+		assert_eq!(Balances::free_balance(&payer), 100 - OUTBOUND_FEE);
+		assert_eq!(
+			SentMessages::get(),
+			vec![ethabi::encode(&[
+				ethabi::Token::Address(source),
+				ethabi::Token::Address(account),
+				ethabi::Token::Uint(3.into()),
+				ethabi::Token::Bool(false),
+				ethabi::Token::Uint(DispatchOutcome::Failed.error_code().into()),
+			])]
+		);
+
+		// The outcome is not acknowledged if the derived account of the source application
+		// cannot pay the outbound fee
+		Balances::make_free_balance_be(&payer, 0);
+		BasicInboundChannel::overweight_message_executed(
+			&MessageId::new(account, 4),
+			source,
+			DispatchOutcome::Succeeded,
+		);
+		assert_eq!(SentMessages::get().len(), 1);
+		assert!(System::events().iter().any(|record| matches!(
+			record.event,
+			RuntimeEvent::BasicInboundChannel(crate::inbound::Event::AcknowledgementFailed {
+				nonce: 4,
+				..
+			})
+		)));
+	});
+}
+
+#[test]
+fn test_delivery_status_unordered() {
+	new_tester(SOURCE_CHANNEL_ADDR.into()).execute_with(|| {
+		let account: H160 = ACCOUNT_ADDR.into();
+
+		assert_ok!(BasicInboundChannel::check_and_record_nonce(
+			account,
+			3,
+			DeliveryMode::Unordered
+		));
+
+		assert_eq!(BasicInboundChannel::delivery_status(account, 0), DeliveryStatus::NotDelivered);
+		assert_eq!(BasicInboundChannel::delivery_status(account, 1), DeliveryStatus::NotDelivered);
+		assert_eq!(
+			BasicInboundChannel::delivery_status(account, 3),
+			DeliveryStatus::Delivered { outcome: None }
+		);
+		assert_eq!(
+			BasicInboundChannel::delivery_status(account, REPLAY_WINDOW_SIZE + 5),
+			DeliveryStatus::NotDelivered
+		);
+	});
+}
+
+#[test]
+fn test_add_and_remove_source_channel() {
+	new_tester(SOURCE_CHANNEL_ADDR.into()).execute_with(|| {
+		let channel = H160::repeat_byte(7);
+
+		assert_ok!(BasicInboundChannel::add_source_channel(
+			RuntimeOrigin::root(),
+			channel,
+			Some(100)
+		));
+		assert_eq!(
+			<SourceChannels<Test>>::get(channel),
+			Some(SourceChannelInfo { sunset: Some(100) })
+		);
+		System::assert_last_event(RuntimeEvent::BasicInboundChannel(
+			crate::inbound::Event::SourceChannelAdded { channel, sunset: Some(100) },
+		));
+
+		assert_ok!(BasicInboundChannel::remove_source_channel(RuntimeOrigin::root(), channel));
+		assert_eq!(<SourceChannels<Test>>::get(channel), None);
+		System::assert_last_event(RuntimeEvent::BasicInboundChannel(
+			crate::inbound::Event::SourceChannelRemoved { channel },
+		));
+
+		assert_noop!(
+			BasicInboundChannel::remove_source_channel(RuntimeOrigin::root(), channel),
+			Error::<Test>::UnknownSourceChannel
+		);
+		assert_noop!(
+			BasicInboundChannel::add_source_channel(
+				RuntimeOrigin::signed(Keyring::Bob.into()),
+				channel,
+				None
+			),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			BasicInboundChannel::remove_source_channel(
+				RuntimeOrigin::signed(Keyring::Bob.into()),
+				SOURCE_CHANNEL_ADDR.into()
+			),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn test_submit_with_sunset_source_channel() {
+	new_tester(H160::zero()).execute_with(|| {
+		let relayer: AccountId = Keyring::Bob.into();
+		let origin = RuntimeOrigin::signed(relayer);
+
+		let message_1 = Message {
+			data: MESSAGE_DATA_0.into(),
+			proof: Proof {
+				block_hash: Default::default(),
+				tx_index: Default::default(),
+				data: Default::default(),
+			},
+		};
+		let message_2 = Message {
+			data: MESSAGE_DATA_1.into(),
+			proof: Proof {
+				block_hash: Default::default(),
+				tx_index: Default::default(),
+				data: Default::default(),
+			},
+		};
+
+		// The mock verifier reports messages as included in Ethereum block 0
+		assert_ok!(BasicInboundChannel::add_source_channel(
+			RuntimeOrigin::root(),
+			SOURCE_CHANNEL_ADDR.into(),
+			Some(1)
+		));
+		assert_ok!(BasicInboundChannel::submit(origin.clone(), message_1));
+
+		assert_ok!(BasicInboundChannel::add_source_channel(
+			RuntimeOrigin::root(),
+			SOURCE_CHANNEL_ADDR.into(),
+			Some(0)
+		));
+		assert_noop!(
+			BasicInboundChannel::submit(origin.clone(), message_2.clone()),
+			Error::<Test>::InvalidSourceChannel
+		);
+
+		assert_ok!(BasicInboundChannel::remove_source_channel(
+			RuntimeOrigin::root(),
+			SOURCE_CHANNEL_ADDR.into()
+		));
+		assert_noop!(
+			BasicInboundChannel::submit(origin, message_2),
+			Error::<Test>::InvalidSourceChannel
+		);
+	});
+}
+
+#[test]
+fn test_migrate_source_channel_to_v1() {
+	new_tester_with_config(basic_inbound_channel::GenesisConfig {
+		source_channels: vec![],
+		reward: REWARD,
+	})
+	.execute_with(|| {
+		let channel: H160 = SOURCE_CHANNEL_ADDR.into();
+		StorageVersion::new(0).put::<BasicInboundChannel>();
+		migration::v1::SourceChannel::<Test>::put(channel);
+
+		migration::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+
+		assert_eq!(<SourceChannels<Test>>::get(channel), Some(SourceChannelInfo { sunset: None }));
+		assert_eq!(migration::v1::SourceChannel::<Test>::get(), None);
+		assert_eq!(BasicInboundChannel::on_chain_storage_version(), 1);
+
+		let message = Message {
+			data: MESSAGE_DATA_0.into(),
+			proof: Proof {
+				block_hash: Default::default(),
+				tx_index: Default::default(),
+				data: Default::default(),
+			},
+		};
+		assert_ok!(BasicInboundChannel::submit(
+			RuntimeOrigin::signed(Keyring::Bob.into()),
+			message
+		));
+
+		// The migration only runs once
+		<SourceChannels<Test>>::remove(channel);
+		migration::v1::SourceChannel::<Test>::put(channel);
+		migration::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+		assert_eq!(<SourceChannels<Test>>::get(channel), None);
+	});
+}
+
+#[test]
+fn test_submit_rewards_relayer() {
+	new_tester(SOURCE_CHANNEL_ADDR.into()).execute_with(|| {
+		let relayer: AccountId = Keyring::Bob.into();
+		let origin = RuntimeOrigin::signed(relayer.clone());
+
+		let message = Message {
+			data: MESSAGE_DATA_0.into(),
+			proof: Proof {
+				block_hash: Default::default(),
+				tx_index: Default::default(),
+				data: Default::default(),
+			},
+		};
+		let post_info = BasicInboundChannel::submit(origin.clone(), message.clone()).unwrap();
+		assert_eq!(post_info.pays_fee, Pays::No);
+
+		assert_eq!(Balances::free_balance(&relayer), REWARD);
+		assert_eq!(Balances::free_balance(&BasicInboundChannel::account_id()), 1_000 - REWARD);
+		System::assert_last_event(RuntimeEvent::BasicInboundChannel(
+			crate::inbound::Event::RelayerRewarded { relayer, amount: REWARD },
+		));
+
+		// Duplicate messages are charged for and not rewarded
+		let err = BasicInboundChannel::submit(origin, message).unwrap_err();
+		assert_eq!(err.post_info.pays_fee, Pays::Yes);
+		assert_eq!(err.error, Error::<Test>::InvalidNonce.into());
+	});
+}
+
+#[test]
+fn test_submit_without_reward_funds() {
+	new_tester(SOURCE_CHANNEL_ADDR.into()).execute_with(|| {
+		let relayer: AccountId = Keyring::Bob.into();
+		let origin = RuntimeOrigin::signed(relayer.clone());
+		let account: H160 = ACCOUNT_ADDR.into();
+
+		assert_ok!(BasicInboundChannel::set_reward(RuntimeOrigin::root(), 1_000));
+
+		let message = Message {
+			data: MESSAGE_DATA_0.into(),
+			proof: Proof {
+				block_hash: Default::default(),
+				tx_index: Default::default(),
+				data: Default::default(),
+			},
+		};
+		assert_ok!(BasicInboundChannel::submit(origin, message));
+
+		// The message is delivered even though the reward could not be paid
+		assert_eq!(<Nonce<Test>>::get(account), 1);
+		assert_eq!(Balances::free_balance(&relayer), 0);
+	});
+}
+
+#[test]
+fn test_set_reward() {
+	new_tester(SOURCE_CHANNEL_ADDR.into()).execute_with(|| {
+		assert_eq!(BasicInboundChannel::reward(), REWARD);
+		assert_ok!(BasicInboundChannel::set_reward(RuntimeOrigin::root(), 20));
+		assert_eq!(BasicInboundChannel::reward(), 20);
+		System::assert_last_event(RuntimeEvent::BasicInboundChannel(
+			crate::inbound::Event::RewardUpdated { reward: 20 },
+		));
+
+		assert_noop!(
+			BasicInboundChannel::set_reward(RuntimeOrigin::signed(Keyring::Bob.into()), 5),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn test_submit_batch() {
+	new_tester(SOURCE_CHANNEL_ADDR.into()).execute_with(|| {
+		let relayer: AccountId = Keyring::Bob.into();
+		let origin = RuntimeOrigin::signed(relayer.clone());
+		let account: H160 = ACCOUNT_ADDR.into();
+
+		let message_1 = Message {
+			data: MESSAGE_DATA_0.into(),
+			proof: Proof {
+				block_hash: Default::default(),
+				tx_index: Default::default(),
+				data: Default::default(),
+			},
+		};
+		let message_2 = Message {
+			data: MESSAGE_DATA_1.into(),
+			proof: Proof {
+				block_hash: Default::default(),
+				tx_index: Default::default(),
+				data: Default::default(),
+			},
+		};
+
+		let weight = <() as WeightInfo>::submit_batch(2, MESSAGE_DATA_0.len() as u32, 0);
+		let post_info =
+			BasicInboundChannel::submit_batch(origin, vec![message_1, message_2]).unwrap();
+		assert_eq!(post_info.pays_fee, Pays::No);
+		assert_eq!(post_info.actual_weight, Some(weight + DISPATCH_WEIGHT.saturating_mul(2)));
+
+		assert_eq!(<Nonce<Test>>::get(account), 2);
+		assert_eq!(Balances::free_balance(&relayer), 2 * REWARD);
+		assert_eq!(
+			BasicInboundChannel::delivery_status(account, 2),
+			DeliveryStatus::Delivered { outcome: Some(DispatchOutcome::Succeeded) }
+		);
+	});
+}
+
+#[test]
+fn test_submit_batch_with_failed_message() {
+	new_tester(SOURCE_CHANNEL_ADDR.into()).execute_with(|| {
+		let relayer: AccountId = Keyring::Bob.into();
+		let origin = RuntimeOrigin::signed(relayer.clone());
+		let account: H160 = ACCOUNT_ADDR.into();
+
+		let message_1 = Message {
+			data: MESSAGE_DATA_0.into(),
+			proof: Proof {
+				block_hash: Default::default(),
+				tx_index: Default::default(),
+				data: Default::default(),
+			},
+		};
+		let message_2 = Message {
+			data: MESSAGE_DATA_1.into(),
+			proof: Proof {
+				block_hash: Default::default(),
+				tx_index: Default::default(),
+				data: Default::default(),
+			},
+		};
+
+		// The duplicate of message 1 fails without reverting the rest of the batch
+		let post_info = BasicInboundChannel::submit_batch(
+			origin,
+			vec![message_1.clone(), message_1, message_2],
+		)
+		.unwrap();
+		assert_eq!(post_info.pays_fee, Pays::Yes);
+
+		assert_eq!(<Nonce<Test>>::get(account), 2);
+		assert_eq!(Balances::free_balance(&relayer), 2 * REWARD);
+		System::assert_has_event(RuntimeEvent::BasicInboundChannel(
+			crate::inbound::Event::BatchMessageFailed {
+				index: 1,
+				error: Error::<Test>::InvalidNonce.into(),
+			},
+		));
+	});
+}
+
+#[test]
+fn test_submit_batch_with_invalid_size() {
+	new_tester(SOURCE_CHANNEL_ADDR.into()).execute_with(|| {
+		let relayer: AccountId = Keyring::Bob.into();
+		let origin = RuntimeOrigin::signed(relayer);
+
+		let message = Message {
+			data: MESSAGE_DATA_0.into(),
+			proof: Proof {
+				block_hash: Default::default(),
+				tx_index: Default::default(),
+				data: Default::default(),
+			},
+		};
+
+		assert_noop!(
+			BasicInboundChannel::submit_batch(origin.clone(), vec![]),
+			Error::<Test>::InvalidBatchSize
+		);
+		assert_noop!(
+			BasicInboundChannel::submit_batch(origin, vec![message; 5]),
+			Error::<Test>::InvalidBatchSize
+		);
+	});
+}
+
+#[test]
+fn test_submit_with_unsupported_envelope_version() {
+	new_tester(SOURCE_CHANNEL_ADDR.into()).execute_with(|| {
+		let relayer: AccountId = Keyring::Bob.into();
+		let origin = RuntimeOrigin::signed(relayer);
+
+		// Corrupt the event signature in topic0 of the log
+		let mut data = MESSAGE_DATA_0;
+		data[25] ^= 0xff;
+
+		let message = Message {
+			data: data.into(),
+			proof: Proof {
+				block_hash: Default::default(),
+				tx_index: Default::default(),
+				data: Default::default(),
+			},
+		};
+		assert_noop!(
+			BasicInboundChannel::submit(origin, message),
+			Error::<Test>::UnsupportedEnvelopeVersion
+		);
+	});
+}
+
+// Build a message from the source channel carrying a version 2 envelope.
+fn make_v2_message(nonce: u64, destination: u32, gas_hint: u64) -> Message {
+	let data = ethabi::encode(&[
+		Token::Address(H160::repeat_byte(2)),
+		Token::Address(H160::repeat_byte(3)),
+		Token::Uint(U256::from(nonce)),
+		Token::Uint(U256::from(destination)),
+		Token::Uint(U256::from(5_000_000_000u64)),
+		Token::Uint(U256::from(gas_hint)),
+		Token::Bytes(vec![1, 2, 3]),
+	]);
+
+	let mut log = rlp::RlpStream::new_list(3);
+	log.append(&SOURCE_CHANNEL_ADDR.to_vec());
+	log.begin_list(1);
+	log.append(&EnvelopeVersion::V2.topic().as_bytes().to_vec());
+	log.append(&data);
+
+	Message {
+		data: log.out().to_vec(),
+		proof: Proof {
+			block_hash: Default::default(),
+			tx_index: Default::default(),
+			data: Default::default(),
+		},
+	}
+}
+
+#[test]
+fn test_submit_v2_envelope() {
+	new_tester(SOURCE_CHANNEL_ADDR.into()).execute_with(|| {
+		let relayer: AccountId = Keyring::Bob.into();
+		let origin = RuntimeOrigin::signed(relayer);
+
+		assert_noop!(
+			BasicInboundChannel::submit(origin.clone(), make_v2_message(1, 2000, 500)),
+			Error::<Test>::InvalidDestination
+		);
+
+		// The gas hint limits the weight available to dispatch the message
+		assert_ok!(BasicInboundChannel::submit(
+			origin.clone(),
+			make_v2_message(1, ParachainId::get(), 500)
+		));
+		assert_eq!(
+			DispatchWeightLimit::get(),
+			Weight::from_parts(500 * WeightPerGas::get(), MaxDispatchWeight::get().proof_size())
+		);
+
+		// But never beyond the maximum dispatch weight
+		assert_ok!(BasicInboundChannel::submit(
+			origin,
+			make_v2_message(2, ParachainId::get(), u64::MAX)
+		));
+		assert_eq!(DispatchWeightLimit::get(), MaxDispatchWeight::get());
+		assert_eq!(<Nonce<Test>>::get(H160::repeat_byte(3)), 2);
+	});
+}
+
+#[test]
+fn test_submit_with_payload_too_large() {
+	new_tester(SOURCE_CHANNEL_ADDR.into()).execute_with(|| {
+		let relayer: AccountId = Keyring::Bob.into();
+		let origin = RuntimeOrigin::signed(relayer);
+
+		// The payload of the message is 32 bytes
+		MaxInboundPayloadSize::set(31);
+
+		let message = Message {
+			data: MESSAGE_DATA_0.into(),
+			proof: Proof {
+				block_hash: Default::default(),
+				tx_index: Default::default(),
+				data: Default::default(),
+			},
+		};
+		assert_noop!(
+			BasicInboundChannel::submit(origin.clone(), message.clone()),
+			Error::<Test>::PayloadTooLarge
+		);
+
+		MaxInboundPayloadSize::set(32);
+		assert_ok!(BasicInboundChannel::submit(origin, message));
+	});
+}
+
+#[test]
+fn test_submit_refunds_unused_dispatch_weight() {
+	new_tester(SOURCE_CHANNEL_ADDR.into()).execute_with(|| {
+		let relayer: AccountId = Keyring::Bob.into();
+		let origin = RuntimeOrigin::signed(relayer);
+
+		let message = Message {
+			data: MESSAGE_DATA_0.into(),
+			proof: Proof {
+				block_hash: Default::default(),
+				tx_index: Default::default(),
+				data: Default::default(),
+			},
+		};
+		let weight = <() as WeightInfo>::submit(MESSAGE_DATA_0.len() as u32, 0);
+
+		let post_info = BasicInboundChannel::submit(origin.clone(), message.clone()).unwrap();
+		assert_eq!(post_info.actual_weight, Some(weight + DISPATCH_WEIGHT));
+
+		// No weight is used for dispatch if the message is not delivered
+		let err = BasicInboundChannel::submit(origin, message).unwrap_err();
+		assert_eq!(err.post_info.actual_weight, Some(weight));
+	});
+}
+
+#[test]
+fn test_submit_with_acknowledgement() {
+	new_tester(SOURCE_CHANNEL_ADDR.into()).execute_with(|| {
+		let relayer: AccountId = Keyring::Bob.into();
+		let origin = RuntimeOrigin::signed(relayer);
+		let source: H160 = SOURCE_APP_ADDR.into();
+
+		let message_1 = Message {
+			data: MESSAGE_DATA_0.into(),
+			proof: Proof {
+				block_hash: Default::default(),
+				tx_index: Default::default(),
+				data: Default::default(),
+			},
+		};
+		let message_2 = Message {
+			data: MESSAGE_DATA_1.into(),
+			proof: Proof {
+				block_hash: Default::default(),
+				tx_index: Default::default(),
+				data: Default::default(),
+			},
+		};
+
+		// Acknowledgements are disabled by default
+		assert_ok!(BasicInboundChannel::submit(origin.clone(), message_1));
+		assert!(SentMessages::get().is_empty());
+
+		assert_ok!(BasicInboundChannel::set_acknowledgement(RuntimeOrigin::root(), source, true));
+		System::assert_last_event(RuntimeEvent::BasicInboundChannel(
+			crate::inbound::Event::AcknowledgementUpdated { source, enabled: true },
+		));
+
+		// The outbound fee is paid by the derived account of the source application
+		let payer = MockAccountIdConverter::convert(source);
+		Balances::make_free_balance_be(&payer, 100);
+		let pallet_balance = Balances::free_balance(BasicInboundChannel::account_id());
+
+		assert_ok!(BasicInboundChannel::submit(origin, message_2));
+		assert_eq!(Balances::free_balance(&payer), 100 - OUTBOUND_FEE);
+		assert_eq!(
+			Balances::free_balance(BasicInboundChannel::account_id()),
+			pallet_balance - REWARD
+		);
+		assert_eq!(
+			SentMessages::get(),
+			vec![ethabi::encode(&[
+				ethabi::Token::Address(source),
+				ethabi::Token::Address(ACCOUNT_ADDR.into()),
+				ethabi::Token::Uint(2.into()),
+				ethabi::Token::Bool(true),
+				ethabi::Token::Uint(0.into()),
+			])]
+		);
+
+		assert_noop!(
+			BasicInboundChannel::set_acknowledgement(
+				RuntimeOrigin::signed(Keyring::Bob.into()),
+				source,
+				false
+			),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn test_submit_with_unpaid_acknowledgement() {
+	new_tester(SOURCE_CHANNEL_ADDR.into()).execute_with(|| {
+		let relayer: AccountId = Keyring::Bob.into();
+		let origin = RuntimeOrigin::signed(relayer.clone());
+		let source: H160 = SOURCE_APP_ADDR.into();
+
+		let message = Message {
+			data: MESSAGE_DATA_0.into(),
+			proof: Proof {
+				block_hash: Default::default(),
+				tx_index: Default::default(),
+				data: Default::default(),
+			},
+		};
+
+		// The derived account of the source application cannot pay the outbound fee
+		assert_ok!(BasicInboundChannel::set_acknowledgement(RuntimeOrigin::root(), source, true));
+		let pallet_balance = Balances::free_balance(BasicInboundChannel::account_id());
+
+		// The message is still delivered, and the relayer rewarded
+		assert_ok!(BasicInboundChannel::submit(origin, message));
+		assert_eq!(<Nonce<Test>>::get(H160::from(ACCOUNT_ADDR)), 1);
+		assert_eq!(Balances::free_balance(&relayer), REWARD);
+		assert_eq!(
+			Balances::free_balance(BasicInboundChannel::account_id()),
+			pallet_balance - REWARD
+		);
+		assert!(SentMessages::get().is_empty());
+		assert!(System::events().iter().any(|record| matches!(
+			record.event,
+			RuntimeEvent::BasicInboundChannel(crate::inbound::Event::AcknowledgementFailed {
+				nonce: 1,
+				..
+			})
+		)));
+	});
+}
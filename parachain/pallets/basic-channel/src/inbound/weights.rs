@@ -1,3 +1,117 @@
-pub trait WeightInfo {}
+//! Weights for basic_channel::inbound
+//!
+//! THESE WEIGHTS ARE PLACEHOLDERS: they were estimated by hand and have not been generated with
+//! the Substrate benchmark CLI. Regenerate this file with `scripts/benchmark.sh` on the
+//! reference hardware before relying on them.
 
-impl WeightInfo for () {}
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for basic_channel::inbound.
+pub trait WeightInfo {
+	fn submit(p: u32, q: u32, ) -> Weight;
+	fn submit_batch(n: u32, p: u32, q: u32, ) -> Weight;
+	fn set_delivery_mode() -> Weight;
+	fn add_source_channel() -> Weight;
+	fn remove_source_channel() -> Weight;
+	fn set_reward() -> Weight;
+	fn set_acknowledgement() -> Weight;
+	fn overweight_message_executed() -> Weight;
+}
+
+/// Weights for basic_channel::inbound using the Snowbridge node and recommended hardware.
+pub struct SnowbridgeWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SnowbridgeWeight<T> {
+	fn submit(p: u32, q: u32, ) -> Weight {
+		Weight::from_ref_time(91_207_000 as u64)
+			.saturating_add(Weight::from_ref_time(5_000 as u64).saturating_mul(p as u64))
+			.saturating_add(Weight::from_ref_time(9_000 as u64).saturating_mul(q as u64))
+			.saturating_add(T::DbWeight::get().reads(18 as u64))
+			.saturating_add(T::DbWeight::get().writes(14 as u64))
+	}
+	fn submit_batch(n: u32, p: u32, q: u32, ) -> Weight {
+		Weight::from_ref_time(21_530_000 as u64)
+			.saturating_add(Weight::from_ref_time(66_518_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(5_000 as u64).saturating_mul(n as u64).saturating_mul(p as u64))
+			.saturating_add(Weight::from_ref_time(9_000 as u64).saturating_mul(n as u64).saturating_mul(q as u64))
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().reads((16 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().writes((14 as u64).saturating_mul(n as u64)))
+	}
+	fn set_delivery_mode() -> Weight {
+		Weight::from_ref_time(9_243_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	fn add_source_channel() -> Weight {
+		Weight::from_ref_time(9_418_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	fn remove_source_channel() -> Weight {
+		Weight::from_ref_time(10_786_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	fn set_reward() -> Weight {
+		Weight::from_ref_time(9_107_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	fn set_acknowledgement() -> Weight {
+		Weight::from_ref_time(9_315_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	fn overweight_message_executed() -> Weight {
+		Weight::from_ref_time(48_203_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(12 as u64))
+			.saturating_add(T::DbWeight::get().writes(8 as u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn submit(p: u32, q: u32, ) -> Weight {
+		Weight::from_ref_time(91_207_000 as u64)
+			.saturating_add(Weight::from_ref_time(5_000 as u64).saturating_mul(p as u64))
+			.saturating_add(Weight::from_ref_time(9_000 as u64).saturating_mul(q as u64))
+			.saturating_add(RocksDbWeight::get().reads(18 as u64))
+			.saturating_add(RocksDbWeight::get().writes(14 as u64))
+	}
+	fn submit_batch(n: u32, p: u32, q: u32, ) -> Weight {
+		Weight::from_ref_time(21_530_000 as u64)
+			.saturating_add(Weight::from_ref_time(66_518_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(5_000 as u64).saturating_mul(n as u64).saturating_mul(p as u64))
+			.saturating_add(Weight::from_ref_time(9_000 as u64).saturating_mul(n as u64).saturating_mul(q as u64))
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().reads((16 as u64).saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().writes((14 as u64).saturating_mul(n as u64)))
+	}
+	fn set_delivery_mode() -> Weight {
+		Weight::from_ref_time(9_243_000 as u64)
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	fn add_source_channel() -> Weight {
+		Weight::from_ref_time(9_418_000 as u64)
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	fn remove_source_channel() -> Weight {
+		Weight::from_ref_time(10_786_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	fn set_reward() -> Weight {
+		Weight::from_ref_time(9_107_000 as u64)
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	fn set_acknowledgement() -> Weight {
+		Weight::from_ref_time(9_315_000 as u64)
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	fn overweight_message_executed() -> Weight {
+		Weight::from_ref_time(48_203_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(12 as u64))
+			.saturating_add(RocksDbWeight::get().writes(8 as u64))
+	}
+}
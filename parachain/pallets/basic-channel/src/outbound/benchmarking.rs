@@ -1,28 +1,185 @@
 //! BasicOutboundChannel pallet benchmarking
 use super::*;
 
-use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite};
-use frame_support::traits::OnInitialize;
+use frame_benchmarking::{
+	account, benchmarks, impl_benchmark_test_suite, whitelisted_caller, BenchmarkError,
+};
+use frame_support::traits::{EnsureOrigin, OnInitialize};
+use frame_system::RawOrigin;
+use rlp::RlpStream;
 
 #[allow(unused_imports)]
 use crate::outbound::Pallet as BasicOutboundChannel;
 
+// Maximum size of a delivery proof, matching the inbound channel benchmarks.
+const MAX_PROOF_SIZE: u32 = 16 * 1024;
+
+// Build the RLP-encoded `MessageDispatched` log emitted by the inbound channel `channel` on
+// Ethereum for the message from `source_id` with the given nonce.
+fn make_dispatched_log(channel: H160, source_id: &[u8], nonce: u64) -> Vec<u8> {
+	let data = ethabi::encode(&[Token::FixedBytes(source_id.to_vec()), Token::Uint(nonce.into())]);
+
+	let mut log = RlpStream::new_list(3);
+	log.append(&channel.as_bytes().to_vec());
+	log.begin_list(1);
+	log.append(&keccak_256(MESSAGE_DISPATCHED_EVENT.signature.as_bytes()).to_vec());
+	log.append(&data);
+	log.out().to_vec()
+}
+
 benchmarks! {
 	where_clause {
 		where
 			T::AccountId: AsRef<[u8]>,
 	}
+	// Benchmark `submit` extrinsic with a payload of `p` bytes under worst case conditions,
+	// i.e. the message queue is full, the message starts a new backlog page and its source has
+	// its own commit schedule.
+	submit {
+		let p in 0 .. T::MaxMessagePayloadSize::get();
+
+		for _ in 0 .. T::MaxMessagesPerCommit::get() {
+			<MessageQueue<T>>::try_append(Message {
+				source_id: account("", 0, 0),
+				nonce: 0u64,
+				fee: T::Currency::minimum_balance(),
+				payload: Default::default(),
+			}).unwrap();
+		}
+
+		let origin = T::SubmitOrigin::try_successful_origin()
+			.map_err(|_| BenchmarkError::Weightless)?;
+		let (who, source_id) = T::SubmitOrigin::ensure_origin(origin.clone())
+			.map_err(|_| BenchmarkError::Weightless)?;
+		<SourceSchedules<T>>::insert(&source_id, CommitSchedule::default());
+		let fee = T::Currency::minimum_balance();
+		<Fee<T>>::put(fee);
+		T::Currency::make_free_balance_be(&who, fee * 100u32.into());
+		let payload: Vec<u8> = vec![1u8; p as usize];
+
+	}: _<T::RuntimeOrigin>(origin, payload)
+	verify {
+		assert_eq!(<MessageBacklog<T>>::decode_len(0).unwrap_or(0), 1);
+	}
+
+	set_fee {
+		let fee = T::Currency::minimum_balance();
+	}: _(RawOrigin::Root, fee)
+	verify {
+		assert_eq!(<Fee<T>>::get(), fee);
+	}
+
+	set_interval {
+		let interval: T::BlockNumber = 10u32.into();
+	}: _(RawOrigin::Root, interval)
+	verify {
+		assert_eq!(<Interval<T>>::get(), interval);
+	}
+
+	set_queue_size_threshold {
+		let threshold = T::MaxMessagesPerCommit::get();
+	}: _(RawOrigin::Root, Some(threshold))
+	verify {
+		assert_eq!(<QueueSizeThreshold<T>>::get(), Some(threshold));
+	}
+
+	set_max_message_age {
+		let max_age: T::BlockNumber = 10u32.into();
+	}: _(RawOrigin::Root, Some(max_age))
+	verify {
+		assert_eq!(<MaxMessageAge<T>>::get(), Some(max_age));
+	}
+
+	// Benchmark `confirm_delivery` extrinsic for a commitment of messages from `n` sources,
+	// proven by `n` proofs of `q` bytes.
+	confirm_delivery {
+		let n in 1 .. T::MaxMessagesPerCommit::get();
+		let q in 0 .. MAX_PROOF_SIZE;
+
+		let origin = T::DeliveryOrigin::try_successful_origin()
+			.map_err(|_| BenchmarkError::Weightless)?;
+		let relayer = T::DeliveryOrigin::ensure_origin(origin.clone())
+			.map_err(|_| BenchmarkError::Weightless)?;
+		let channel = H160::repeat_byte(1);
+		<InboundChannel<T>>::put(channel);
+
+		let nonces: Vec<NonceRange<T::SourceId>> = (0 .. n)
+			.map(|i| NonceRange { source_id: account("", i, 0), first: 1, last: 1 })
+			.collect();
+		let logs = nonces.iter()
+			.map(|range| make_dispatched_log(channel, &range.source_id.encode(), range.last))
+			.collect();
+		let proofs = T::Verifier::initialize_benchmark_messages(logs, q);
+
+		let hash = H256::repeat_byte(1);
+		let fee = T::Currency::minimum_balance();
+		<Commitments<T>>::insert(hash, CommitmentInfo {
+			block_number: Zero::zero(),
+			nonces: nonces.try_into().unwrap(),
+		});
+		<CommitmentFees<T>>::insert(hash, fee);
+
+	}: _<T::RuntimeOrigin>(origin, hash, proofs)
+	verify {
+		assert_eq!(<RelayerRewards<T>>::get(&relayer), fee);
+	}
+
+	claim_rewards {
+		let relayer: T::AccountId = whitelisted_caller();
+		let amount = T::Currency::minimum_balance() * 10u32.into();
+		T::Currency::make_free_balance_be(
+			&BasicOutboundChannel::<T>::account_id(),
+			amount * 2u32.into(),
+		);
+		<RelayerRewards<T>>::insert(&relayer, amount);
+
+	}: _(RawOrigin::Signed(relayer.clone()))
+	verify {
+		assert_eq!(T::Currency::free_balance(&relayer), amount);
+	}
+
+	add_relayer {
+		let relayer: T::AccountId = account("relayer", 0, 0);
+	}: _(RawOrigin::Root, relayer.clone())
+	verify {
+		assert!(<Relayers<T>>::contains_key(&relayer));
+	}
+
+	remove_relayer {
+		let relayer: T::AccountId = account("relayer", 0, 0);
+		<Relayers<T>>::insert(&relayer, ());
+	}: _(RawOrigin::Root, relayer.clone())
+	verify {
+		assert!(!<Relayers<T>>::contains_key(&relayer));
+	}
+
+	set_source_schedule {
+		let source_id: T::SourceId = account("", 0, 0);
+		let schedule = CommitSchedule { max_message_age: Some(10u32.into()), ..Default::default() };
+	}: _(RawOrigin::Root, source_id.clone(), Some(schedule.clone()))
+	verify {
+		assert_eq!(<SourceSchedules<T>>::get(source_id), Some(schedule));
+	}
+
+	set_inbound_channel {
+		let channel = H160::repeat_byte(1);
+	}: _(RawOrigin::Root, channel)
+	verify {
+		assert_eq!(<InboundChannel<T>>::get(), channel);
+	}
+
 	// Benchmark `on_initialize` under worst case conditions, i.e. messages
 	// in queue are committed.
 	on_commit {
 		let m in 1 .. T::MaxMessagesPerCommit::get();
 		let p in 0 .. T::MaxMessagePayloadSize::get()-1;
 
-		for _ in 0 .. m {
+		for i in 0 .. m {
 			let payload: Vec<u8> = (0..).take(p as usize).collect();
 			<MessageQueue<T>>::try_append(Message {
-				source_id: account("", 0, 0),
+				source_id: account("", i, 0),
 				nonce: 0u64,
+				fee: T::Currency::minimum_balance(),
 				payload: payload.try_into().unwrap(),
 			}).unwrap();
 		}
@@ -34,6 +191,28 @@ benchmarks! {
 		assert_eq!(<MessageQueue<T>>::get().len(), 0);
 	}
 
+	reemit_commitment {
+		let m in 1 .. T::MaxMessagesPerCommit::get();
+		let p in 0 .. T::MaxMessagePayloadSize::get()-1;
+
+		for i in 0 .. m {
+			let payload: Vec<u8> = (0..).take(p as usize).collect();
+			<MessageQueue<T>>::try_append(Message {
+				source_id: account("", i, 0),
+				nonce: 0u64,
+				fee: T::Currency::minimum_balance(),
+				payload: payload.try_into().unwrap(),
+			}).unwrap();
+		}
+		let messages = <MessageQueue<T>>::get();
+		BasicOutboundChannel::<T>::commit(Weight::MAX);
+		let hash = <Commitments<T>>::iter_keys().next().unwrap();
+
+	}: _(RawOrigin::Root, hash, messages)
+	verify {
+		assert!(<Commitments<T>>::contains_key(hash));
+	}
+
 	// Benchmark 'on_initialize` for the case where it is a commitment interval
 	// but there are no messages in the queue.
 	on_commit_no_messages {
@@ -7,46 +7,98 @@ mod benchmarking;
 mod test;
 
 use codec::{Decode, Encode, MaxEncodedLen};
-use ethabi::{self, Token};
+use ethabi::{self, Event as EthereumEvent, Param, ParamKind, Token};
 use frame_support::{
-	dispatch::DispatchResult, ensure, pallet_prelude::Member, traits::Get, weights::Weight,
-	BoundedVec, CloneNoBound, Parameter, PartialEqNoBound, RuntimeDebugNoBound,
+	dispatch::{DispatchError, DispatchResult},
+	ensure,
+	pallet_prelude::Member,
+	traits::{Currency, EnsureOrigin, ExistenceRequirement, Get},
+	weights::Weight,
+	BoundedVec, CloneNoBound, PalletId, Parameter, PartialEqNoBound, RuntimeDebugNoBound,
 };
 use scale_info::TypeInfo;
-use sp_core::H256;
-use sp_runtime::traits::Hash;
+use sp_core::{H160, H256};
+use sp_runtime::{
+	traits::{AccountIdConversion, Hash, Saturating, Zero},
+	RuntimeDebug,
+};
 
-use sp_std::prelude::*;
+use sp_std::{marker::PhantomData, prelude::*};
 
-use sp_io::offchain_index::set;
+use sp_io::{hashing::keccak_256, offchain_index::set};
 
-use snowbridge_core::types::AuxiliaryDigestItem;
+use snowbridge_core::{
+	types::AuxiliaryDigestItem, Message as EthereumMessage, OutboundChannel, Verifier,
+};
+use snowbridge_ethereum::Log;
 
 use snowbridge_basic_channel_merkle_proof::merkle_root;
 
 pub use weights::WeightInfo;
 
+use crate::inbound::{average_size, proof_size};
+
+// Used to decode the event emitted by the inbound channel on Ethereum when it dispatches a
+// message, which proves that the message was delivered.
+static MESSAGE_DISPATCHED_EVENT: &EthereumEvent = &EthereumEvent {
+	signature: "MessageDispatched(bytes32,uint64)",
+	inputs: &[
+		Param { kind: ParamKind::FixedBytes(32), indexed: false },
+		Param { kind: ParamKind::Uint(64), indexed: false },
+	],
+	anonymous: false,
+};
+
+/// Decode a `MessageDispatched(bytes32 sourceID, uint64 nonce)` log emitted by the inbound
+/// channel on Ethereum into the source ID and nonce of the dispatched message.
+fn decode_message_dispatched(log: Log) -> Option<(Vec<u8>, u64)> {
+	let topic: H256 = keccak_256(MESSAGE_DISPATCHED_EVENT.signature.as_bytes()).into();
+	if log.topics.first() != Some(&topic) {
+		return None
+	}
+
+	let tokens = MESSAGE_DISPATCHED_EVENT.decode(log.topics, log.data).ok()?;
+	match tokens.as_slice() {
+		[Token::FixedBytes(source_id), Token::Uint(nonce)] if nonce.bits() <= 64 =>
+			Some((source_id.clone(), nonce.low_u64())),
+		_ => None,
+	}
+}
+
+/// Weight of `confirm_delivery` with the given delivery proofs.
+fn confirm_delivery_weight<T: Config>(proofs: &[EthereumMessage]) -> Weight {
+	T::WeightInfo::confirm_delivery(
+		proofs.len() as u32,
+		average_size(proofs, |proof| proof_size(&proof.proof)),
+	)
+}
+
 #[derive(
 	Encode, Decode, CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound, MaxEncodedLen, TypeInfo,
 )]
 #[scale_info(skip_type_params(M))]
-#[codec(mel_bound(SourceId: MaxEncodedLen))]
-pub struct Message<SourceId, M: Get<u32>>
+#[codec(mel_bound(SourceId: MaxEncodedLen, Balance: MaxEncodedLen))]
+pub struct Message<SourceId, Balance, M: Get<u32>>
 where
 	SourceId: Parameter + Member + MaxEncodedLen,
+	Balance: Parameter + Member + MaxEncodedLen,
 {
 	/// ID of source parachain
 	source_id: SourceId,
 	/// Unique nonce to prevent replaying messages
 	#[codec(compact)]
 	nonce: u64,
+	/// Fee paid by the sender of the message. Only used for off-chain reconciliation, it is
+	/// not part of the ethabi-encoded message.
+	fee: Balance,
 	/// Payload for target application.
 	payload: BoundedVec<u8, M>,
 }
 
-impl<SourceId, M: Get<u32>> Into<Token> for Message<SourceId, M>
+impl<SourceId, Balance, M: Get<u32>> Into<Token> for Message<SourceId, Balance, M>
 where
 	SourceId: Decode + Parameter + Member + MaxEncodedLen, //+ TypeInfo,
+	Balance: Parameter + Member + MaxEncodedLen,
 {
 	fn into(self) -> Token {
 		Token::Tuple(vec![
@@ -57,6 +109,55 @@ where
 	}
 }
 
+/// Range of nonces included in a commitment for a single source.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct NonceRange<SourceId> {
+	pub source_id: SourceId,
+	/// Nonce of the first message from the source
+	pub first: u64,
+	/// Nonce of the last message from the source
+	pub last: u64,
+}
+
+/// Commit schedule of a single source, which is checked in addition to the global schedule.
+/// Each trigger only considers the messages of the source, and is disabled when `None`.
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct CommitSchedule<BlockNumber> {
+	/// Commit the messages of the source in every block whose number is a multiple of the
+	/// interval, or in every block if the interval is zero.
+	pub interval: Option<BlockNumber>,
+	/// Commit as soon as at least this many messages of the source are waiting.
+	pub queue_size_threshold: Option<u32>,
+	/// Commit as soon as the oldest waiting message of the source has waited this many blocks.
+	pub max_message_age: Option<BlockNumber>,
+}
+
+/// Messages of a source with a [`CommitSchedule`] which are waiting to be committed.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct PendingMessages<BlockNumber> {
+	/// Number of messages waiting in the queue or the backlog
+	pub count: u32,
+	/// Block in which the oldest waiting message was submitted
+	pub queued_since: BlockNumber,
+}
+
+/// Record of an emitted commitment, used to re-emit the commitment if it was missed.
+#[derive(
+	Encode, Decode, CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound, MaxEncodedLen, TypeInfo,
+)]
+#[scale_info(skip_type_params(M))]
+#[codec(mel_bound(BlockNumber: MaxEncodedLen, SourceId: MaxEncodedLen))]
+pub struct CommitmentInfo<BlockNumber, SourceId, M: Get<u32>>
+where
+	BlockNumber: Parameter + Member + MaxEncodedLen,
+	SourceId: Parameter + Member + MaxEncodedLen,
+{
+	/// Block in which the commitment was emitted
+	pub block_number: BlockNumber,
+	/// Nonces of the committed messages, per source
+	pub nonces: BoundedVec<NonceRange<SourceId>, M>,
+}
+
 // base_weight=(0.75*0.5)*(10**12)=375_000_000_000
 // we leave the extra 10_000_000_000/375_000_000_000=2.66% as margin
 // so we can use at most 365000000000 for the commit call
@@ -64,6 +165,20 @@ where
 // MaxMessagesPerCommit=20 and MaxMessagePayloadSize=256
 pub const MINIMUM_WEIGHT_REMAIN_IN_BLOCK: Weight = Weight::from_ref_time(10_000_000_000);
 
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+pub type MessageOf<T> =
+	Message<<T as Config>::SourceId, BalanceOf<T>, <T as Config>::MaxMessagePayloadSize>;
+
+pub type CommitmentInfoOf<T> = CommitmentInfo<
+	<T as frame_system::Config>::BlockNumber,
+	<T as Config>::SourceId,
+	<T as Config>::MaxMessagesPerCommit,
+>;
+
+pub type CommitScheduleOf<T> = CommitSchedule<<T as frame_system::Config>::BlockNumber>;
+
 pub use pallet::*;
 
 #[frame_support::pallet]
@@ -93,6 +208,34 @@ pub mod pallet {
 		#[pallet::constant]
 		type MaxMessagesPerCommit: Get<u32>;
 
+		/// Max number of sources with their own [`CommitSchedule`]
+		#[pallet::constant]
+		type MaxScheduledSources: Get<u32>;
+
+		/// Origin allowed to submit messages via the `submit` extrinsic. Resolves to the
+		/// account paying the message fee and the `SourceId` that the message will be sent
+		/// from. See [`EnsureSignedSource`] for signed origins sending from their own account.
+		type SubmitOrigin: EnsureOrigin<
+			Self::RuntimeOrigin,
+			Success = (Self::AccountId, Self::SourceId),
+		>;
+
+		/// Currency used to pay message fees and relayer rewards
+		type Currency: Currency<Self::AccountId>;
+
+		/// Pallet ID of the treasury account holding message fees until they are paid out to
+		/// relayers
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Origin allowed to prove that a commitment has been delivered to Ethereum. Resolves to
+		/// the relayer which is rewarded with the fees of the commitment. See
+		/// [`EnsureRelayer`] for an origin allowing the registered [`Relayers`].
+		type DeliveryOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = Self::AccountId>;
+
+		/// Verifier of the Ethereum logs which prove the delivery of commitments.
+		type Verifier: Verifier;
+
 		/// Weight information for extrinsics in this pallet
 		type WeightInfo: WeightInfo;
 	}
@@ -100,45 +243,156 @@ pub mod pallet {
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		MessageAccepted(u64),
-		Committed { hash: H256, data: Vec<Message<T::SourceId, T::MaxMessagePayloadSize>> },
+		MessageAccepted { source_id: T::SourceId, nonce: u64, fee: BalanceOf<T> },
+		Committed { hash: H256, data: Vec<MessageOf<T>> },
+		FeeUpdated { fee: BalanceOf<T> },
+		DeliveryConfirmed { hash: H256, relayer: T::AccountId, reward: BalanceOf<T> },
+		RewardsClaimed { relayer: T::AccountId, amount: BalanceOf<T> },
+		CommitmentReemitted { hash: H256 },
+		IntervalUpdated { interval: T::BlockNumber },
+		QueueSizeThresholdUpdated { threshold: Option<u32> },
+		MaxMessageAgeUpdated { max_age: Option<T::BlockNumber> },
+		RelayerAdded { relayer: T::AccountId },
+		RelayerRemoved { relayer: T::AccountId },
+		InboundChannelUpdated { channel: H160 },
+		SourceScheduleUpdated { source_id: T::SourceId, schedule: Option<CommitScheduleOf<T>> },
 	}
 
 	#[pallet::error]
 	pub enum Error<T> {
 		/// The message payload exceeds byte limit.
 		PayloadTooLarge,
-		/// No more messages can be queued for the channel during this commit cycle.
+		/// The message could not be added to the queue.
 		QueueSizeLimitReached,
 		/// Cannot increment nonce
 		Overflow,
+		/// The commitment is unknown. Either it was never committed or its delivery was
+		/// already confirmed.
+		UnknownCommitment,
+		/// The relayer has no rewards to claim.
+		NoRewards,
+		/// The messages do not match the commitment.
+		InvalidCommitment,
+		/// The account is not a registered relayer.
+		UnknownRelayer,
+		/// The proofs do not show that the inbound channel on Ethereum dispatched the last
+		/// message of each source in the commitment.
+		InvalidDeliveryProof,
+		/// No more sources can be given their own commit schedule.
+		TooManyScheduledSources,
 	}
 
-	/// Interval between commitments
+	/// Interval between commitments. Messages are committed in every block whose number is a
+	/// multiple of the interval, or in every block if the interval is zero.
 	#[pallet::storage]
 	#[pallet::getter(fn interval)]
 	pub(super) type Interval<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
 
+	/// If set, messages are also committed as soon as the queue holds at least this many
+	/// messages.
+	#[pallet::storage]
+	#[pallet::getter(fn queue_size_threshold)]
+	pub(super) type QueueSizeThreshold<T: Config> = StorageValue<_, u32, OptionQuery>;
+
+	/// If set, messages are also committed as soon as the oldest queued message has waited this
+	/// many blocks.
+	#[pallet::storage]
+	#[pallet::getter(fn max_message_age)]
+	pub(super) type MaxMessageAge<T: Config> = StorageValue<_, T::BlockNumber, OptionQuery>;
+
+	/// Block in which the oldest message waiting to be committed was submitted.
+	#[pallet::storage]
+	pub(super) type QueuedSince<T: Config> = StorageValue<_, T::BlockNumber, OptionQuery>;
+
+	/// Commit schedules of sources which are checked in addition to the global schedule.
+	#[pallet::storage]
+	#[pallet::getter(fn source_schedule)]
+	pub(super) type SourceSchedules<T: Config> =
+		CountedStorageMap<_, Twox64Concat, T::SourceId, CommitScheduleOf<T>, OptionQuery>;
+
+	/// Messages waiting to be committed from sources with an entry in [`SourceSchedules`].
+	#[pallet::storage]
+	pub(super) type PendingSourceMessages<T: Config> =
+		StorageMap<_, Twox64Concat, T::SourceId, PendingMessages<T::BlockNumber>, OptionQuery>;
+
 	/// Messages waiting to be committed.
 	#[pallet::storage]
-	pub(super) type MessageQueue<T: Config> = StorageValue<
+	pub(super) type MessageQueue<T: Config> =
+		StorageValue<_, BoundedVec<MessageOf<T>, T::MaxMessagesPerCommit>, ValueQuery>;
+
+	/// Messages which did not fit in the [`MessageQueue`], stored in pages of up to
+	/// `MaxMessagesPerCommit` messages. Pages are keyed by a sequence number and are moved into
+	/// the [`MessageQueue`] in order, one page per commitment.
+	#[pallet::storage]
+	pub(super) type MessageBacklog<T: Config> = StorageMap<
 		_,
-		BoundedVec<Message<T::SourceId, T::MaxMessagePayloadSize>, T::MaxMessagesPerCommit>,
+		Twox64Concat,
+		u64,
+		BoundedVec<MessageOf<T>, T::MaxMessagesPerCommit>,
 		ValueQuery,
 	>;
 
+	/// Sequence number of the oldest page in the [`MessageBacklog`].
+	#[pallet::storage]
+	pub(super) type BacklogHead<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+	/// Sequence number of the next page to be added to the [`MessageBacklog`].
+	#[pallet::storage]
+	pub(super) type BacklogTail<T: Config> = StorageValue<_, u64, ValueQuery>;
+
 	#[pallet::storage]
 	pub type Nonce<T: Config> = StorageMap<_, Twox64Concat, T::SourceId, u64, ValueQuery>;
 
+	/// Fee charged for each message submitted to the channel
+	#[pallet::storage]
+	#[pallet::getter(fn fee)]
+	pub type Fee<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	/// Fees held in the treasury for each commitment, awaiting confirmation of delivery.
+	#[pallet::storage]
+	pub type CommitmentFees<T: Config> = StorageMap<_, Identity, H256, BalanceOf<T>, OptionQuery>;
+
+	/// Commitments which have been emitted but whose delivery has not yet been confirmed.
+	#[pallet::storage]
+	pub type Commitments<T: Config> =
+		StorageMap<_, Identity, H256, CommitmentInfoOf<T>, OptionQuery>;
+
+	/// Hashes of the commitments emitted in the most recent block which had commitments.
+	#[pallet::storage]
+	#[pallet::unbounded]
+	pub(super) type LatestCommitments<T: Config> =
+		StorageValue<_, (T::BlockNumber, Vec<H256>), OptionQuery>;
+
+	/// Rewards earned by relayers for confirmed deliveries which have not yet been claimed.
+	#[pallet::storage]
+	pub type RelayerRewards<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	/// Relayers allowed to confirm the delivery of commitments through [`EnsureRelayer`].
+	#[pallet::storage]
+	pub type Relayers<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, (), OptionQuery>;
+
+	/// Address of the inbound channel on Ethereum, whose `MessageDispatched` events prove the
+	/// delivery of commitments.
+	#[pallet::storage]
+	#[pallet::getter(fn inbound_channel)]
+	pub type InboundChannel<T: Config> = StorageValue<_, H160, ValueQuery>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
 		pub interval: T::BlockNumber,
+		pub fee: BalanceOf<T>,
+		pub inbound_channel: H160,
 	}
 
 	#[cfg(feature = "std")]
 	impl<T: Config> Default for GenesisConfig<T> {
 		fn default() -> Self {
-			Self { interval: Default::default() }
+			Self {
+				interval: Default::default(),
+				fee: Default::default(),
+				inbound_channel: Default::default(),
+			}
 		}
 	}
 
@@ -146,6 +400,220 @@ pub mod pallet {
 	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
 		fn build(&self) {
 			<Interval<T>>::put(self.interval);
+			<Fee<T>>::put(self.fee);
+			<InboundChannel<T>>::put(self.inbound_channel);
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Submit a message on the outbound channel. The transaction fee charged scales with
+		/// the size of the payload. The message fee is paid by the account which the
+		/// [`Config::SubmitOrigin`] resolves to.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::submit(payload.len() as u32))]
+		pub fn submit(origin: OriginFor<T>, payload: Vec<u8>) -> DispatchResult {
+			let (who, source_id) = T::SubmitOrigin::ensure_origin(origin)?;
+			Self::submit_message(&who, &source_id, &payload)?;
+			Ok(())
+		}
+
+		/// Set the fee charged for each message.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::set_fee())]
+		pub fn set_fee(origin: OriginFor<T>, fee: BalanceOf<T>) -> DispatchResult {
+			ensure_root(origin)?;
+			<Fee<T>>::put(fee);
+			Self::deposit_event(Event::FeeUpdated { fee });
+			Ok(())
+		}
+
+		/// Prove that the commitment with the given hash was delivered to Ethereum. The fees
+		/// held for the commitment are credited to the relayer proving delivery.
+		///
+		/// Messages from a source are dispatched in nonce order on Ethereum, so the delivery of
+		/// a commitment is proven by the `MessageDispatched` event of the last message of each
+		/// source in the commitment. `proofs` holds one proof of such an event per source, in
+		/// the order in which the sources appear in the commitment.
+		#[pallet::call_index(2)]
+		#[pallet::weight(confirm_delivery_weight::<T>(&proofs))]
+		pub fn confirm_delivery(
+			origin: OriginFor<T>,
+			hash: H256,
+			proofs: Vec<EthereumMessage>,
+		) -> DispatchResult {
+			let relayer = T::DeliveryOrigin::ensure_origin(origin)?;
+
+			let info = <Commitments<T>>::get(hash).ok_or(Error::<T>::UnknownCommitment)?;
+			ensure!(proofs.len() == info.nonces.len(), Error::<T>::InvalidDeliveryProof);
+			let channel = <InboundChannel<T>>::get();
+			for (range, proof) in info.nonces.iter().zip(proofs.iter()) {
+				let (log, _) = T::Verifier::verify(proof)?;
+				ensure!(log.address == channel, Error::<T>::InvalidDeliveryProof);
+				let (source_id, nonce) =
+					decode_message_dispatched(log).ok_or(Error::<T>::InvalidDeliveryProof)?;
+				ensure!(
+					source_id == range.source_id.encode() && nonce == range.last,
+					Error::<T>::InvalidDeliveryProof
+				);
+			}
+
+			<Commitments<T>>::remove(hash);
+			// No fees are held for commitments of messages which were submitted for free
+			let reward = <CommitmentFees<T>>::take(hash).unwrap_or_else(Zero::zero);
+			<RelayerRewards<T>>::mutate(&relayer, |rewards| {
+				*rewards = rewards.saturating_add(reward)
+			});
+
+			Self::deposit_event(Event::DeliveryConfirmed { hash, relayer, reward });
+			Ok(())
+		}
+
+		/// Pay out all rewards earned by the signer from the treasury.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::claim_rewards())]
+		pub fn claim_rewards(origin: OriginFor<T>) -> DispatchResult {
+			let relayer = ensure_signed(origin)?;
+
+			let amount = <RelayerRewards<T>>::take(&relayer);
+			ensure!(!amount.is_zero(), Error::<T>::NoRewards);
+
+			T::Currency::transfer(
+				&Self::account_id(),
+				&relayer,
+				amount,
+				ExistenceRequirement::AllowDeath,
+			)?;
+
+			Self::deposit_event(Event::RewardsClaimed { relayer, amount });
+			Ok(())
+		}
+
+		/// Emit a previously committed set of messages again, for a commitment which was missed
+		/// by relayers. The messages must be the ones originally committed, in the same order.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::reemit_commitment(
+			messages.len() as u32,
+			messages.iter().map(|message| message.payload.len() as u32).max().unwrap_or(0),
+		))]
+		pub fn reemit_commitment(
+			origin: OriginFor<T>,
+			hash: H256,
+			messages: BoundedVec<MessageOf<T>, T::MaxMessagesPerCommit>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(<Commitments<T>>::contains_key(hash), Error::<T>::UnknownCommitment);
+
+			let eth_messages = Self::encode_messages(&messages);
+			ensure!(
+				merkle_root::<<T as Config>::Hashing, Vec<Vec<u8>>, Vec<u8>>(eth_messages.clone()) ==
+					hash,
+				Error::<T>::InvalidCommitment,
+			);
+
+			Self::emit_commitment(hash, messages, eth_messages);
+
+			Self::deposit_event(Event::CommitmentReemitted { hash });
+			Ok(())
+		}
+
+		/// Set the interval between commitments.
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::set_interval())]
+		pub fn set_interval(origin: OriginFor<T>, interval: T::BlockNumber) -> DispatchResult {
+			ensure_root(origin)?;
+			<Interval<T>>::put(interval);
+			Self::deposit_event(Event::IntervalUpdated { interval });
+			Ok(())
+		}
+
+		/// Set the number of queued messages which triggers a commitment, or disable the
+		/// threshold with `None`.
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::set_queue_size_threshold())]
+		pub fn set_queue_size_threshold(
+			origin: OriginFor<T>,
+			threshold: Option<u32>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			<QueueSizeThreshold<T>>::set(threshold);
+			Self::deposit_event(Event::QueueSizeThresholdUpdated { threshold });
+			Ok(())
+		}
+
+		/// Set the age in blocks of the oldest queued message which triggers a commitment, or
+		/// disable the maximum age with `None`.
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::set_max_message_age())]
+		pub fn set_max_message_age(
+			origin: OriginFor<T>,
+			max_age: Option<T::BlockNumber>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			<MaxMessageAge<T>>::set(max_age);
+			Self::deposit_event(Event::MaxMessageAgeUpdated { max_age });
+			Ok(())
+		}
+
+		/// Allow `relayer` to confirm the delivery of commitments.
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::WeightInfo::add_relayer())]
+		pub fn add_relayer(origin: OriginFor<T>, relayer: T::AccountId) -> DispatchResult {
+			ensure_root(origin)?;
+			<Relayers<T>>::insert(&relayer, ());
+			Self::deposit_event(Event::RelayerAdded { relayer });
+			Ok(())
+		}
+
+		/// Stop `relayer` from confirming the delivery of commitments. Rewards already earned
+		/// by the relayer can still be claimed.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::remove_relayer())]
+		pub fn remove_relayer(origin: OriginFor<T>, relayer: T::AccountId) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(<Relayers<T>>::contains_key(&relayer), Error::<T>::UnknownRelayer);
+			<Relayers<T>>::remove(&relayer);
+			Self::deposit_event(Event::RelayerRemoved { relayer });
+			Ok(())
+		}
+
+		/// Set the commit schedule of `source_id`, which is checked in addition to the global
+		/// schedule, or remove it with `None`.
+		#[pallet::call_index(11)]
+		#[pallet::weight(T::WeightInfo::set_source_schedule())]
+		pub fn set_source_schedule(
+			origin: OriginFor<T>,
+			source_id: T::SourceId,
+			schedule: Option<CommitScheduleOf<T>>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			match schedule.clone() {
+				Some(schedule) => {
+					ensure!(
+						<SourceSchedules<T>>::contains_key(&source_id) ||
+							<SourceSchedules<T>>::count() < T::MaxScheduledSources::get(),
+						Error::<T>::TooManyScheduledSources
+					);
+					<SourceSchedules<T>>::insert(&source_id, schedule);
+				},
+				None => {
+					<SourceSchedules<T>>::remove(&source_id);
+					<PendingSourceMessages<T>>::remove(&source_id);
+				},
+			}
+			Self::deposit_event(Event::SourceScheduleUpdated { source_id, schedule });
+			Ok(())
+		}
+
+		/// Set the address of the inbound channel on Ethereum, whose events prove the delivery
+		/// of commitments.
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::set_inbound_channel())]
+		pub fn set_inbound_channel(origin: OriginFor<T>, channel: H160) -> DispatchResult {
+			ensure_root(origin)?;
+			<InboundChannel<T>>::put(channel);
+			Self::deposit_event(Event::InboundChannelUpdated { channel });
+			Ok(())
 		}
 	}
 
@@ -154,10 +622,11 @@ pub mod pallet {
 	where
 		T::AccountId: AsRef<[u8]>,
 	{
-		// Generate a message commitment when the chain is idle with enough remaining weight
+		// Generate a message commitment when the chain is idle with enough remaining weight,
+		// and the commit schedule is due.
 		// The commitment hash is included in an [`AuxiliaryDigestItem`] in the block header,
 		// with the corresponding commitment is persisted offchain.
-		fn on_idle(_n: T::BlockNumber, total_weight: Weight) -> Weight {
+		fn on_idle(n: T::BlockNumber, total_weight: Weight) -> Weight {
 			let weight_remaining = total_weight.saturating_sub(T::WeightInfo::on_commit(
 				T::MaxMessagesPerCommit::get(),
 				T::MaxMessagePayloadSize::get(),
@@ -165,82 +634,341 @@ pub mod pallet {
 			if weight_remaining.ref_time() <= MINIMUM_WEIGHT_REMAIN_IN_BLOCK.ref_time() {
 				return total_weight
 			}
-			Self::commit(total_weight)
+
+			// Reading the commit schedule, see `should_commit`
+			let schedule_weight = T::DbWeight::get()
+				.reads(7)
+				.saturating_add(T::DbWeight::get().reads(2 * T::MaxScheduledSources::get() as u64));
+			if !Self::should_commit(n) {
+				return schedule_weight
+			}
+			Self::commit(total_weight.saturating_sub(MINIMUM_WEIGHT_REMAIN_IN_BLOCK))
+				.saturating_add(schedule_weight)
 		}
 	}
 
 	impl<T: Config> Pallet<T> {
-		/// Submit message on the outbound channel
-		pub fn submit(source_id: &T::SourceId, payload: &[u8]) -> DispatchResult {
-			ensure!(
-				<MessageQueue<T>>::decode_len().unwrap_or(0) <
-					T::MaxMessagesPerCommit::get() as usize,
-				Error::<T>::QueueSizeLimitReached,
-			);
-
+		/// Submit message on the outbound channel. The message fee is paid by `who` into the
+		/// treasury. Returns the nonce assigned to the message.
+		///
+		/// If the [`MessageQueue`] is full, the message is added to the [`MessageBacklog`] to be
+		/// included in a later commitment.
+		pub fn submit_message(
+			who: &T::AccountId,
+			source_id: &T::SourceId,
+			payload: &[u8],
+		) -> Result<u64, DispatchError> {
 			let message_payload =
 				payload.to_vec().try_into().map_err(|_| Error::<T>::PayloadTooLarge)?;
 			let nonce = <Nonce<T>>::get(source_id);
 			let next_nonce = nonce.checked_add(1).ok_or(Error::<T>::Overflow)?;
 
-			<MessageQueue<T>>::try_append(Message {
+			let fee = <Fee<T>>::get();
+			if !fee.is_zero() {
+				T::Currency::transfer(
+					who,
+					&Self::account_id(),
+					fee,
+					ExistenceRequirement::KeepAlive,
+				)?;
+			}
+
+			let message =
+				Message { source_id: source_id.clone(), nonce, fee, payload: message_payload };
+			if <BacklogHead<T>>::get() == <BacklogTail<T>>::get() &&
+				<MessageQueue<T>>::decode_len().unwrap_or(0) <
+					T::MaxMessagesPerCommit::get() as usize
+			{
+				<MessageQueue<T>>::try_append(message)
+					.map_err(|_| Error::<T>::QueueSizeLimitReached)?;
+			} else {
+				Self::append_to_backlog(message)?;
+			}
+			let now = <frame_system::Pallet<T>>::block_number();
+			if !<QueuedSince<T>>::exists() {
+				<QueuedSince<T>>::put(now);
+			}
+			if <SourceSchedules<T>>::contains_key(source_id) {
+				<PendingSourceMessages<T>>::mutate(source_id, |pending| match pending {
+					Some(pending) => pending.count = pending.count.saturating_add(1),
+					None => *pending = Some(PendingMessages { count: 1, queued_since: now }),
+				});
+			}
+			Self::deposit_event(Event::MessageAccepted {
 				source_id: source_id.clone(),
 				nonce,
-				payload: message_payload,
-			})
-			.map_err(|_| Error::<T>::QueueSizeLimitReached)?;
-			Self::deposit_event(Event::MessageAccepted(nonce));
+				fee,
+			});
 
 			<Nonce<T>>::set(source_id, next_nonce);
 
-			Ok(())
+			Ok(nonce)
 		}
 
 		/// Commit messages enqueued on the outbound channel.
-		/// Find the Merkle root of all of the messages in the queue. (TODO: take a sublist, later
-		/// use weights to determine the size of the sublist). Use ethabi-encoded messages as the
-		/// leaves of the Merkle tree. Then:
-		/// - Store the commitment hash on the parachain for the Ethereum light client to query.
-		/// - Emit an event with the commitment hash and SCALE-encoded message bundles for a
-		/// relayer to read.
-		/// - Persist the ethabi-encoded message bundles to off-chain storage.
-		pub fn commit(_total_weight: Weight) -> Weight {
-			// TODO: SNO-310 consider using mutate here. If some part of emitting message bundles
-			// fails, we don't want the MessageQueue to be empty.
-			let message_queue = <MessageQueue<T>>::take();
-			if message_queue.is_empty() {
-				return T::WeightInfo::on_commit_no_messages()
+		///
+		/// Messages in the queue are committed, after which the queue is refilled with the next
+		/// page of the backlog. This is repeated for as long as the weight of the next commitment
+		/// fits within `total_weight`, so a block may contain several commitments.
+		pub fn commit(total_weight: Weight) -> Weight {
+			let mut weight_used = Weight::zero();
+			loop {
+				let message_queue = <MessageQueue<T>>::get();
+				if message_queue.is_empty() {
+					return weight_used.saturating_add(T::WeightInfo::on_commit_no_messages())
+				}
+
+				let weight = T::WeightInfo::on_commit(
+					message_queue.len() as u32,
+					Self::average_payload_size(&message_queue),
+				)
+				// Moving the next backlog page into the queue
+				.saturating_add(T::DbWeight::get().reads_writes(3, 3))
+				// Updating the pending messages of scheduled sources, at worst one per message
+				.saturating_add(
+					T::DbWeight::get()
+						.reads_writes(message_queue.len() as u64, message_queue.len() as u64),
+				);
+				if weight_used.saturating_add(weight).any_gt(total_weight) {
+					return weight_used
+				}
+
+				Self::commit_messages(message_queue);
+
+				// Messages are only removed from the queue once the commitment is emitted
+				match Self::take_backlog_page() {
+					Some(page) => <MessageQueue<T>>::put(page),
+					None => {
+						<MessageQueue<T>>::kill();
+						<QueuedSince<T>>::kill();
+					},
+				}
+
+				weight_used = weight_used.saturating_add(weight);
+			}
+		}
+
+		/// Whether messages should be committed in block `now`, according to the configured
+		/// interval, queue size threshold and maximum message age, or to the schedule of a
+		/// source with messages waiting to be committed.
+		///
+		/// Messages from all sources share the queue and are committed together, so a trigger
+		/// for one source also commits the messages of every other source.
+		fn should_commit(now: T::BlockNumber) -> bool {
+			let interval = Self::interval();
+			if interval.is_zero() || (now % interval).is_zero() {
+				return true
+			}
+
+			let source_due = <PendingSourceMessages<T>>::iter().any(|(source_id, pending)| {
+				<SourceSchedules<T>>::get(source_id)
+					.map_or(false, |schedule| Self::source_due(now, &schedule, &pending))
+			});
+			if source_due {
+				return true
+			}
+
+			if let Some(threshold) = Self::queue_size_threshold() {
+				let backlog_empty = <BacklogHead<T>>::get() == <BacklogTail<T>>::get();
+				let queue_size = <MessageQueue<T>>::decode_len().unwrap_or(0);
+				if !backlog_empty || queue_size >= threshold as usize {
+					return true
+				}
 			}
 
-			// Store these for the on_commit call at the end
-			let message_count = message_queue.len() as u32;
-			let average_payload_size = Self::average_payload_size(&message_queue);
+			// Messages moved from the backlog were submitted after the ones they replace, so
+			// this may overestimate their age, but never underestimates it.
+			match (Self::max_message_age(), <QueuedSince<T>>::get()) {
+				(Some(max_age), Some(queued_since)) => now.saturating_sub(queued_since) >= max_age,
+				_ => false,
+			}
+		}
 
-			let eth_messages: Vec<Vec<u8>> = message_queue
-				.clone()
-				.into_iter()
-				.map(|msg| ethabi::encode(&vec![msg.into()]))
-				.collect();
+		/// Whether the waiting messages of a source should be committed in block `now`,
+		/// according to its schedule.
+		fn source_due(
+			now: T::BlockNumber,
+			schedule: &CommitScheduleOf<T>,
+			pending: &PendingMessages<T::BlockNumber>,
+		) -> bool {
+			if pending.count == 0 {
+				return false
+			}
+			let on_interval = schedule
+				.interval
+				.map_or(false, |interval| interval.is_zero() || (now % interval).is_zero());
+			let over_threshold = schedule
+				.queue_size_threshold
+				.map_or(false, |threshold| pending.count >= threshold);
+			let too_old = schedule
+				.max_message_age
+				.map_or(false, |max_age| now.saturating_sub(pending.queued_since) >= max_age);
+			on_interval || over_threshold || too_old
+		}
 
+		/// Find the Merkle root of the given messages, using the ethabi-encoded messages as the
+		/// leaves of the Merkle tree. Then:
+		/// - Emit the commitment, see [`Self::emit_commitment`].
+		/// - Record the commitment so that it can be re-emitted if it is missed.
+		/// - Hold the fees paid for the messages until delivery of the commitment is confirmed.
+		fn commit_messages(messages: BoundedVec<MessageOf<T>, T::MaxMessagesPerCommit>) {
+			let fees = messages
+				.iter()
+				.fold(BalanceOf::<T>::zero(), |acc, msg| acc.saturating_add(msg.fee));
+			let nonces = Self::nonce_ranges(&messages);
+			Self::remove_pending_source_messages(&nonces);
+
+			let eth_messages = Self::encode_messages(&messages);
 			let commitment_hash =
 				merkle_root::<<T as Config>::Hashing, Vec<Vec<u8>>, Vec<u8>>(eth_messages.clone());
 
+			Self::emit_commitment(commitment_hash, messages, eth_messages);
+
+			<Commitments<T>>::insert(
+				commitment_hash,
+				CommitmentInfo { block_number: <frame_system::Pallet<T>>::block_number(), nonces },
+			);
+			if !fees.is_zero() {
+				<CommitmentFees<T>>::insert(commitment_hash, fees);
+			}
+		}
+
+		/// - Store the commitment hash on the parachain for the Ethereum light client to query.
+		/// - Persist the ethabi-encoded message bundles to off-chain storage.
+		/// - Emit an event with the commitment hash and SCALE-encoded message bundles for a
+		/// relayer to read.
+		fn emit_commitment(
+			commitment_hash: H256,
+			messages: BoundedVec<MessageOf<T>, T::MaxMessagesPerCommit>,
+			eth_messages: Vec<Vec<u8>>,
+		) {
 			let digest_item = AuxiliaryDigestItem::Commitment(commitment_hash.clone()).into();
 			<frame_system::Pallet<T>>::deposit_log(digest_item);
 
+			let block_number = <frame_system::Pallet<T>>::block_number();
+			<LatestCommitments<T>>::mutate(|latest| match latest {
+				Some((number, hashes)) if *number == block_number => hashes.push(commitment_hash),
+				_ => *latest = Some((block_number, vec![commitment_hash])),
+			});
+
+			set(commitment_hash.as_bytes(), &eth_messages.encode());
+
 			Self::deposit_event(Event::Committed {
 				hash: commitment_hash,
-				data: message_queue.to_vec(),
+				data: messages.into_inner(),
 			});
+		}
 
-			set(commitment_hash.as_bytes(), &eth_messages.encode());
+		fn encode_messages(messages: &[MessageOf<T>]) -> Vec<Vec<u8>> {
+			messages.iter().cloned().map(|msg| ethabi::encode(&vec![msg.into()])).collect()
+		}
+
+		/// Find the range of nonces committed for each source.
+		fn nonce_ranges(
+			messages: &[MessageOf<T>],
+		) -> BoundedVec<NonceRange<T::SourceId>, T::MaxMessagesPerCommit> {
+			let mut ranges: BoundedVec<NonceRange<T::SourceId>, T::MaxMessagesPerCommit> =
+				Default::default();
+			for message in messages {
+				match ranges.iter_mut().find(|range| range.source_id == message.source_id) {
+					Some(range) => range.last = message.nonce,
+					// Cannot fail, as there are no more sources than messages
+					None => {
+						let _ = ranges.try_push(NonceRange {
+							source_id: message.source_id.clone(),
+							first: message.nonce,
+							last: message.nonce,
+						});
+					},
+				}
+			}
+			ranges
+		}
+
+		/// Stop tracking the committed messages of sources with their own schedule. As for
+		/// [`QueuedSince`], the age of the remaining messages may be overestimated.
+		fn remove_pending_source_messages(nonces: &[NonceRange<T::SourceId>]) {
+			for range in nonces {
+				<PendingSourceMessages<T>>::mutate_exists(&range.source_id, |pending| {
+					if let Some(info) = pending {
+						let committed = range.last.saturating_sub(range.first).saturating_add(1);
+						info.count =
+							info.count.saturating_sub(committed.min(u32::MAX as u64) as u32);
+						if info.count == 0 {
+							*pending = None;
+						}
+					}
+				});
+			}
+		}
+
+		/// Number of messages waiting to be committed, including those in the backlog.
+		pub fn pending_messages() -> u64 {
+			let queued = <MessageQueue<T>>::decode_len().unwrap_or(0) as u64;
+			(<BacklogHead<T>>::get()..<BacklogTail<T>>::get()).fold(queued, |acc, page| {
+				acc.saturating_add(<MessageBacklog<T>>::decode_len(page).unwrap_or(0) as u64)
+			})
+		}
+
+		/// Nonce which will be assigned to the next message from `source_id`.
+		pub fn next_nonce(source_id: &T::SourceId) -> u64 {
+			<Nonce<T>>::get(source_id)
+		}
+
+		/// Hash of the latest commitment and the block in which it was emitted.
+		pub fn latest_commitment() -> Option<(H256, T::BlockNumber)> {
+			<LatestCommitments<T>>::get()
+				.and_then(|(block_number, hashes)| Some((*hashes.last()?, block_number)))
+		}
+
+		/// Hashes of the commitments emitted in the current block, in order.
+		pub fn block_commitments() -> Vec<H256> {
+			match <LatestCommitments<T>>::get() {
+				Some((block_number, hashes))
+					if block_number == <frame_system::Pallet<T>>::block_number() =>
+					hashes,
+				_ => Vec::new(),
+			}
+		}
+
+		/// Add a message to the last page of the backlog, starting a new page if it is full.
+		fn append_to_backlog(message: MessageOf<T>) -> DispatchResult {
+			let head = <BacklogHead<T>>::get();
+			let tail = <BacklogTail<T>>::get();
+
+			if tail > head &&
+				<MessageBacklog<T>>::decode_len(tail - 1).unwrap_or(0) <
+					T::MaxMessagesPerCommit::get() as usize
+			{
+				return <MessageBacklog<T>>::try_append(tail - 1, message)
+					.map_err(|_| Error::<T>::QueueSizeLimitReached.into())
+			}
+
+			let page: BoundedVec<_, _> =
+				vec![message].try_into().map_err(|_| Error::<T>::QueueSizeLimitReached)?;
+			<MessageBacklog<T>>::insert(tail, page);
+			<BacklogTail<T>>::put(tail.checked_add(1).ok_or(Error::<T>::Overflow)?);
 
-			return T::WeightInfo::on_commit(message_count, average_payload_size)
+			Ok(())
+		}
+
+		/// Remove the oldest page from the backlog.
+		fn take_backlog_page() -> Option<BoundedVec<MessageOf<T>, T::MaxMessagesPerCommit>> {
+			let head = <BacklogHead<T>>::get();
+			if head == <BacklogTail<T>>::get() {
+				return None
+			}
+
+			<BacklogHead<T>>::put(head + 1);
+			Some(<MessageBacklog<T>>::take(head))
+		}
+
+		/// The treasury account holding message fees.
+		pub fn account_id() -> T::AccountId {
+			T::PalletId::get().into_account_truncating()
 		}
 
-		fn average_payload_size(
-			messages: &[Message<T::SourceId, T::MaxMessagePayloadSize>],
-		) -> u32 {
+		fn average_payload_size(messages: &[MessageOf<T>]) -> u32 {
 			let sum: usize = messages.iter().fold(0, |acc, x| acc + (*x).payload.len());
 			// We overestimate message payload size rather than underestimate.
 			// So add 1 here to account for integer division truncation.
@@ -248,3 +976,60 @@ pub mod pallet {
 		}
 	}
 }
+
+/// Ensures that the origin is signed, and sends messages from the signer's own account, which
+/// also pays the message fee.
+pub struct EnsureSignedSource<AccountId>(PhantomData<AccountId>);
+
+impl<O, AccountId> EnsureOrigin<O> for EnsureSignedSource<AccountId>
+where
+	O: Into<Result<frame_system::RawOrigin<AccountId>, O>>
+		+ From<frame_system::RawOrigin<AccountId>>,
+	AccountId: Clone + Decode,
+{
+	type Success = (AccountId, AccountId);
+
+	fn try_origin(o: O) -> Result<Self::Success, O> {
+		o.into().and_then(|o| match o {
+			frame_system::RawOrigin::Signed(who) => Ok((who.clone(), who)),
+			o => Err(O::from(o)),
+		})
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn try_successful_origin() -> Result<O, ()> {
+		let who = AccountId::decode(&mut sp_runtime::traits::TrailingZeroInput::zeroes())
+			.map_err(|_| ())?;
+		Ok(O::from(frame_system::RawOrigin::Signed(who)))
+	}
+}
+
+/// Ensures that the origin is signed by one of the [`Relayers`] registered by governance.
+pub struct EnsureRelayer<T>(PhantomData<T>);
+
+impl<T: Config> EnsureOrigin<T::RuntimeOrigin> for EnsureRelayer<T> {
+	type Success = T::AccountId;
+
+	fn try_origin(o: T::RuntimeOrigin) -> Result<Self::Success, T::RuntimeOrigin> {
+		o.into().and_then(|o| match o {
+			frame_system::RawOrigin::Signed(who) if <Relayers<T>>::contains_key(&who) => Ok(who),
+			o => Err(T::RuntimeOrigin::from(o)),
+		})
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn try_successful_origin() -> Result<T::RuntimeOrigin, ()> {
+		let relayer: T::AccountId = frame_benchmarking::account("relayer", 0, 0);
+		<Relayers<T>>::insert(&relayer, ());
+		Ok(frame_system::RawOrigin::Signed(relayer).into())
+	}
+}
+
+impl<T> OutboundChannel<T::AccountId> for Pallet<T>
+where
+	T: Config<SourceId = <T as frame_system::Config>::AccountId>,
+{
+	fn submit(who: &T::AccountId, payload: &[u8]) -> Result<u64, DispatchError> {
+		Self::submit_message(who, who, payload)
+	}
+}
@@ -2,9 +2,11 @@ use super::*;
 
 use frame_support::{
 	assert_noop, assert_ok, parameter_types,
-	traits::{Everything, GenesisBuild, OnInitialize},
+	traits::{Everything, GenesisBuild, OnIdle, OnInitialize},
+	PalletId,
 };
-use sp_core::H256;
+use frame_system::{EventRecord, Phase};
+use sp_core::{H160, H256};
 use sp_keyring::AccountKeyring as Keyring;
 use sp_runtime::{
 	testing::Header,
@@ -13,6 +15,9 @@ use sp_runtime::{
 };
 use sp_std::convert::From;
 
+use snowbridge_core::Proof;
+use snowbridge_ethereum::{Header as EthereumHeader, U256};
+
 use crate::outbound as basic_outbound_channel;
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -25,12 +30,14 @@ frame_support::construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Pallet, Call, Storage, Event<T>},
-		BasicOutboundChannel: basic_outbound_channel::{Pallet, Config<T>, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		BasicOutboundChannel: basic_outbound_channel::{Pallet, Call, Config<T>, Storage, Event<T>},
 	}
 );
 
 pub type Signature = MultiSignature;
 pub type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::AccountId;
+pub type Balance = u128;
 
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
@@ -54,7 +61,7 @@ impl frame_system::Config for Test {
 	type DbWeight = ();
 	type Version = ();
 	type PalletInfo = PalletInfo;
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<Balance>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
@@ -63,9 +70,55 @@ impl frame_system::Config for Test {
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = Balance;
+	type RuntimeEvent = RuntimeEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+// Mock verifier
+pub struct MockVerifier;
+
+impl Verifier for MockVerifier {
+	fn verify(message: &EthereumMessage) -> Result<(Log, u64), DispatchError> {
+		let log: Log = rlp::decode(&message.data).unwrap();
+		Ok((log, 0))
+	}
+
+	fn initialize_storage(_: Vec<EthereumHeader>, _: U256, _: u8) -> Result<(), &'static str> {
+		Ok(())
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn initialize_benchmark_messages(logs: Vec<Vec<u8>>, proof_size: u32) -> Vec<EthereumMessage> {
+		logs.into_iter()
+			.map(|log| EthereumMessage {
+				data: log,
+				proof: Proof {
+					block_hash: Default::default(),
+					tx_index: Default::default(),
+					data: (vec![], vec![vec![0u8; proof_size as usize]]),
+				},
+			})
+			.collect()
+	}
+}
+
 parameter_types! {
 	pub const MaxMessagePayloadSize: u32 = 256;
 	pub const MaxMessagesPerCommit: u32 = 20;
+	pub const MaxScheduledSources: u32 = 2;
+	pub const OutboundChannelPalletId: PalletId = PalletId(*b"s/basout");
 }
 
 impl basic_outbound_channel::Config for Test {
@@ -74,14 +127,34 @@ impl basic_outbound_channel::Config for Test {
 	type Hashing = Keccak256;
 	type MaxMessagePayloadSize = MaxMessagePayloadSize;
 	type MaxMessagesPerCommit = MaxMessagesPerCommit;
+	type MaxScheduledSources = MaxScheduledSources;
+	type SubmitOrigin = EnsureSignedSource<AccountId>;
+	type Currency = Balances;
+	type PalletId = OutboundChannelPalletId;
+	type DeliveryOrigin = EnsureRelayer<Test>;
+	type Verifier = MockVerifier;
 	type WeightInfo = ();
 }
 
+const FEE: Balance = 10;
+
+const INBOUND_CHANNEL: [u8; 20] = [1u8; 20];
+
 pub fn new_tester() -> sp_io::TestExternalities {
 	let mut storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
 
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(Keyring::Alice.into(), 1_000_000), (Keyring::Bob.into(), 1_000_000)],
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+
 	let config: basic_outbound_channel::GenesisConfig<Test> =
-		basic_outbound_channel::GenesisConfig { interval: 1u64 };
+		basic_outbound_channel::GenesisConfig {
+			interval: 1u64,
+			fee: FEE,
+			inbound_channel: INBOUND_CHANNEL.into(),
+		};
 	config.assimilate_storage(&mut storage).unwrap();
 
 	let mut ext: sp_io::TestExternalities = storage.into();
@@ -90,6 +163,37 @@ pub fn new_tester() -> sp_io::TestExternalities {
 	ext
 }
 
+// Build a proof of the `MessageDispatched` event emitted by `channel` on Ethereum for the
+// message from `source_id` with the given nonce.
+fn dispatched_proof(channel: H160, source_id: &AccountId, nonce: u64) -> EthereumMessage {
+	let data = ethabi::encode(&[Token::FixedBytes(source_id.encode()), Token::Uint(nonce.into())]);
+
+	let mut log = rlp::RlpStream::new_list(3);
+	log.append(&channel.as_bytes().to_vec());
+	log.begin_list(1);
+	log.append(&keccak_256(b"MessageDispatched(bytes32,uint64)").to_vec());
+	log.append(&data);
+
+	EthereumMessage {
+		data: log.out().to_vec(),
+		proof: Proof {
+			block_hash: Default::default(),
+			tx_index: Default::default(),
+			data: Default::default(),
+		},
+	}
+}
+
+// Build the proofs of delivery of the commitment with the given hash.
+fn delivery_proofs(hash: H256) -> Vec<EthereumMessage> {
+	<Commitments<Test>>::get(hash)
+		.unwrap()
+		.nonces
+		.iter()
+		.map(|range| dispatched_proof(INBOUND_CHANNEL.into(), &range.source_id, range.last))
+		.collect()
+}
+
 fn run_to_block(n: u64) {
 	while System::block_number() < n {
 		System::set_block_number(System::block_number() + 1);
@@ -103,7 +207,7 @@ fn test_submit() {
 	new_tester().execute_with(|| {
 		let source_id: &AccountId = &Keyring::Bob.into();
 
-		assert_ok!(BasicOutboundChannel::submit(source_id, &vec![0, 1, 2]));
+		assert_ok!(BasicOutboundChannel::submit_message(source_id, source_id, &vec![0, 1, 2]));
 
 		assert_eq!(<Nonce<Test>>::get(source_id), 1);
 		assert_eq!(<MessageQueue<Test>>::get().len(), 1);
@@ -111,18 +215,134 @@ fn test_submit() {
 }
 
 #[test]
-fn test_submit_exceeds_queue_limit() {
+fn test_submit_extrinsic() {
 	new_tester().execute_with(|| {
-		let source_id: &AccountId = &Keyring::Bob.into();
+		let sender: AccountId = Keyring::Bob.into();
 
-		let max_messages = MaxMessagesPerCommit::get();
-		(0..max_messages)
-			.for_each(|_| BasicOutboundChannel::submit(source_id, &vec![0, 1, 2]).unwrap());
+		assert_ok!(BasicOutboundChannel::submit(
+			RuntimeOrigin::signed(sender.clone()),
+			vec![0, 1, 2]
+		));
+		assert_ok!(BasicOutboundChannel::submit(RuntimeOrigin::signed(sender.clone()), vec![3, 4]));
+
+		assert_eq!(<Nonce<Test>>::get(&sender), 2);
+		assert_eq!(<MessageQueue<Test>>::get().len(), 2);
+		assert_eq!(
+			System::events().last(),
+			Some(&EventRecord {
+				phase: Phase::Initialization,
+				event: RuntimeEvent::BasicOutboundChannel(Event::MessageAccepted {
+					source_id: sender.clone(),
+					nonce: 1,
+					fee: FEE
+				}),
+				topics: vec![],
+			})
+		);
+	});
+}
 
+#[test]
+fn test_submit_extrinsic_requires_signed_origin() {
+	new_tester().execute_with(|| {
 		assert_noop!(
-			BasicOutboundChannel::submit(source_id, &vec![0, 1, 2]),
-			Error::<Test>::QueueSizeLimitReached,
+			BasicOutboundChannel::submit(RuntimeOrigin::none(), vec![0, 1, 2]),
+			DispatchError::BadOrigin,
 		);
+	});
+}
+
+#[test]
+fn test_submit_extrinsic_overflows_into_backlog() {
+	new_tester().execute_with(|| {
+		let sender: AccountId = Keyring::Bob.into();
+
+		let max_messages = MaxMessagesPerCommit::get();
+		(0..max_messages).for_each(|_| {
+			assert_ok!(BasicOutboundChannel::submit(
+				RuntimeOrigin::signed(sender.clone()),
+				vec![0, 1, 2]
+			));
+		});
+
+		assert_ok!(BasicOutboundChannel::submit(RuntimeOrigin::signed(sender), vec![0, 1, 2]));
+
+		assert_eq!(<MessageQueue<Test>>::get().len(), max_messages as usize);
+		assert_eq!(<MessageBacklog<Test>>::get(0).len(), 1);
+	});
+}
+
+#[test]
+fn test_submit_overflows_into_backlog() {
+	new_tester().execute_with(|| {
+		let source_id: &AccountId = &Keyring::Bob.into();
+
+		let max_messages = MaxMessagesPerCommit::get();
+		(0..2 * max_messages + 1).for_each(|_| {
+			assert_ok!(BasicOutboundChannel::submit_message(source_id, source_id, &vec![0, 1, 2]));
+		});
+
+		assert_eq!(<MessageQueue<Test>>::get().len(), max_messages as usize);
+		assert_eq!(<MessageBacklog<Test>>::get(0).len(), max_messages as usize);
+		assert_eq!(<MessageBacklog<Test>>::get(1).len(), 1);
+		assert_eq!(<BacklogHead<Test>>::get(), 0);
+		assert_eq!(<BacklogTail<Test>>::get(), 2);
+		assert_eq!(<Nonce<Test>>::get(source_id), 2 * max_messages as u64 + 1);
+	})
+}
+
+#[test]
+fn test_commit_drains_backlog() {
+	new_tester().execute_with(|| {
+		let source_id: &AccountId = &Keyring::Bob.into();
+
+		let max_messages = MaxMessagesPerCommit::get();
+		(0..2 * max_messages + 1).for_each(|_| {
+			assert_ok!(BasicOutboundChannel::submit_message(source_id, source_id, &vec![0, 1, 2]));
+		});
+
+		run_to_block(2);
+		BasicOutboundChannel::commit(Weight::MAX);
+
+		let commitments = System::events()
+			.into_iter()
+			.filter_map(|record| match record.event {
+				RuntimeEvent::BasicOutboundChannel(Event::Committed { data, .. }) => Some(data),
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(commitments.len(), 3);
+		assert_eq!(commitments[0].first().map(|m| m.nonce), Some(0));
+		assert_eq!(commitments[1].first().map(|m| m.nonce), Some(max_messages as u64));
+		assert_eq!(commitments[2].first().map(|m| m.nonce), Some(2 * max_messages as u64));
+
+		assert_eq!(<MessageQueue<Test>>::get().len(), 0);
+		assert_eq!(<BacklogHead<Test>>::get(), <BacklogTail<Test>>::get());
+	})
+}
+
+#[test]
+fn test_commit_respects_weight_limit() {
+	new_tester().execute_with(|| {
+		let source_id: &AccountId = &Keyring::Bob.into();
+
+		let max_messages = MaxMessagesPerCommit::get();
+		(0..2 * max_messages).for_each(|_| {
+			assert_ok!(BasicOutboundChannel::submit_message(source_id, source_id, &vec![0, 1, 2]));
+		});
+
+		// Only enough weight for a single commitment
+		let weight_limit = <() as WeightInfo>::on_commit(max_messages, 4);
+
+		run_to_block(2);
+		assert_eq!(BasicOutboundChannel::commit(weight_limit), weight_limit);
+
+		// The next page of the backlog is waiting to be committed
+		assert_eq!(<MessageQueue<Test>>::get().len(), max_messages as usize);
+		assert_eq!(<BacklogHead<Test>>::get(), <BacklogTail<Test>>::get());
+
+		BasicOutboundChannel::commit(weight_limit);
+		assert_eq!(<MessageQueue<Test>>::get().len(), 0);
 	})
 }
 
@@ -139,7 +359,7 @@ fn test_submit_exceeds_payload_limit() {
 		payload.push(10);
 
 		assert_noop!(
-			BasicOutboundChannel::submit(source_id, payload.as_slice()),
+			BasicOutboundChannel::submit_message(source_id, source_id, payload.as_slice()),
 			Error::<Test>::PayloadTooLarge,
 		);
 	})
@@ -150,7 +370,7 @@ fn test_commit_single_user() {
 	new_tester().execute_with(|| {
 		let source_id: &AccountId = &Keyring::Bob.into();
 
-		assert_ok!(BasicOutboundChannel::submit(source_id, &vec![0, 1, 2]));
+		assert_ok!(BasicOutboundChannel::submit_message(source_id, source_id, &vec![0, 1, 2]));
 		run_to_block(2);
 		BasicOutboundChannel::commit(Weight::MAX);
 
@@ -165,8 +385,8 @@ fn test_commit_multi_user() {
 		let alice: &AccountId = &Keyring::Alice.into();
 		let bob: &AccountId = &Keyring::Bob.into();
 
-		assert_ok!(BasicOutboundChannel::submit(alice, &vec![0, 1, 2]));
-		assert_ok!(BasicOutboundChannel::submit(bob, &vec![0, 1, 2]));
+		assert_ok!(BasicOutboundChannel::submit_message(alice, alice, &vec![0, 1, 2]));
+		assert_ok!(BasicOutboundChannel::submit_message(bob, bob, &vec![0, 1, 2]));
 		run_to_block(2);
 		BasicOutboundChannel::commit(Weight::MAX);
 
@@ -175,3 +395,669 @@ fn test_commit_multi_user() {
 		assert_eq!(<MessageQueue<Test>>::get().len(), 0);
 	})
 }
+
+#[test]
+fn test_submit_charges_fee() {
+	new_tester().execute_with(|| {
+		let sender: AccountId = Keyring::Bob.into();
+		let treasury = BasicOutboundChannel::account_id();
+
+		assert_ok!(BasicOutboundChannel::submit(
+			RuntimeOrigin::signed(sender.clone()),
+			vec![0, 1, 2]
+		));
+
+		assert_eq!(Balances::free_balance(&sender), 1_000_000 - FEE);
+		assert_eq!(Balances::free_balance(&treasury), FEE);
+	});
+}
+
+#[test]
+fn test_submit_with_insufficient_balance() {
+	new_tester().execute_with(|| {
+		let sender: AccountId = Keyring::Charlie.into();
+
+		assert_noop!(
+			BasicOutboundChannel::submit(RuntimeOrigin::signed(sender), vec![0, 1, 2]),
+			pallet_balances::Error::<Test>::InsufficientBalance,
+		);
+	});
+}
+
+#[test]
+fn test_set_fee() {
+	new_tester().execute_with(|| {
+		assert_ok!(BasicOutboundChannel::set_fee(RuntimeOrigin::root(), 42));
+		assert_eq!(<Fee<Test>>::get(), 42);
+
+		assert_noop!(
+			BasicOutboundChannel::set_fee(RuntimeOrigin::signed(Keyring::Bob.into()), 42),
+			DispatchError::BadOrigin,
+		);
+	});
+}
+
+#[test]
+fn test_set_schedule() {
+	new_tester().execute_with(|| {
+		assert_ok!(BasicOutboundChannel::set_interval(RuntimeOrigin::root(), 5));
+		assert_eq!(<Interval<Test>>::get(), 5);
+		System::assert_last_event(RuntimeEvent::BasicOutboundChannel(Event::IntervalUpdated {
+			interval: 5,
+		}));
+
+		assert_ok!(BasicOutboundChannel::set_queue_size_threshold(RuntimeOrigin::root(), Some(3)));
+		assert_eq!(<QueueSizeThreshold<Test>>::get(), Some(3));
+		System::assert_last_event(RuntimeEvent::BasicOutboundChannel(
+			Event::QueueSizeThresholdUpdated { threshold: Some(3) },
+		));
+
+		assert_ok!(BasicOutboundChannel::set_max_message_age(RuntimeOrigin::root(), Some(2)));
+		assert_eq!(<MaxMessageAge<Test>>::get(), Some(2));
+		System::assert_last_event(RuntimeEvent::BasicOutboundChannel(
+			Event::MaxMessageAgeUpdated { max_age: Some(2) },
+		));
+
+		assert_ok!(BasicOutboundChannel::set_max_message_age(RuntimeOrigin::root(), None));
+		assert_eq!(<MaxMessageAge<Test>>::get(), None);
+
+		let bob: AccountId = Keyring::Bob.into();
+		assert_noop!(
+			BasicOutboundChannel::set_interval(RuntimeOrigin::signed(bob.clone()), 5),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			BasicOutboundChannel::set_queue_size_threshold(
+				RuntimeOrigin::signed(bob.clone()),
+				None
+			),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			BasicOutboundChannel::set_max_message_age(RuntimeOrigin::signed(bob), None),
+			DispatchError::BadOrigin,
+		);
+	});
+}
+
+#[test]
+fn test_commit_on_interval() {
+	new_tester().execute_with(|| {
+		let bob: &AccountId = &Keyring::Bob.into();
+
+		assert_ok!(BasicOutboundChannel::set_interval(RuntimeOrigin::root(), 3));
+		assert_ok!(BasicOutboundChannel::submit_message(bob, bob, &vec![0, 1, 2]));
+
+		BasicOutboundChannel::on_idle(2, Weight::MAX);
+		assert_eq!(<MessageQueue<Test>>::get().len(), 1);
+
+		BasicOutboundChannel::on_idle(3, Weight::MAX);
+		assert_eq!(<MessageQueue<Test>>::get().len(), 0);
+		assert_eq!(<QueuedSince<Test>>::get(), None);
+	});
+}
+
+#[test]
+fn test_commit_on_queue_size_threshold() {
+	new_tester().execute_with(|| {
+		let bob: &AccountId = &Keyring::Bob.into();
+
+		assert_ok!(BasicOutboundChannel::set_interval(RuntimeOrigin::root(), 100));
+		assert_ok!(BasicOutboundChannel::set_queue_size_threshold(RuntimeOrigin::root(), Some(2)));
+
+		assert_ok!(BasicOutboundChannel::submit_message(bob, bob, &vec![0, 1, 2]));
+		BasicOutboundChannel::on_idle(2, Weight::MAX);
+		assert_eq!(<MessageQueue<Test>>::get().len(), 1);
+
+		assert_ok!(BasicOutboundChannel::submit_message(bob, bob, &vec![0, 1, 2]));
+		BasicOutboundChannel::on_idle(3, Weight::MAX);
+		assert_eq!(<MessageQueue<Test>>::get().len(), 0);
+	});
+}
+
+#[test]
+fn test_commit_on_max_message_age() {
+	new_tester().execute_with(|| {
+		let bob: &AccountId = &Keyring::Bob.into();
+
+		assert_ok!(BasicOutboundChannel::set_interval(RuntimeOrigin::root(), 100));
+		assert_ok!(BasicOutboundChannel::set_max_message_age(RuntimeOrigin::root(), Some(2)));
+
+		// Submitted in block 1
+		assert_ok!(BasicOutboundChannel::submit_message(bob, bob, &vec![0, 1, 2]));
+		assert_eq!(<QueuedSince<Test>>::get(), Some(1));
+
+		BasicOutboundChannel::on_idle(2, Weight::MAX);
+		assert_eq!(<MessageQueue<Test>>::get().len(), 1);
+
+		BasicOutboundChannel::on_idle(3, Weight::MAX);
+		assert_eq!(<MessageQueue<Test>>::get().len(), 0);
+		assert_eq!(<QueuedSince<Test>>::get(), None);
+	});
+}
+
+#[test]
+fn test_commit_on_source_schedule() {
+	new_tester().execute_with(|| {
+		let alice: &AccountId = &Keyring::Alice.into();
+		let bob: &AccountId = &Keyring::Bob.into();
+
+		assert_ok!(BasicOutboundChannel::set_interval(RuntimeOrigin::root(), 100));
+		assert_ok!(BasicOutboundChannel::set_source_schedule(
+			RuntimeOrigin::root(),
+			bob.clone(),
+			Some(CommitSchedule { max_message_age: Some(2), ..Default::default() }),
+		));
+
+		// Messages from sources without a schedule wait for the global schedule
+		assert_ok!(BasicOutboundChannel::submit_message(alice, alice, &vec![0, 1, 2]));
+		BasicOutboundChannel::on_idle(5, Weight::MAX);
+		assert_eq!(<MessageQueue<Test>>::get().len(), 1);
+		assert_eq!(<PendingSourceMessages<Test>>::get(alice), None);
+
+		System::set_block_number(5);
+		assert_ok!(BasicOutboundChannel::submit_message(bob, bob, &vec![0, 1, 2]));
+		assert_eq!(
+			<PendingSourceMessages<Test>>::get(bob),
+			Some(PendingMessages { count: 1, queued_since: 5 })
+		);
+
+		BasicOutboundChannel::on_idle(6, Weight::MAX);
+		assert_eq!(<MessageQueue<Test>>::get().len(), 2);
+
+		// The messages of all sources are committed with the message from bob
+		BasicOutboundChannel::on_idle(7, Weight::MAX);
+		assert_eq!(<MessageQueue<Test>>::get().len(), 0);
+		assert_eq!(<PendingSourceMessages<Test>>::get(bob), None);
+	});
+}
+
+#[test]
+fn test_commit_on_source_queue_size_threshold_and_interval() {
+	new_tester().execute_with(|| {
+		let alice: &AccountId = &Keyring::Alice.into();
+		let bob: &AccountId = &Keyring::Bob.into();
+
+		assert_ok!(BasicOutboundChannel::set_interval(RuntimeOrigin::root(), 100));
+		assert_ok!(BasicOutboundChannel::set_source_schedule(
+			RuntimeOrigin::root(),
+			alice.clone(),
+			Some(CommitSchedule { interval: Some(4), ..Default::default() }),
+		));
+		assert_ok!(BasicOutboundChannel::set_source_schedule(
+			RuntimeOrigin::root(),
+			bob.clone(),
+			Some(CommitSchedule { queue_size_threshold: Some(2), ..Default::default() }),
+		));
+
+		assert_ok!(BasicOutboundChannel::submit_message(bob, bob, &vec![0, 1, 2]));
+		BasicOutboundChannel::on_idle(2, Weight::MAX);
+		assert_eq!(<MessageQueue<Test>>::get().len(), 1);
+
+		assert_ok!(BasicOutboundChannel::submit_message(bob, bob, &vec![0, 1, 2]));
+		BasicOutboundChannel::on_idle(3, Weight::MAX);
+		assert_eq!(<MessageQueue<Test>>::get().len(), 0);
+
+		// The interval of alice only applies once alice has messages waiting
+		BasicOutboundChannel::on_idle(4, Weight::MAX);
+		assert_ok!(BasicOutboundChannel::submit_message(alice, alice, &vec![0, 1, 2]));
+		BasicOutboundChannel::on_idle(5, Weight::MAX);
+		assert_eq!(<MessageQueue<Test>>::get().len(), 1);
+
+		BasicOutboundChannel::on_idle(8, Weight::MAX);
+		assert_eq!(<MessageQueue<Test>>::get().len(), 0);
+	});
+}
+
+#[test]
+fn test_set_source_schedule() {
+	new_tester().execute_with(|| {
+		let alice: AccountId = Keyring::Alice.into();
+		let bob: AccountId = Keyring::Bob.into();
+		let ferdie: AccountId = Keyring::Ferdie.into();
+		let schedule = CommitSchedule { max_message_age: Some(2), ..Default::default() };
+
+		assert_noop!(
+			BasicOutboundChannel::set_source_schedule(
+				RuntimeOrigin::signed(bob.clone()),
+				bob.clone(),
+				Some(schedule.clone())
+			),
+			DispatchError::BadOrigin,
+		);
+
+		assert_ok!(BasicOutboundChannel::set_source_schedule(
+			RuntimeOrigin::root(),
+			bob.clone(),
+			Some(schedule.clone())
+		));
+		System::assert_last_event(RuntimeEvent::BasicOutboundChannel(
+			Event::SourceScheduleUpdated {
+				source_id: bob.clone(),
+				schedule: Some(schedule.clone()),
+			},
+		));
+		assert_ok!(BasicOutboundChannel::set_source_schedule(
+			RuntimeOrigin::root(),
+			alice.clone(),
+			Some(schedule.clone())
+		));
+
+		// Updating the schedule of a source does not count against the limit
+		assert_ok!(BasicOutboundChannel::set_source_schedule(
+			RuntimeOrigin::root(),
+			alice.clone(),
+			Some(CommitSchedule::default())
+		));
+		assert_noop!(
+			BasicOutboundChannel::set_source_schedule(
+				RuntimeOrigin::root(),
+				ferdie,
+				Some(schedule)
+			),
+			Error::<Test>::TooManyScheduledSources,
+		);
+
+		assert_ok!(BasicOutboundChannel::submit_message(&bob, &bob, &vec![0, 1, 2]));
+		assert!(<PendingSourceMessages<Test>>::contains_key(&bob));
+
+		assert_ok!(BasicOutboundChannel::set_source_schedule(
+			RuntimeOrigin::root(),
+			bob.clone(),
+			None
+		));
+		assert_eq!(<SourceSchedules<Test>>::get(&bob), None);
+		assert!(!<PendingSourceMessages<Test>>::contains_key(&bob));
+		System::assert_last_event(RuntimeEvent::BasicOutboundChannel(
+			Event::SourceScheduleUpdated { source_id: bob, schedule: None },
+		));
+	});
+}
+
+#[test]
+fn test_confirm_delivery_and_claim_rewards() {
+	new_tester().execute_with(|| {
+		let alice: AccountId = Keyring::Alice.into();
+		let bob: AccountId = Keyring::Bob.into();
+		let relayer: AccountId = Keyring::Ferdie.into();
+
+		assert_ok!(BasicOutboundChannel::submit(RuntimeOrigin::signed(alice), vec![0, 1, 2]));
+		assert_ok!(BasicOutboundChannel::submit(RuntimeOrigin::signed(bob), vec![0, 1, 2]));
+		BasicOutboundChannel::commit(Weight::MAX);
+
+		let (hash, fees) = <CommitmentFees<Test>>::iter().next().unwrap();
+		assert_eq!(fees, 2 * FEE);
+
+		assert_ok!(BasicOutboundChannel::add_relayer(RuntimeOrigin::root(), relayer.clone()));
+		assert_ok!(BasicOutboundChannel::confirm_delivery(
+			RuntimeOrigin::signed(relayer.clone()),
+			hash,
+			delivery_proofs(hash)
+		));
+		assert_eq!(<CommitmentFees<Test>>::get(hash), None);
+		assert_eq!(<Commitments<Test>>::get(hash), None);
+		assert_eq!(<RelayerRewards<Test>>::get(&relayer), 2 * FEE);
+
+		assert_ok!(BasicOutboundChannel::claim_rewards(RuntimeOrigin::signed(relayer.clone())));
+		assert_eq!(Balances::free_balance(&relayer), 2 * FEE);
+		assert_eq!(Balances::free_balance(&BasicOutboundChannel::account_id()), 0);
+		assert_eq!(<RelayerRewards<Test>>::get(&relayer), 0);
+	});
+}
+
+#[test]
+fn test_confirm_delivery_by_relayer() {
+	new_tester().execute_with(|| {
+		let bob: AccountId = Keyring::Bob.into();
+		let relayer: AccountId = Keyring::Ferdie.into();
+
+		assert_ok!(BasicOutboundChannel::submit(RuntimeOrigin::signed(bob), vec![0, 1, 2]));
+		BasicOutboundChannel::commit(Weight::MAX);
+		let (hash, _) = <CommitmentFees<Test>>::iter().next().unwrap();
+
+		assert_noop!(
+			BasicOutboundChannel::confirm_delivery(
+				RuntimeOrigin::signed(relayer.clone()),
+				hash,
+				delivery_proofs(hash)
+			),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			BasicOutboundChannel::confirm_delivery(
+				RuntimeOrigin::root(),
+				hash,
+				delivery_proofs(hash)
+			),
+			DispatchError::BadOrigin,
+		);
+
+		assert_ok!(BasicOutboundChannel::add_relayer(RuntimeOrigin::root(), relayer.clone()));
+		System::assert_last_event(RuntimeEvent::BasicOutboundChannel(Event::RelayerAdded {
+			relayer: relayer.clone(),
+		}));
+
+		assert_ok!(BasicOutboundChannel::confirm_delivery(
+			RuntimeOrigin::signed(relayer.clone()),
+			hash,
+			delivery_proofs(hash)
+		));
+		assert_eq!(<RelayerRewards<Test>>::get(&relayer), FEE);
+	});
+}
+
+#[test]
+fn test_add_and_remove_relayer() {
+	new_tester().execute_with(|| {
+		let relayer: AccountId = Keyring::Ferdie.into();
+
+		assert_noop!(
+			BasicOutboundChannel::add_relayer(
+				RuntimeOrigin::signed(relayer.clone()),
+				relayer.clone()
+			),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			BasicOutboundChannel::remove_relayer(RuntimeOrigin::root(), relayer.clone()),
+			Error::<Test>::UnknownRelayer,
+		);
+
+		assert_ok!(BasicOutboundChannel::add_relayer(RuntimeOrigin::root(), relayer.clone()));
+		assert!(<Relayers<Test>>::contains_key(&relayer));
+
+		assert_ok!(BasicOutboundChannel::remove_relayer(RuntimeOrigin::root(), relayer.clone()));
+		assert!(!<Relayers<Test>>::contains_key(&relayer));
+		System::assert_last_event(RuntimeEvent::BasicOutboundChannel(Event::RelayerRemoved {
+			relayer: relayer.clone(),
+		}));
+
+		assert_noop!(
+			BasicOutboundChannel::confirm_delivery(
+				RuntimeOrigin::signed(relayer),
+				H256::repeat_byte(1),
+				vec![]
+			),
+			DispatchError::BadOrigin,
+		);
+	});
+}
+
+#[test]
+fn test_confirm_delivery_without_fees() {
+	new_tester().execute_with(|| {
+		let bob: AccountId = Keyring::Bob.into();
+		let relayer: AccountId = Keyring::Ferdie.into();
+
+		assert_ok!(BasicOutboundChannel::set_fee(RuntimeOrigin::root(), 0));
+		assert_ok!(BasicOutboundChannel::submit(RuntimeOrigin::signed(bob), vec![0, 1, 2]));
+		BasicOutboundChannel::commit(Weight::MAX);
+
+		let (hash, _) = last_commitment();
+		assert!(<Commitments<Test>>::contains_key(hash));
+		assert_eq!(<CommitmentFees<Test>>::get(hash), None);
+
+		assert_ok!(BasicOutboundChannel::add_relayer(RuntimeOrigin::root(), relayer.clone()));
+		assert_ok!(BasicOutboundChannel::confirm_delivery(
+			RuntimeOrigin::signed(relayer.clone()),
+			hash,
+			delivery_proofs(hash)
+		));
+		assert_eq!(<Commitments<Test>>::get(hash), None);
+		assert_eq!(<RelayerRewards<Test>>::get(&relayer), 0);
+		System::assert_last_event(RuntimeEvent::BasicOutboundChannel(Event::DeliveryConfirmed {
+			hash,
+			relayer,
+			reward: 0,
+		}));
+	});
+}
+
+fn last_commitment() -> (H256, Vec<MessageOf<Test>>) {
+	System::events()
+		.into_iter()
+		.rev()
+		.find_map(|record| match record.event {
+			RuntimeEvent::BasicOutboundChannel(Event::Committed { hash, data }) =>
+				Some((hash, data)),
+			_ => None,
+		})
+		.unwrap()
+}
+
+#[test]
+fn test_commit_records_commitment() {
+	new_tester().execute_with(|| {
+		let alice: &AccountId = &Keyring::Alice.into();
+		let bob: &AccountId = &Keyring::Bob.into();
+
+		assert_ok!(BasicOutboundChannel::submit_message(alice, alice, &vec![0, 1, 2]));
+		assert_ok!(BasicOutboundChannel::submit_message(bob, bob, &vec![0, 1, 2]));
+		assert_ok!(BasicOutboundChannel::submit_message(alice, alice, &vec![0, 1, 2]));
+		run_to_block(2);
+		BasicOutboundChannel::commit(Weight::MAX);
+
+		let (hash, _) = last_commitment();
+		assert_eq!(System::digest().logs, vec![AuxiliaryDigestItem::Commitment(hash).into()]);
+		assert_eq!(
+			<Commitments<Test>>::get(hash),
+			Some(CommitmentInfo {
+				block_number: 2,
+				nonces: vec![
+					NonceRange { source_id: alice.clone(), first: 0, last: 1 },
+					NonceRange { source_id: bob.clone(), first: 0, last: 0 },
+				]
+				.try_into()
+				.unwrap(),
+			})
+		);
+	})
+}
+
+#[test]
+fn test_commitment_queries() {
+	new_tester().execute_with(|| {
+		let bob: &AccountId = &Keyring::Bob.into();
+
+		let max_messages = MaxMessagesPerCommit::get();
+		(0..max_messages + 1).for_each(|_| {
+			assert_ok!(BasicOutboundChannel::submit_message(bob, bob, &vec![0, 1, 2]));
+		});
+		assert_eq!(BasicOutboundChannel::pending_messages(), max_messages as u64 + 1);
+		assert_eq!(BasicOutboundChannel::next_nonce(bob), max_messages as u64 + 1);
+		assert_eq!(BasicOutboundChannel::latest_commitment(), None);
+		assert_eq!(BasicOutboundChannel::block_commitments(), vec![]);
+
+		run_to_block(2);
+		BasicOutboundChannel::commit(Weight::MAX);
+
+		let (hash, _) = last_commitment();
+		assert_eq!(BasicOutboundChannel::pending_messages(), 0);
+		assert_eq!(BasicOutboundChannel::latest_commitment(), Some((hash, 2)));
+		assert_eq!(BasicOutboundChannel::block_commitments().len(), 2);
+		assert_eq!(BasicOutboundChannel::block_commitments().last(), Some(&hash));
+
+		run_to_block(3);
+		assert_eq!(BasicOutboundChannel::latest_commitment(), Some((hash, 2)));
+		assert_eq!(BasicOutboundChannel::block_commitments(), vec![]);
+	})
+}
+
+#[test]
+fn test_reemit_commitment() {
+	new_tester().execute_with(|| {
+		let bob: &AccountId = &Keyring::Bob.into();
+
+		assert_ok!(BasicOutboundChannel::submit_message(bob, bob, &vec![0, 1, 2]));
+		run_to_block(2);
+		BasicOutboundChannel::commit(Weight::MAX);
+		let (hash, messages) = last_commitment();
+
+		run_to_block(3);
+		assert_ok!(BasicOutboundChannel::reemit_commitment(
+			RuntimeOrigin::root(),
+			hash,
+			messages.clone().try_into().unwrap(),
+		));
+
+		let committed = System::events()
+			.into_iter()
+			.filter(|record| {
+				record.event ==
+					RuntimeEvent::BasicOutboundChannel(Event::Committed {
+						hash,
+						data: messages.clone(),
+					})
+			})
+			.count();
+		assert_eq!(committed, 2);
+		assert_eq!(
+			System::digest().logs.last(),
+			Some(&AuxiliaryDigestItem::Commitment(hash).into())
+		);
+		System::assert_last_event(RuntimeEvent::BasicOutboundChannel(Event::CommitmentReemitted {
+			hash,
+		}));
+	})
+}
+
+#[test]
+fn test_reemit_commitment_with_invalid_messages() {
+	new_tester().execute_with(|| {
+		let bob: &AccountId = &Keyring::Bob.into();
+
+		assert_ok!(BasicOutboundChannel::submit_message(bob, bob, &vec![0, 1, 2]));
+		run_to_block(2);
+		BasicOutboundChannel::commit(Weight::MAX);
+		let (hash, mut messages) = last_commitment();
+		messages[0].payload = vec![3, 4, 5].try_into().unwrap();
+
+		assert_noop!(
+			BasicOutboundChannel::reemit_commitment(
+				RuntimeOrigin::root(),
+				hash,
+				messages.clone().try_into().unwrap(),
+			),
+			Error::<Test>::InvalidCommitment,
+		);
+		assert_noop!(
+			BasicOutboundChannel::reemit_commitment(
+				RuntimeOrigin::root(),
+				H256::repeat_byte(1),
+				messages.clone().try_into().unwrap(),
+			),
+			Error::<Test>::UnknownCommitment,
+		);
+		assert_noop!(
+			BasicOutboundChannel::reemit_commitment(
+				RuntimeOrigin::signed(bob.clone()),
+				hash,
+				messages.try_into().unwrap(),
+			),
+			DispatchError::BadOrigin,
+		);
+	})
+}
+
+#[test]
+fn test_confirm_delivery_of_unknown_commitment() {
+	new_tester().execute_with(|| {
+		let relayer: AccountId = Keyring::Ferdie.into();
+
+		assert_noop!(
+			BasicOutboundChannel::confirm_delivery(
+				RuntimeOrigin::signed(relayer.clone()),
+				H256::repeat_byte(1),
+				vec![]
+			),
+			DispatchError::BadOrigin,
+		);
+
+		assert_ok!(BasicOutboundChannel::add_relayer(RuntimeOrigin::root(), relayer.clone()));
+		assert_noop!(
+			BasicOutboundChannel::confirm_delivery(
+				RuntimeOrigin::signed(relayer),
+				H256::repeat_byte(1),
+				vec![]
+			),
+			Error::<Test>::UnknownCommitment,
+		);
+	});
+}
+
+#[test]
+fn test_confirm_delivery_with_invalid_proofs() {
+	new_tester().execute_with(|| {
+		let alice: AccountId = Keyring::Alice.into();
+		let bob: AccountId = Keyring::Bob.into();
+		let relayer: AccountId = Keyring::Ferdie.into();
+
+		assert_ok!(BasicOutboundChannel::submit(RuntimeOrigin::signed(alice.clone()), vec![0]));
+		assert_ok!(BasicOutboundChannel::submit(RuntimeOrigin::signed(alice.clone()), vec![1]));
+		assert_ok!(BasicOutboundChannel::submit(RuntimeOrigin::signed(bob.clone()), vec![2]));
+		BasicOutboundChannel::commit(Weight::MAX);
+		let (hash, _) = <CommitmentFees<Test>>::iter().next().unwrap();
+		assert_ok!(BasicOutboundChannel::add_relayer(RuntimeOrigin::root(), relayer.clone()));
+		let origin = RuntimeOrigin::signed(relayer.clone());
+
+		let invalid_proofs = vec![
+			// Missing the proof for bob
+			vec![dispatched_proof(INBOUND_CHANNEL.into(), &alice, 2)],
+			// Only the first message from alice was dispatched
+			vec![
+				dispatched_proof(INBOUND_CHANNEL.into(), &alice, 1),
+				dispatched_proof(INBOUND_CHANNEL.into(), &bob, 1),
+			],
+			// Proofs in the wrong order
+			vec![
+				dispatched_proof(INBOUND_CHANNEL.into(), &bob, 1),
+				dispatched_proof(INBOUND_CHANNEL.into(), &alice, 2),
+			],
+			// Dispatched by another contract
+			vec![
+				dispatched_proof(H160::repeat_byte(2), &alice, 2),
+				dispatched_proof(INBOUND_CHANNEL.into(), &bob, 1),
+			],
+		];
+		for proofs in invalid_proofs {
+			assert_noop!(
+				BasicOutboundChannel::confirm_delivery(origin.clone(), hash, proofs),
+				Error::<Test>::InvalidDeliveryProof,
+			);
+		}
+
+		assert_ok!(BasicOutboundChannel::confirm_delivery(origin, hash, delivery_proofs(hash)));
+		assert_eq!(<RelayerRewards<Test>>::get(&relayer), 3 * FEE);
+	});
+}
+
+#[test]
+fn test_set_inbound_channel() {
+	new_tester().execute_with(|| {
+		let channel = H160::repeat_byte(2);
+
+		assert_noop!(
+			BasicOutboundChannel::set_inbound_channel(
+				RuntimeOrigin::signed(Keyring::Bob.into()),
+				channel
+			),
+			DispatchError::BadOrigin,
+		);
+
+		assert_ok!(BasicOutboundChannel::set_inbound_channel(RuntimeOrigin::root(), channel));
+		assert_eq!(<InboundChannel<Test>>::get(), channel);
+		System::assert_last_event(RuntimeEvent::BasicOutboundChannel(
+			Event::InboundChannelUpdated { channel },
+		));
+	});
+}
+
+#[test]
+fn test_claim_rewards_without_rewards() {
+	new_tester().execute_with(|| {
+		assert_noop!(
+			BasicOutboundChannel::claim_rewards(RuntimeOrigin::signed(Keyring::Ferdie.into())),
+			Error::<Test>::NoRewards,
+		);
+	});
+}
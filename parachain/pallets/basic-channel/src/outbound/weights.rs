@@ -1,32 +1,10 @@
-//! Autogenerated weights for basic_channel::outbound
+//! Weights for basic_channel::outbound
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-//! DATE: 2021-11-25, STEPS: `50`, REPEAT: 20, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("spec.json"), DB CACHE: 128
-
-// Executed Command:
-// target/release/snowbridge
-// benchmark
-// --chain
-// spec.json
-// --execution
-// wasm
-// --wasm-execution
-// compiled
-// --pallet
-// basic_channel::outbound
-// --extra
-// --extrinsic
-// *
-// --repeat
-// 20
-// --steps
-// 50
-// --output
-// pallets/basic-channel/src/outbound/weights.rs
-// --template
-// templates/module-weight-template.hbs
-
+//! THESE WEIGHTS ARE PLACEHOLDERS: they were estimated by hand and have not been generated with
+//! the Substrate benchmark CLI, except for `on_commit_no_messages` and the execution time of
+//! `on_commit`, which were benchmarked on 2021-11-25 before this pallet gained fees and a backlog.
+//! Regenerate this file with `scripts/benchmark.sh` on the reference hardware before relying on
+//! them.
 
 #![cfg_attr(rustfmt, rustfmt_skip)]
 #![allow(unused_parens)]
@@ -37,13 +15,60 @@ use sp_std::marker::PhantomData;
 
 /// Weight functions needed for basic_channel::outbound.
 pub trait WeightInfo {
+	fn submit(p: u32, ) -> Weight;
+	fn set_fee() -> Weight;
+	fn set_interval() -> Weight;
+	fn set_queue_size_threshold() -> Weight;
+	fn set_max_message_age() -> Weight;
+	fn confirm_delivery(n: u32, q: u32, ) -> Weight;
+	fn claim_rewards() -> Weight;
 	fn on_commit_no_messages() -> Weight;
 	fn on_commit(m: u32, p: u32, ) -> Weight;
+	fn reemit_commitment(m: u32, p: u32, ) -> Weight;
+	fn add_relayer() -> Weight;
+	fn remove_relayer() -> Weight;
+	fn set_inbound_channel() -> Weight;
+	fn set_source_schedule() -> Weight;
 }
 
 /// Weights for basic_channel::outbound using the Snowbridge node and recommended hardware.
 pub struct SnowbridgeWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SnowbridgeWeight<T> {
+	fn submit(p: u32, ) -> Weight {
+		Weight::from_ref_time(43_917_000 as u64)
+			.saturating_add(Weight::from_ref_time(2_000 as u64).saturating_mul(p as u64))
+			.saturating_add(T::DbWeight::get().reads(10 as u64))
+			.saturating_add(T::DbWeight::get().writes(7 as u64))
+	}
+	fn set_fee() -> Weight {
+		Weight::from_ref_time(9_874_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	fn set_interval() -> Weight {
+		Weight::from_ref_time(9_512_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	fn set_queue_size_threshold() -> Weight {
+		Weight::from_ref_time(9_498_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	fn set_max_message_age() -> Weight {
+		Weight::from_ref_time(9_537_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	fn confirm_delivery(n: u32, q: u32, ) -> Weight {
+		Weight::from_ref_time(17_209_000 as u64)
+			.saturating_add(Weight::from_ref_time(48_316_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(9_000 as u64).saturating_mul(n as u64).saturating_mul(q as u64))
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	fn claim_rewards() -> Weight {
+		Weight::from_ref_time(38_431_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
 	fn on_commit_no_messages() -> Weight {
 		Weight::from_ref_time(5_228_000 as u64)
 			.saturating_add(T::DbWeight::get().reads(2))
@@ -54,13 +79,73 @@ impl<T: frame_system::Config> WeightInfo for SnowbridgeWeight<T> {
 			.saturating_add(Weight::from_ref_time(100_849_000 as u64).saturating_mul(m as u64))
 			// Standard Error: 1_000
 			.saturating_add(Weight::from_ref_time(3_880_000 as u64).saturating_mul(p as u64))
-			.saturating_add(T::DbWeight::get().reads(3 as u64))
-			.saturating_add(T::DbWeight::get().writes(2 as u64))
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(5 as u64))
+	}
+	fn reemit_commitment(m: u32, p: u32, ) -> Weight {
+		Weight::from_ref_time(12_150_000 as u64)
+			.saturating_add(Weight::from_ref_time(98_516_000 as u64).saturating_mul(m as u64))
+			.saturating_add(Weight::from_ref_time(3_872_000 as u64).saturating_mul(p as u64))
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	fn add_relayer() -> Weight {
+		Weight::from_ref_time(10_112_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	fn remove_relayer() -> Weight {
+		Weight::from_ref_time(12_305_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	fn set_inbound_channel() -> Weight {
+		Weight::from_ref_time(9_402_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	fn set_source_schedule() -> Weight {
+		Weight::from_ref_time(14_820_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
 	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
+	fn submit(p: u32, ) -> Weight {
+		Weight::from_ref_time(43_917_000 as u64)
+			.saturating_add(Weight::from_ref_time(2_000 as u64).saturating_mul(p as u64))
+			.saturating_add(RocksDbWeight::get().reads(10 as u64))
+			.saturating_add(RocksDbWeight::get().writes(7 as u64))
+	}
+	fn set_fee() -> Weight {
+		Weight::from_ref_time(9_874_000 as u64)
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	fn set_interval() -> Weight {
+		Weight::from_ref_time(9_512_000 as u64)
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	fn set_queue_size_threshold() -> Weight {
+		Weight::from_ref_time(9_498_000 as u64)
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	fn set_max_message_age() -> Weight {
+		Weight::from_ref_time(9_537_000 as u64)
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	fn confirm_delivery(n: u32, q: u32, ) -> Weight {
+		Weight::from_ref_time(17_209_000 as u64)
+			.saturating_add(Weight::from_ref_time(48_316_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(9_000 as u64).saturating_mul(n as u64).saturating_mul(q as u64))
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+	fn claim_rewards() -> Weight {
+		Weight::from_ref_time(38_431_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
 	fn on_commit_no_messages() -> Weight {
 		Weight::from_ref_time(5_228_000 as u64)
 			.saturating_add(RocksDbWeight::get().reads(2))
@@ -71,7 +156,32 @@ impl WeightInfo for () {
 			.saturating_add(Weight::from_ref_time(100_849_000 as u64).saturating_mul(m as u64))
 			// Standard Error: 1_000
 			.saturating_add(Weight::from_ref_time(3_880_000 as u64).saturating_mul(p as u64))
-			.saturating_add(RocksDbWeight::get().reads(3 as u64))
-			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(5 as u64))
+	}
+	fn reemit_commitment(m: u32, p: u32, ) -> Weight {
+		Weight::from_ref_time(12_150_000 as u64)
+			.saturating_add(Weight::from_ref_time(98_516_000 as u64).saturating_mul(m as u64))
+			.saturating_add(Weight::from_ref_time(3_872_000 as u64).saturating_mul(p as u64))
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	fn add_relayer() -> Weight {
+		Weight::from_ref_time(10_112_000 as u64)
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	fn remove_relayer() -> Weight {
+		Weight::from_ref_time(12_305_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	fn set_inbound_channel() -> Weight {
+		Weight::from_ref_time(9_402_000 as u64)
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	fn set_source_schedule() -> Weight {
+		Weight::from_ref_time(14_820_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
 	}
 }
@@ -0,0 +1,15 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_core::H160;
+
+sp_api::decl_runtime_apis! {
+	pub trait DispatchApi<AccountId>
+	where
+		AccountId: Codec,
+	{
+		/// The account derived from the Ethereum account `address`, which calls sent by
+		/// `address` are dispatched as signed by.
+		fn derived_account(address: H160) -> AccountId;
+	}
+}
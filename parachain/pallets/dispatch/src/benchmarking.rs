@@ -0,0 +1,54 @@
+//! Dispatch pallet benchmarking
+use super::*;
+
+use frame_benchmarking::{benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_system::RawOrigin as SystemOrigin;
+use sp_runtime::traits::TrailingZeroInput;
+
+#[allow(unused_imports)]
+use crate::Pallet as Dispatch;
+
+benchmarks! {
+	where_clause {
+		where
+			<T as Config>::RuntimeCall: From<frame_system::Call<T>>,
+	}
+
+	// Benchmark `execute_overweight` extrinsic, excluding the weight of the call itself.
+	execute_overweight {
+		let caller: T::AccountId = whitelisted_caller();
+		let id = T::MessageId::decode(&mut TrailingZeroInput::zeroes()).unwrap();
+
+		let call: <T as Config>::RuntimeCall =
+			frame_system::Call::<T>::remark { remark: vec![] }.into();
+		let expiry = <frame_system::Pallet<T>>::block_number()
+			.saturating_add(T::OverweightMessageExpiry::get());
+		<OverweightMessages<T>>::insert(&id, OverweightMessage {
+			source: H160::repeat_byte(1),
+			payload: call.encode(),
+			expiry,
+		});
+		<OverweightMessageExpiries<T>>::insert(expiry, &id, ());
+		let weight_limit = call.get_dispatch_info().weight;
+
+	}: _(SystemOrigin::Signed(caller), id.clone(), weight_limit)
+	verify {
+		assert!(!<OverweightMessages<T>>::contains_key(&id));
+		assert!(!<OverweightMessageExpiries<T>>::contains_key(expiry, &id));
+	}
+
+	allow_call {
+	}: _(SystemOrigin::Root, 1, 2)
+	verify {
+		assert!(<AllowedCalls<T>>::contains_key((1, 2)));
+	}
+
+	disallow_call {
+		<AllowedCalls<T>>::insert((1, 2), ());
+	}: _(SystemOrigin::Root, 1, 2)
+	verify {
+		assert!(!<AllowedCalls<T>>::contains_key((1, 2)));
+	}
+}
+
+impl_benchmark_test_suite!(Dispatch, crate::tests::new_test_ext(), crate::tests::Test);
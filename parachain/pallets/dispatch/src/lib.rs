@@ -1,19 +1,30 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+
 use frame_support::{
-	dispatch::{DispatchResult, Dispatchable, GetDispatchInfo, Parameter},
+	dispatch::{extract_actual_weight, DispatchResult, Dispatchable, GetDispatchInfo, Parameter},
+	storage::with_storage_layer,
 	traits::{Contains, EnsureOrigin},
+	weights::Weight,
 };
 
 use scale_info::TypeInfo;
 use sp_core::RuntimeDebug;
 
 use sp_core::H160;
-use sp_std::prelude::*;
+use sp_io::hashing::blake2_256;
+use sp_runtime::traits::{Convert, Saturating, TrailingZeroInput};
+use sp_std::{marker::PhantomData, prelude::*};
+
+use snowbridge_core::{DispatchOutcome, MessageDispatch, OverweightMessageExecuted};
 
-use snowbridge_core::MessageDispatch;
+use codec::{Decode, DecodeAll, Encode, MaxEncodedLen};
 
-use codec::{Decode, Encode, MaxEncodedLen};
+pub use weights::WeightInfo;
 
 #[derive(Copy, Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 pub struct RawOrigin(pub H160);
@@ -42,6 +53,82 @@ where
 	}
 }
 
+/// Prefix hashed together with an Ethereum address to derive its Substrate account.
+pub const ETHEREUM_ACCOUNT_PREFIX: &[u8] = b"ethereum";
+
+/// Derives the Substrate account of an Ethereum account from the hash of its address, prefixed
+/// with [`ETHEREUM_ACCOUNT_PREFIX`]. Nobody knows the private key of a derived account, so it
+/// can only be controlled with calls sent from the Ethereum account.
+pub struct HashedEthereumAccount<AccountId>(PhantomData<AccountId>);
+
+impl<AccountId: Decode> Convert<H160, AccountId> for HashedEthereumAccount<AccountId> {
+	fn convert(address: H160) -> AccountId {
+		let hash = (ETHEREUM_ACCOUNT_PREFIX, address).using_encoded(blake2_256);
+		AccountId::decode(&mut TrailingZeroInput::new(&hash))
+			.expect("infinite length input; no invalid inputs for type; qed")
+	}
+}
+
+/// Dispatches calls sent from an Ethereum account with [`RawOrigin`], for pallets which accept
+/// [`EnsureEthereumAccount`].
+pub struct EthereumAccountOrigin;
+
+impl<OuterOrigin: From<RawOrigin>> Convert<H160, OuterOrigin> for EthereumAccountOrigin {
+	fn convert(address: H160) -> OuterOrigin {
+		RawOrigin(address).into()
+	}
+}
+
+/// Dispatches calls sent from an Ethereum account as signed by its derived account.
+pub struct SignedByDerivedAccount<T>(PhantomData<T>);
+
+impl<T, OuterOrigin> Convert<H160, OuterOrigin> for SignedByDerivedAccount<T>
+where
+	T: Config,
+	OuterOrigin: From<frame_system::RawOrigin<T::AccountId>>,
+{
+	fn convert(address: H160) -> OuterOrigin {
+		frame_system::RawOrigin::Signed(T::AccountIdConverter::convert(address)).into()
+	}
+}
+
+/// First byte of a payload carrying a batch of calls rather than a single call. Single calls are
+/// encoded starting with the index of their pallet, so no pallet may use this index in a runtime
+/// which dispatches batches.
+pub const BATCH_PAYLOAD_PREFIX: u8 = 0xff;
+
+/// The calls carried by the payload of a message.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum MessageCalls<Call> {
+	/// A single call, encoded as is.
+	Single(Call),
+	/// A batch of calls, encoded as [`BATCH_PAYLOAD_PREFIX`] followed by the vector of calls.
+	/// The calls are dispatched in order, and are all reverted if any of them fails.
+	Batch(Vec<Call>),
+}
+
+impl<Call> MessageCalls<Call> {
+	/// All calls carried by the payload.
+	pub fn calls(&self) -> &[Call] {
+		match self {
+			MessageCalls::Single(call) => sp_std::slice::from_ref(call),
+			MessageCalls::Batch(calls) => calls,
+		}
+	}
+}
+
+/// A message whose calls required more weight than the limit when it was dispatched, kept so that
+/// it can be executed later with a higher weight limit.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct OverweightMessage<BlockNumber> {
+	/// The Ethereum account which sent the message.
+	pub source: H160,
+	/// The encoded call or batch of calls.
+	pub payload: Vec<u8>,
+	/// The last block in which the message can be executed.
+	pub expiry: BlockNumber,
+}
+
 pub use pallet::*;
 
 #[frame_support::pallet]
@@ -78,13 +165,136 @@ pub mod pallet {
 		/// The pallet will filter all incoming calls right before they're dispatched. If this
 		/// filter rejects the call, special event (`Event::MessageRejected`) is emitted.
 		type CallFilter: Contains<<Self as Config>::RuntimeCall>;
+
+		/// Number of blocks for which an overweight message can be executed with
+		/// `execute_overweight`.
+		#[pallet::constant]
+		type OverweightMessageExpiry: Get<Self::BlockNumber>;
+
+		/// Maximum number of calls in the batch carried by a message.
+		#[pallet::constant]
+		type MaxCallsPerMessage: Get<u32>;
+
+		/// Converts the Ethereum account which sent a message into the origin its call is
+		/// dispatched with.
+		type OriginConverter: Convert<H160, <Self as Config>::RuntimeOrigin>;
+
+		/// Derives the Substrate account of an Ethereum account.
+		type AccountIdConverter: Convert<H160, Self::AccountId>;
+
+		/// Notified of the outcome of overweight messages executed with `execute_overweight`,
+		/// so that the delivery receipt of the message can be updated.
+		type OnOverweightMessageExecuted: OverweightMessageExecuted<Self::MessageId>;
+
+		/// Weight information for extrinsics in this pallet
+		type WeightInfo: WeightInfo;
 	}
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_idle(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			Self::prune_expired_messages(now, remaining_weight)
+		}
+	}
 
 	#[pallet::call]
-	impl<T: Config> Pallet<T> {}
+	impl<T: Config> Pallet<T> {
+		/// Execute the overweight message `id`, allowing its calls to use up to `weight_limit`.
+		/// Messages which have expired are removed without being executed.
+		///
+		/// Any signed account may execute an overweight message, paying the fee for the weight
+		/// it allows. The calls are still dispatched with the origin derived from the Ethereum
+		/// account which sent the message, and only if they are allowed by the call filter at
+		/// the time of execution, so the caller cannot change what the message does.
+		#[pallet::call_index(0)]
+		#[pallet::weight(
+			T::WeightInfo::execute_overweight()
+				.saturating_add(T::OnOverweightMessageExecuted::weight())
+				.saturating_add(*weight_limit)
+		)]
+		pub fn execute_overweight(
+			origin: OriginFor<T>,
+			id: T::MessageId,
+			weight_limit: Weight,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			let message =
+				<OverweightMessages<T>>::get(&id).ok_or(Error::<T>::UnknownOverweightMessage)?;
+			let weight = T::WeightInfo::execute_overweight();
+
+			if <frame_system::Pallet<T>>::block_number() > message.expiry {
+				<OverweightMessages<T>>::remove(&id);
+				<OverweightMessageExpiries<T>>::remove(message.expiry, &id);
+				Self::deposit_event(Event::OverweightMessageExpired(id));
+				return Ok(Some(weight).into())
+			}
+
+			let calls =
+				Self::decode_calls(&message.payload).ok_or(Error::<T>::InvalidOverweightMessage)?;
+			ensure!(
+				!Self::required_weight(&calls).any_gt(weight_limit),
+				Error::<T>::WeightLimitTooLow
+			);
+
+			<OverweightMessages<T>>::remove(&id);
+			<OverweightMessageExpiries<T>>::remove(message.expiry, &id);
+			let weight = weight.saturating_add(T::OnOverweightMessageExecuted::weight());
+
+			// The call filter may have changed since the message was received
+			if !calls.calls().iter().all(T::CallFilter::contains) {
+				T::OnOverweightMessageExecuted::overweight_message_executed(
+					&id,
+					message.source,
+					DispatchOutcome::Rejected,
+				);
+				Self::deposit_event(Event::MessageRejected(id));
+				return Ok(Some(weight).into())
+			}
+
+			let (outcome, calls_weight) = Self::execute(id.clone(), message.source, calls);
+			T::OnOverweightMessageExecuted::overweight_message_executed(
+				&id,
+				message.source,
+				outcome,
+			);
+
+			Ok(Some(weight.saturating_add(calls_weight)).into())
+		}
+
+		/// Allow Ethereum accounts to dispatch the call `call_index` of the pallet at
+		/// `pallet_index`.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::allow_call())]
+		pub fn allow_call(
+			origin: OriginFor<T>,
+			pallet_index: u8,
+			call_index: u8,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			<AllowedCalls<T>>::insert((pallet_index, call_index), ());
+			Self::deposit_event(Event::CallAllowed(pallet_index, call_index));
+			Ok(())
+		}
+
+		/// Stop allowing Ethereum accounts to dispatch the call `call_index` of the pallet at
+		/// `pallet_index`.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::disallow_call())]
+		pub fn disallow_call(
+			origin: OriginFor<T>,
+			pallet_index: u8,
+			call_index: u8,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(
+				<AllowedCalls<T>>::contains_key((pallet_index, call_index)),
+				Error::<T>::CallNotAllowed
+			);
+			<AllowedCalls<T>>::remove((pallet_index, call_index));
+			Self::deposit_event(Event::CallDisallowed(pallet_index, call_index));
+			Ok(())
+		}
+	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
@@ -95,6 +305,82 @@ pub mod pallet {
 		MessageRejected(T::MessageId),
 		/// We have failed to decode a RuntimeCall from the message.
 		MessageDecodeFailed(T::MessageId),
+		/// Message has not been dispatched as its calls require more weight (`.1`) than the
+		/// limit (`.2`). It is stored until it is executed with `execute_overweight` or it
+		/// expires at block `.3`.
+		MessageOverweight(T::MessageId, Weight, Weight, T::BlockNumber),
+		/// An overweight message expired before it was executed, and has been removed.
+		OverweightMessageExpired(T::MessageId),
+		/// Ethereum accounts are allowed to dispatch the call `.1` of the pallet at index `.0`.
+		CallAllowed(u8, u8),
+		/// Ethereum accounts are no longer allowed to dispatch the call `.1` of the pallet at
+		/// index `.0`.
+		CallDisallowed(u8, u8),
+		/// The batch of calls in a message has been dispatched with given result. `.1` calls
+		/// were dispatched successfully; if the result is an error, the call following them
+		/// failed and all of the calls have been reverted.
+		MessageBatchDispatched(T::MessageId, u32, DispatchResult),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// There is no overweight message with the given id.
+		UnknownOverweightMessage,
+		/// The overweight message could not be decoded into calls.
+		InvalidOverweightMessage,
+		/// The weight limit is lower than the weight required by the call.
+		WeightLimitTooLow,
+		/// The call is not in the allow-list.
+		CallNotAllowed,
+	}
+
+	/// Messages which were not dispatched as they required more weight than the limit.
+	#[pallet::storage]
+	#[pallet::unbounded]
+	pub type OverweightMessages<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::MessageId,
+		OverweightMessage<T::BlockNumber>,
+		OptionQuery,
+	>;
+
+	/// Ids of the overweight messages expiring at a block, so that they can be removed once they
+	/// have expired.
+	#[pallet::storage]
+	pub type OverweightMessageExpiries<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		Blake2_128Concat,
+		T::MessageId,
+		(),
+		OptionQuery,
+	>;
+
+	/// The earliest expiry block of overweight messages which may not have been removed yet.
+	/// Not set until the first message is stored.
+	#[pallet::storage]
+	pub type NextExpiryToPrune<T: Config> = StorageValue<_, T::BlockNumber, OptionQuery>;
+
+	/// Calls, identified by pallet index and call index, which Ethereum accounts are allowed to
+	/// dispatch when [`AllowedCallFilter`] is used as the call filter.
+	#[pallet::storage]
+	pub type AllowedCalls<T: Config> = StorageMap<_, Twox64Concat, (u8, u8), (), OptionQuery>;
+
+	#[pallet::genesis_config]
+	#[derive(Default)]
+	pub struct GenesisConfig {
+		pub allowed_calls: Vec<(u8, u8)>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			for call in &self.allowed_calls {
+				<AllowedCalls<T>>::insert(call, ());
+			}
+		}
 	}
 
 	#[pallet::origin]
@@ -102,28 +388,164 @@ pub mod pallet {
 
 	pub type MessageIdOf<T> = <T as Config>::MessageId;
 
+	impl<T: Config> Pallet<T> {
+		/// The Substrate account derived from the Ethereum account `address`.
+		pub fn derived_account(address: H160) -> T::AccountId {
+			T::AccountIdConverter::convert(address)
+		}
+
+		/// Decode the payload of a message into either a single call or a non-empty batch of at
+		/// most `MaxCallsPerMessage` calls.
+		pub fn decode_calls(payload: &[u8]) -> Option<MessageCalls<<T as Config>::RuntimeCall>> {
+			match payload {
+				[BATCH_PAYLOAD_PREFIX, batch @ ..] => {
+					let calls = BoundedVec::<<T as Config>::RuntimeCall, T::MaxCallsPerMessage>::decode_all(
+						&mut &batch[..],
+					)
+					.ok()?;
+					(!calls.is_empty()).then(|| MessageCalls::Batch(calls.into_inner()))
+				},
+				_ => <T as Config>::RuntimeCall::decode(&mut &payload[..])
+					.ok()
+					.map(MessageCalls::Single),
+			}
+		}
+
+		/// Remove the overweight messages which expired before block `now`, using at most
+		/// `weight_limit`. Returns the weight used.
+		fn prune_expired_messages(now: T::BlockNumber, weight_limit: Weight) -> Weight {
+			let db_weight = T::DbWeight::get();
+			// Read and write the expiry cursor
+			let mut weight = db_weight.reads_writes(1, 1);
+			// Read the next entry of the expiry index, remove it and its message
+			let prune_weight = db_weight.reads_writes(1, 2);
+			if weight.saturating_add(prune_weight).any_gt(weight_limit) {
+				return Weight::zero()
+			}
+
+			let mut block = match <NextExpiryToPrune<T>>::get() {
+				Some(block) => block,
+				None => return db_weight.reads(1),
+			};
+
+			'blocks: while block < now {
+				let mut expired = <OverweightMessageExpiries<T>>::drain_prefix(block);
+				loop {
+					if weight.saturating_add(prune_weight).any_gt(weight_limit) {
+						break 'blocks
+					}
+					weight.saturating_accrue(prune_weight);
+					match expired.next() {
+						Some((id, ())) => {
+							<OverweightMessages<T>>::remove(&id);
+							Self::deposit_event(Event::OverweightMessageExpired(id));
+						},
+						None => break,
+					}
+				}
+				block.saturating_inc();
+			}
+
+			<NextExpiryToPrune<T>>::put(block);
+			weight
+		}
+
+		/// Total weight declared by the calls of a message.
+		fn required_weight(calls: &MessageCalls<<T as Config>::RuntimeCall>) -> Weight {
+			calls.calls().iter().fold(Weight::zero(), |weight, call| {
+				weight.saturating_add(call.get_dispatch_info().weight)
+			})
+		}
+
+		/// Dispatch the calls of a message sent from `source`, returning the outcome and the
+		/// actual weight of the calls.
+		fn execute(
+			id: MessageIdOf<T>,
+			source: H160,
+			calls: MessageCalls<<T as Config>::RuntimeCall>,
+		) -> (DispatchOutcome, Weight) {
+			match calls {
+				MessageCalls::Single(call) => {
+					let info = call.get_dispatch_info();
+					let result = call.dispatch(T::OriginConverter::convert(source));
+					let weight = extract_actual_weight(&result, &info);
+					let outcome = match result {
+						Ok(_) => DispatchOutcome::Succeeded,
+						Err(_) => DispatchOutcome::Failed,
+					};
+
+					Self::deposit_event(Event::MessageDispatched(
+						id,
+						result.map(drop).map_err(|e| e.error),
+					));
+
+					(outcome, weight)
+				},
+				MessageCalls::Batch(calls) => {
+					let mut weight = Weight::zero();
+					let mut dispatched: u32 = 0;
+					let result = with_storage_layer(|| -> DispatchResult {
+						for call in calls {
+							let info = call.get_dispatch_info();
+							let result = call.dispatch(T::OriginConverter::convert(source));
+							weight = weight.saturating_add(extract_actual_weight(&result, &info));
+							result.map_err(|e| e.error)?;
+							dispatched += 1;
+						}
+						Ok(())
+					});
+					let outcome = match result {
+						Ok(_) => DispatchOutcome::Succeeded,
+						Err(_) => DispatchOutcome::Failed,
+					};
+
+					Self::deposit_event(Event::MessageBatchDispatched(id, dispatched, result));
+
+					(outcome, weight)
+				},
+			}
+		}
+	}
+
 	impl<T: Config> MessageDispatch<T, MessageIdOf<T>> for Pallet<T> {
-		fn dispatch(source: H160, id: MessageIdOf<T>, payload: &[u8]) {
-			let call = match <T as Config>::RuntimeCall::decode(&mut &payload[..]) {
-				Ok(call) => call,
-				Err(_) => {
+		fn dispatch(
+			source: H160,
+			id: MessageIdOf<T>,
+			payload: &[u8],
+			weight_limit: Weight,
+		) -> (DispatchOutcome, Weight) {
+			let calls = match Self::decode_calls(payload) {
+				Some(calls) => calls,
+				None => {
 					Self::deposit_event(Event::MessageDecodeFailed(id));
-					return
+					return (DispatchOutcome::DecodeFailed, Weight::zero())
 				},
 			};
 
-			if !T::CallFilter::contains(&call) {
+			if !calls.calls().iter().all(T::CallFilter::contains) {
 				Self::deposit_event(Event::MessageRejected(id));
-				return
+				return (DispatchOutcome::Rejected, Weight::zero())
 			}
 
-			let origin = RawOrigin(source).into();
-			let result = call.dispatch(origin);
+			let weight = Self::required_weight(&calls);
+			if weight.any_gt(weight_limit) {
+				let expiry = <frame_system::Pallet<T>>::block_number()
+					.saturating_add(T::OverweightMessageExpiry::get());
+				<OverweightMessages<T>>::insert(
+					&id,
+					OverweightMessage { source, payload: payload.to_vec(), expiry },
+				);
+				<OverweightMessageExpiries<T>>::insert(expiry, &id, ());
+				// The expiry period may have been shortened since earlier messages were stored
+				<NextExpiryToPrune<T>>::mutate(|next| match next {
+					Some(next) if *next <= expiry => (),
+					_ => *next = Some(expiry),
+				});
+				Self::deposit_event(Event::MessageOverweight(id, weight, weight_limit, expiry));
+				return (DispatchOutcome::Overweight, Weight::zero())
+			}
 
-			Self::deposit_event(Event::MessageDispatched(
-				id,
-				result.map(drop).map_err(|e| e.error),
-			));
+			Self::execute(id, source, calls)
 		}
 
 		#[cfg(feature = "runtime-benchmarks")]
@@ -136,15 +558,35 @@ pub mod pallet {
 	}
 }
 
+/// Call filter which only accepts calls in the [`AllowedCalls`] allow-list. Calls are identified
+/// by the pallet index and call index they are encoded with.
+pub struct AllowedCallFilter<T>(PhantomData<T>);
+
+impl<T: Config> Contains<<T as Config>::RuntimeCall> for AllowedCallFilter<T> {
+	fn contains(call: &<T as Config>::RuntimeCall) -> bool {
+		call.using_encoded(|encoded| match encoded {
+			[pallet_index, call_index, ..] =>
+				<AllowedCalls<T>>::contains_key((*pallet_index, *call_index)),
+			_ => false,
+		})
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use frame_support::{dispatch::DispatchError, parameter_types, traits::Everything};
+	use frame_support::{
+		assert_noop, assert_ok,
+		dispatch::DispatchError,
+		parameter_types,
+		traits::{Everything, GenesisBuild, Get, Hooks},
+		weights::constants::RocksDbWeight,
+	};
 	use frame_system::{EventRecord, Phase};
 	use sp_core::H256;
 	use sp_runtime::{
 		testing::Header,
-		traits::{BlakeTwo256, IdentityLookup},
+		traits::{BlakeTwo256, Hash, IdentityLookup},
 	};
 
 	use crate as dispatch;
@@ -159,7 +601,7 @@ mod tests {
 			UncheckedExtrinsic = UncheckedExtrinsic,
 		{
 			System: frame_system::{Pallet, Call, Storage, Event<T>},
-			Dispatch: dispatch::{Pallet, Storage, Origin, Event<T>},
+			Dispatch: dispatch::{Pallet, Call, Config, Storage, Origin, Event<T>},
 		}
 	);
 
@@ -167,6 +609,9 @@ mod tests {
 
 	parameter_types! {
 		pub const BlockHashCount: u64 = 250;
+		pub static OverweightMessageExpiry: u64 = 10;
+		pub const MaxCallsPerMessage: u32 = 4;
+		pub static ExecutedOverweightMessages: Vec<(u64, H160, DispatchOutcome)> = vec![];
 	}
 
 	impl frame_system::Config for Test {
@@ -190,7 +635,7 @@ mod tests {
 		type SystemWeightInfo = ();
 		type BlockWeights = ();
 		type BlockLength = ();
-		type DbWeight = ();
+		type DbWeight = RocksDbWeight;
 		type SS58Prefix = ();
 		type OnSetCode = ();
 		type MaxConsumers = frame_support::traits::ConstU32<16>;
@@ -202,35 +647,68 @@ mod tests {
 			match call {
 				RuntimeCall::System(frame_system::pallet::Call::<Test>::remark { remark: _ }) =>
 					true,
+				RuntimeCall::System(frame_system::pallet::Call::<Test>::remark_with_event {
+					remark: _,
+				}) => true,
+				RuntimeCall::System(frame_system::pallet::Call::<Test>::set_heap_pages {
+					pages: _,
+				}) => true,
 				_ => false,
 			}
 		}
 	}
 
+	pub struct MockOverweightMessageExecuted;
+	impl OverweightMessageExecuted<u64> for MockOverweightMessageExecuted {
+		fn weight() -> Weight {
+			Weight::from_parts(1_000, 0)
+		}
+		fn overweight_message_executed(id: &u64, source: H160, outcome: DispatchOutcome) {
+			let mut executed = ExecutedOverweightMessages::get();
+			executed.push((*id, source, outcome));
+			ExecutedOverweightMessages::set(executed);
+		}
+	}
+
 	impl dispatch::Config for Test {
 		type RuntimeOrigin = RuntimeOrigin;
 		type RuntimeEvent = RuntimeEvent;
 		type MessageId = u64;
 		type RuntimeCall = RuntimeCall;
 		type CallFilter = CallFilter;
+		type OverweightMessageExpiry = OverweightMessageExpiry;
+		type MaxCallsPerMessage = MaxCallsPerMessage;
+		type OriginConverter = SignedByDerivedAccount<Test>;
+		type AccountIdConverter = HashedEthereumAccount<AccountId>;
+		type OnOverweightMessageExecuted = MockOverweightMessageExecuted;
+		type WeightInfo = ();
 	}
 
-	fn new_test_ext() -> sp_io::TestExternalities {
+	pub fn new_test_ext() -> sp_io::TestExternalities {
 		let t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
 		sp_io::TestExternalities::new(t)
 	}
 
+	fn batch_payload(calls: Vec<RuntimeCall>) -> Vec<u8> {
+		let mut payload = vec![BATCH_PAYLOAD_PREFIX];
+		calls.encode_to(&mut payload);
+		payload
+	}
+
 	#[test]
 	fn test_dispatch_bridge_message() {
 		new_test_ext().execute_with(|| {
 			let id = 37;
 			let source = H160::repeat_byte(7);
 
-			let message =
-				RuntimeCall::System(frame_system::Call::remark { remark: vec![] }).encode();
+			let call = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+			let message = call.encode();
 
 			System::set_block_number(1);
-			Dispatch::dispatch(source, id, &message);
+			assert_eq!(
+				Dispatch::dispatch(source, id, &message, Weight::MAX),
+				(DispatchOutcome::Succeeded, call.get_dispatch_info().weight)
+			);
 
 			assert_eq!(
 				System::events(),
@@ -238,7 +716,7 @@ mod tests {
 					phase: Phase::Initialization,
 					event: RuntimeEvent::Dispatch(crate::Event::<Test>::MessageDispatched(
 						id,
-						Err(DispatchError::BadOrigin)
+						Ok(())
 					)),
 					topics: vec![],
 				}],
@@ -246,6 +724,46 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn test_dispatch_signed_by_derived_account() {
+		new_test_ext().execute_with(|| {
+			let id = 37;
+			let source = H160::repeat_byte(7);
+
+			let call = RuntimeCall::System(frame_system::Call::remark_with_event {
+				remark: vec![1, 2, 3],
+			});
+
+			System::set_block_number(1);
+			assert_eq!(
+				Dispatch::dispatch(source, id, &call.encode(), Weight::MAX),
+				(DispatchOutcome::Succeeded, call.get_dispatch_info().weight)
+			);
+
+			// The call sees the derived account of the Ethereum account as its signer
+			assert_eq!(
+				System::events().into_iter().map(|record| record.event).collect::<Vec<_>>(),
+				vec![
+					RuntimeEvent::System(frame_system::Event::Remarked {
+						sender: Dispatch::derived_account(source),
+						hash: BlakeTwo256::hash(&[1, 2, 3]),
+					}),
+					RuntimeEvent::Dispatch(crate::Event::<Test>::MessageDispatched(id, Ok(()))),
+				],
+			);
+
+			// Calls which require root fail, as they are not dispatched as root
+			let call = RuntimeCall::System(frame_system::Call::set_heap_pages { pages: 1 });
+			assert_eq!(
+				Dispatch::dispatch(source, id + 1, &call.encode(), Weight::MAX).0,
+				DispatchOutcome::Failed
+			);
+			System::assert_last_event(RuntimeEvent::Dispatch(
+				crate::Event::<Test>::MessageDispatched(id + 1, Err(DispatchError::BadOrigin)),
+			));
+		})
+	}
+
 	#[test]
 	fn test_message_decode_failed() {
 		new_test_ext().execute_with(|| {
@@ -255,7 +773,10 @@ mod tests {
 			let message: Vec<u8> = vec![1, 2, 3];
 
 			System::set_block_number(1);
-			Dispatch::dispatch(source, id, &message);
+			assert_eq!(
+				Dispatch::dispatch(source, id, &message, Weight::MAX),
+				(DispatchOutcome::DecodeFailed, Weight::zero())
+			);
 
 			assert_eq!(
 				System::events(),
@@ -278,7 +799,10 @@ mod tests {
 				RuntimeCall::System(frame_system::Call::set_code { code: vec![] }).encode();
 
 			System::set_block_number(1);
-			Dispatch::dispatch(source, id, &message);
+			assert_eq!(
+				Dispatch::dispatch(source, id, &message, Weight::MAX),
+				(DispatchOutcome::Rejected, Weight::zero())
+			);
 
 			assert_eq!(
 				System::events(),
@@ -290,4 +814,506 @@ mod tests {
 			);
 		})
 	}
+
+	#[test]
+	fn test_message_overweight() {
+		new_test_ext().execute_with(|| {
+			let id = 37;
+			let source = H160::repeat_byte(7);
+
+			let call = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+			let message = call.encode();
+			let weight = call.get_dispatch_info().weight;
+			let weight_limit = weight - Weight::from_ref_time(1);
+
+			System::set_block_number(1);
+			assert_eq!(
+				Dispatch::dispatch(source, id, &message, weight_limit),
+				(DispatchOutcome::Overweight, Weight::zero())
+			);
+
+			assert_eq!(
+				System::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: RuntimeEvent::Dispatch(crate::Event::<Test>::MessageOverweight(
+						id,
+						weight,
+						weight_limit,
+						11
+					)),
+					topics: vec![],
+				}],
+			);
+			assert_eq!(
+				<OverweightMessages<Test>>::get(id),
+				Some(OverweightMessage { source, payload: message, expiry: 11 })
+			);
+		})
+	}
+
+	#[test]
+	fn test_execute_overweight() {
+		new_test_ext().execute_with(|| {
+			let id = 37;
+			let source = H160::repeat_byte(7);
+
+			let call = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+			let message = call.encode();
+			let weight = call.get_dispatch_info().weight;
+
+			System::set_block_number(1);
+			Dispatch::dispatch(source, id, &message, Weight::zero());
+
+			assert_noop!(
+				Dispatch::execute_overweight(RuntimeOrigin::signed(1), id, Weight::zero()),
+				Error::<Test>::WeightLimitTooLow
+			);
+			assert_noop!(
+				Dispatch::execute_overweight(RuntimeOrigin::signed(1), 38, weight),
+				Error::<Test>::UnknownOverweightMessage
+			);
+
+			System::set_block_number(11);
+			let post_info =
+				Dispatch::execute_overweight(RuntimeOrigin::signed(1), id, weight).unwrap();
+			assert_eq!(
+				post_info.actual_weight,
+				Some(
+					<() as WeightInfo>::execute_overweight() +
+						MockOverweightMessageExecuted::weight() +
+						weight
+				)
+			);
+			assert!(!<OverweightMessages<Test>>::contains_key(id));
+			assert!(!<OverweightMessageExpiries<Test>>::contains_key(11, id));
+			System::assert_last_event(RuntimeEvent::Dispatch(
+				crate::Event::<Test>::MessageDispatched(id, Ok(())),
+			));
+			assert_eq!(
+				ExecutedOverweightMessages::get(),
+				vec![(id, source, DispatchOutcome::Succeeded)]
+			);
+		})
+	}
+
+	#[test]
+	fn test_execute_overweight_with_weight_limit_too_low() {
+		new_test_ext().execute_with(|| {
+			let id = 37;
+			let source = H160::repeat_byte(7);
+
+			let call = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+			let message = call.encode();
+			let weight = call.get_dispatch_info().weight;
+
+			System::set_block_number(1);
+			Dispatch::dispatch(source, id, &message, Weight::zero());
+
+			// The message is kept so that it can be executed with a higher limit
+			assert_noop!(
+				Dispatch::execute_overweight(
+					RuntimeOrigin::signed(1),
+					id,
+					weight - Weight::from_ref_time(1)
+				),
+				Error::<Test>::WeightLimitTooLow
+			);
+			assert_eq!(
+				<OverweightMessages<Test>>::get(id),
+				Some(OverweightMessage { source, payload: message, expiry: 11 })
+			);
+			assert!(ExecutedOverweightMessages::get().is_empty());
+
+			assert_ok!(Dispatch::execute_overweight(RuntimeOrigin::signed(1), id, weight));
+			assert!(!<OverweightMessages<Test>>::contains_key(id));
+		})
+	}
+
+	#[test]
+	fn test_execute_overweight_after_expiry() {
+		new_test_ext().execute_with(|| {
+			let id = 37;
+			let source = H160::repeat_byte(7);
+
+			let call = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+			let weight = call.get_dispatch_info().weight;
+
+			System::set_block_number(1);
+			Dispatch::dispatch(source, id, &call.encode(), Weight::zero());
+
+			System::set_block_number(12);
+			assert_ok!(Dispatch::execute_overweight(RuntimeOrigin::signed(1), id, weight));
+			assert!(!<OverweightMessages<Test>>::contains_key(id));
+			assert!(!<OverweightMessageExpiries<Test>>::contains_key(11, id));
+			System::assert_last_event(RuntimeEvent::Dispatch(
+				crate::Event::<Test>::OverweightMessageExpired(id),
+			));
+			assert!(ExecutedOverweightMessages::get().is_empty());
+		})
+	}
+
+	#[test]
+	fn test_expired_messages_pruned_on_idle() {
+		new_test_ext().execute_with(|| {
+			let source = H160::repeat_byte(7);
+			let call = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+
+			// Nothing to prune before the first overweight message
+			assert_eq!(
+				Dispatch::on_idle(1, Weight::MAX),
+				<Test as frame_system::Config>::DbWeight::get().reads(1)
+			);
+
+			System::set_block_number(1);
+			Dispatch::dispatch(source, 1, &call.encode(), Weight::zero());
+			Dispatch::dispatch(source, 2, &call.encode(), Weight::zero());
+			System::set_block_number(2);
+			Dispatch::dispatch(source, 3, &call.encode(), Weight::zero());
+			assert_eq!(<NextExpiryToPrune<Test>>::get(), Some(11));
+
+			// Messages can still be executed in their expiry block
+			Dispatch::on_idle(11, Weight::MAX);
+			assert_eq!(<OverweightMessages<Test>>::iter().count(), 3);
+
+			// Not enough weight to prune a message
+			assert_eq!(Dispatch::on_idle(12, Weight::zero()), Weight::zero());
+			assert_eq!(<OverweightMessages<Test>>::iter().count(), 3);
+
+			System::reset_events();
+			Dispatch::on_idle(12, Weight::MAX);
+			assert!(!<OverweightMessages<Test>>::contains_key(1));
+			assert!(!<OverweightMessages<Test>>::contains_key(2));
+			assert!(<OverweightMessages<Test>>::contains_key(3));
+			assert_eq!(<OverweightMessageExpiries<Test>>::iter().count(), 1);
+			assert_eq!(<NextExpiryToPrune<Test>>::get(), Some(12));
+			let mut expired =
+				System::events().into_iter().map(|record| record.event).collect::<Vec<_>>();
+			expired.sort_by_key(|event| format!("{:?}", event));
+			assert_eq!(
+				expired,
+				vec![
+					RuntimeEvent::Dispatch(crate::Event::<Test>::OverweightMessageExpired(1)),
+					RuntimeEvent::Dispatch(crate::Event::<Test>::OverweightMessageExpired(2)),
+				]
+			);
+
+			Dispatch::on_idle(20, Weight::MAX);
+			assert_eq!(<OverweightMessages<Test>>::iter().count(), 0);
+			assert_eq!(<OverweightMessageExpiries<Test>>::iter().count(), 0);
+			assert_eq!(<NextExpiryToPrune<Test>>::get(), Some(20));
+		})
+	}
+
+	#[test]
+	fn test_messages_pruned_after_expiry_period_is_shortened() {
+		new_test_ext().execute_with(|| {
+			let source = H160::repeat_byte(7);
+			let call = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+
+			System::set_block_number(1);
+			Dispatch::dispatch(source, 1, &call.encode(), Weight::zero());
+			assert_eq!(<NextExpiryToPrune<Test>>::get(), Some(11));
+
+			OverweightMessageExpiry::set(2);
+			Dispatch::dispatch(source, 2, &call.encode(), Weight::zero());
+			assert_eq!(<NextExpiryToPrune<Test>>::get(), Some(3));
+
+			// A later expiry does not delay pruning
+			OverweightMessageExpiry::set(10);
+			Dispatch::dispatch(source, 3, &call.encode(), Weight::zero());
+			assert_eq!(<NextExpiryToPrune<Test>>::get(), Some(3));
+
+			Dispatch::on_idle(4, Weight::MAX);
+			assert!(!<OverweightMessages<Test>>::contains_key(2));
+			assert!(<OverweightMessages<Test>>::contains_key(1));
+			assert_eq!(<NextExpiryToPrune<Test>>::get(), Some(4));
+		})
+	}
+
+	#[test]
+	fn test_dispatch_batch() {
+		new_test_ext().execute_with(|| {
+			let id = 37;
+			let source = H160::repeat_byte(7);
+
+			let calls = vec![
+				RuntimeCall::System(frame_system::Call::remark_with_event { remark: vec![1] }),
+				RuntimeCall::System(frame_system::Call::remark_with_event { remark: vec![2] }),
+			];
+			let weight = calls[0].get_dispatch_info().weight + calls[1].get_dispatch_info().weight;
+
+			System::set_block_number(1);
+			assert_eq!(
+				Dispatch::dispatch(source, id, &batch_payload(calls), Weight::MAX),
+				(DispatchOutcome::Succeeded, weight)
+			);
+
+			let sender = Dispatch::derived_account(source);
+			assert_eq!(
+				System::events().into_iter().map(|record| record.event).collect::<Vec<_>>(),
+				vec![
+					RuntimeEvent::System(frame_system::Event::Remarked {
+						sender,
+						hash: BlakeTwo256::hash(&[1]),
+					}),
+					RuntimeEvent::System(frame_system::Event::Remarked {
+						sender,
+						hash: BlakeTwo256::hash(&[2]),
+					}),
+					RuntimeEvent::Dispatch(crate::Event::<Test>::MessageBatchDispatched(
+						id,
+						2,
+						Ok(())
+					)),
+				],
+			);
+		})
+	}
+
+	#[test]
+	fn test_dispatch_batch_reverted() {
+		new_test_ext().execute_with(|| {
+			let id = 37;
+			let source = H160::repeat_byte(7);
+
+			// The second call requires root, so the first one is reverted
+			let calls = vec![
+				RuntimeCall::System(frame_system::Call::remark_with_event { remark: vec![1] }),
+				RuntimeCall::System(frame_system::Call::set_heap_pages { pages: 1 }),
+				RuntimeCall::System(frame_system::Call::remark_with_event { remark: vec![2] }),
+			];
+			let weight = calls[0].get_dispatch_info().weight + calls[1].get_dispatch_info().weight;
+
+			System::set_block_number(1);
+			assert_eq!(
+				Dispatch::dispatch(source, id, &batch_payload(calls), Weight::MAX),
+				(DispatchOutcome::Failed, weight)
+			);
+
+			assert_eq!(
+				System::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: RuntimeEvent::Dispatch(crate::Event::<Test>::MessageBatchDispatched(
+						id,
+						1,
+						Err(DispatchError::BadOrigin)
+					)),
+					topics: vec![],
+				}],
+			);
+		})
+	}
+
+	#[test]
+	fn test_batch_rejected() {
+		new_test_ext().execute_with(|| {
+			let id = 37;
+			let source = H160::repeat_byte(7);
+
+			let calls = vec![
+				RuntimeCall::System(frame_system::Call::remark { remark: vec![] }),
+				RuntimeCall::System(frame_system::Call::set_code { code: vec![] }),
+			];
+
+			System::set_block_number(1);
+			assert_eq!(
+				Dispatch::dispatch(source, id, &batch_payload(calls), Weight::MAX),
+				(DispatchOutcome::Rejected, Weight::zero())
+			);
+			System::assert_last_event(RuntimeEvent::Dispatch(
+				crate::Event::<Test>::MessageRejected(id),
+			));
+		})
+	}
+
+	#[test]
+	fn test_batch_decode_failed() {
+		new_test_ext().execute_with(|| {
+			let id = 37;
+			let source = H160::repeat_byte(7);
+			let call = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+
+			System::set_block_number(1);
+			for calls in [vec![], vec![call; MaxCallsPerMessage::get() as usize + 1]] {
+				assert_eq!(
+					Dispatch::dispatch(source, id, &batch_payload(calls), Weight::MAX),
+					(DispatchOutcome::DecodeFailed, Weight::zero())
+				);
+				System::assert_last_event(RuntimeEvent::Dispatch(
+					crate::Event::<Test>::MessageDecodeFailed(id),
+				));
+			}
+		})
+	}
+
+	#[test]
+	fn test_execute_overweight_batch() {
+		new_test_ext().execute_with(|| {
+			let id = 37;
+			let source = H160::repeat_byte(7);
+
+			let calls = vec![
+				RuntimeCall::System(frame_system::Call::remark { remark: vec![] }),
+				RuntimeCall::System(frame_system::Call::remark { remark: vec![] }),
+			];
+			let weight = calls[0].get_dispatch_info().weight + calls[1].get_dispatch_info().weight;
+
+			System::set_block_number(1);
+			assert_eq!(
+				Dispatch::dispatch(
+					source,
+					id,
+					&batch_payload(calls),
+					weight - Weight::from_ref_time(1)
+				),
+				(DispatchOutcome::Overweight, Weight::zero())
+			);
+
+			assert_noop!(
+				Dispatch::execute_overweight(
+					RuntimeOrigin::signed(1),
+					id,
+					weight - Weight::from_ref_time(1)
+				),
+				Error::<Test>::WeightLimitTooLow
+			);
+			let post_info =
+				Dispatch::execute_overweight(RuntimeOrigin::signed(1), id, weight).unwrap();
+			assert_eq!(
+				post_info.actual_weight,
+				Some(
+					<() as WeightInfo>::execute_overweight() +
+						MockOverweightMessageExecuted::weight() +
+						weight
+				)
+			);
+			System::assert_last_event(RuntimeEvent::Dispatch(
+				crate::Event::<Test>::MessageBatchDispatched(id, 2, Ok(())),
+			));
+		})
+	}
+
+	#[test]
+	fn test_allowed_call_filter() {
+		new_test_ext().execute_with(|| {
+			let call = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+			let (pallet_index, call_index) = (call.encode()[0], call.encode()[1]);
+
+			System::set_block_number(1);
+			assert!(!AllowedCallFilter::<Test>::contains(&call));
+
+			assert_ok!(Dispatch::allow_call(RuntimeOrigin::root(), pallet_index, call_index));
+			assert!(AllowedCallFilter::<Test>::contains(&call));
+			System::assert_last_event(RuntimeEvent::Dispatch(crate::Event::<Test>::CallAllowed(
+				pallet_index,
+				call_index,
+			)));
+
+			// Other calls of the same pallet are still filtered
+			let other_call = RuntimeCall::System(frame_system::Call::set_code { code: vec![] });
+			assert!(!AllowedCallFilter::<Test>::contains(&other_call));
+
+			assert_ok!(Dispatch::disallow_call(RuntimeOrigin::root(), pallet_index, call_index));
+			assert!(!AllowedCallFilter::<Test>::contains(&call));
+			System::assert_last_event(RuntimeEvent::Dispatch(
+				crate::Event::<Test>::CallDisallowed(pallet_index, call_index),
+			));
+
+			assert_noop!(
+				Dispatch::disallow_call(RuntimeOrigin::root(), pallet_index, call_index),
+				Error::<Test>::CallNotAllowed
+			);
+			assert_noop!(
+				Dispatch::allow_call(RuntimeOrigin::signed(1), pallet_index, call_index),
+				DispatchError::BadOrigin
+			);
+		})
+	}
+
+	#[test]
+	fn test_allow_call() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+
+			assert_noop!(
+				Dispatch::allow_call(RuntimeOrigin::signed(1), 0, 1),
+				DispatchError::BadOrigin
+			);
+			assert!(!<AllowedCalls<Test>>::contains_key((0, 1)));
+
+			assert_ok!(Dispatch::allow_call(RuntimeOrigin::root(), 0, 1));
+			assert!(<AllowedCalls<Test>>::contains_key((0, 1)));
+			System::assert_last_event(RuntimeEvent::Dispatch(crate::Event::<Test>::CallAllowed(
+				0, 1,
+			)));
+
+			// Allowing a call twice is harmless
+			assert_ok!(Dispatch::allow_call(RuntimeOrigin::root(), 0, 1));
+			assert_eq!(<AllowedCalls<Test>>::iter().count(), 1);
+		})
+	}
+
+	#[test]
+	fn test_disallow_call() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+
+			assert_noop!(
+				Dispatch::disallow_call(RuntimeOrigin::root(), 0, 1),
+				Error::<Test>::CallNotAllowed
+			);
+
+			<AllowedCalls<Test>>::insert((0, 1), ());
+			<AllowedCalls<Test>>::insert((0, 2), ());
+			assert_noop!(
+				Dispatch::disallow_call(RuntimeOrigin::signed(1), 0, 1),
+				DispatchError::BadOrigin
+			);
+
+			assert_ok!(Dispatch::disallow_call(RuntimeOrigin::root(), 0, 1));
+			assert!(!<AllowedCalls<Test>>::contains_key((0, 1)));
+			assert!(<AllowedCalls<Test>>::contains_key((0, 2)));
+			System::assert_last_event(RuntimeEvent::Dispatch(
+				crate::Event::<Test>::CallDisallowed(0, 1),
+			));
+
+			assert_noop!(
+				Dispatch::disallow_call(RuntimeOrigin::root(), 0, 1),
+				Error::<Test>::CallNotAllowed
+			);
+		})
+	}
+
+	#[test]
+	fn test_allowed_calls_genesis() {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		GenesisBuild::<Test>::assimilate_storage(
+			&dispatch::GenesisConfig { allowed_calls: vec![(0, 1), (3, 4)] },
+			&mut t,
+		)
+		.unwrap();
+		sp_io::TestExternalities::new(t).execute_with(|| {
+			assert!(<AllowedCalls<Test>>::contains_key((0, 1)));
+			assert!(<AllowedCalls<Test>>::contains_key((3, 4)));
+			assert_eq!(<AllowedCalls<Test>>::iter().count(), 2);
+		})
+	}
+
+	#[test]
+	fn test_derived_account() {
+		new_test_ext().execute_with(|| {
+			let source = H160::repeat_byte(7);
+			let account = Dispatch::derived_account(source);
+
+			assert_eq!(account, Dispatch::derived_account(source));
+			assert_ne!(account, Dispatch::derived_account(H160::repeat_byte(8)));
+
+			// Calls sent from the Ethereum account can be dispatched as signed by its derived
+			// account
+			let origin: RuntimeOrigin = SignedByDerivedAccount::<Test>::convert(source);
+			assert_eq!(frame_system::ensure_signed(origin), Ok(account));
+		})
+	}
 }
@@ -0,0 +1,56 @@
+//! Weights for dispatch
+//!
+//! THESE WEIGHTS ARE PLACEHOLDERS: they were estimated by hand and have not been generated with
+//! the Substrate benchmark CLI. Regenerate this file from the `dispatch` benchmarks with
+//! `templates/module-weight-template.hbs` before relying on them.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for dispatch.
+pub trait WeightInfo {
+	fn execute_overweight() -> Weight;
+	fn allow_call() -> Weight;
+	fn disallow_call() -> Weight;
+}
+
+/// Weights for dispatch using the Snowbridge node and recommended hardware.
+pub struct SnowbridgeWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SnowbridgeWeight<T> {
+	fn execute_overweight() -> Weight {
+		Weight::from_ref_time(18_624_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	fn allow_call() -> Weight {
+		Weight::from_ref_time(8_932_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	fn disallow_call() -> Weight {
+		Weight::from_ref_time(11_204_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn execute_overweight() -> Weight {
+		Weight::from_ref_time(18_624_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+	fn allow_call() -> Weight {
+		Weight::from_ref_time(8_932_000 as u64)
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	fn disallow_call() -> Weight {
+		Weight::from_ref_time(11_204_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+}
@@ -0,0 +1,69 @@
+//! ERC20App pallet benchmarking
+use super::*;
+
+use codec::Decode;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_support::traits::tokens::fungibles::Inspect;
+use frame_system::RawOrigin;
+use sp_runtime::traits::TrailingZeroInput;
+use xcm::latest::prelude::*;
+
+#[allow(unused_imports)]
+use crate::Pallet as ERC20App;
+
+// Register `token` with a newly created asset, returning the id of the asset.
+fn register_token<T: Config>(token: H160) -> T::AssetId {
+	let asset_id = T::AssetId::decode(&mut TrailingZeroInput::zeroes()).unwrap();
+	T::Assets::create(asset_id, whitelisted_caller(), true, 1).unwrap();
+	<AssetIds<T>>::insert(token, asset_id);
+	asset_id
+}
+
+benchmarks! {
+	mint {
+		let token = H160::repeat_byte(2);
+		let asset_id = register_token::<T>(token);
+		<Address<T>>::put(H160::repeat_byte(1));
+
+		let recipient: T::AccountId = account("recipient", 0, 0);
+		let recipient_lookup = T::Lookup::unlookup(recipient.clone());
+		let amount = 500;
+
+	}: _(RawOrigin::Signed(ERC20App::<T>::app_account()), token, H160::repeat_byte(3), recipient_lookup, amount, None)
+	verify {
+		assert_eq!(T::Assets::balance(asset_id, &recipient), amount);
+	}
+
+	// Benchmark `mint` extrinsic when the minted tokens are forwarded to another parachain.
+	mint_and_forward {
+		let token = H160::repeat_byte(2);
+		let asset_id = register_token::<T>(token);
+		<Address<T>>::put(H160::repeat_byte(1));
+
+		let recipient: T::AccountId = account("recipient", 0, 0);
+		let recipient_lookup = T::Lookup::unlookup(recipient.clone());
+		let destination = RemoteDestination {
+			dest: MultiLocation::new(1, X1(Parachain(1001))).into(),
+			beneficiary: MultiLocation::new(0, X1(AccountKey20 { network: None, key: [5; 20] })).into(),
+			fee: (MultiLocation::new(0, X1(GeneralIndex(asset_id.into()))), 100).into(),
+		};
+
+	}: mint(RawOrigin::Signed(ERC20App::<T>::app_account()), token, H160::repeat_byte(3), recipient_lookup, 500, Some(Box::new(destination)))
+
+	register_token {
+		let token = H160::repeat_byte(2);
+		let asset_id = T::AssetId::decode(&mut TrailingZeroInput::zeroes()).unwrap();
+	}: _(RawOrigin::Root, token, asset_id)
+	verify {
+		assert_eq!(<AssetIds<T>>::get(token), Some(asset_id));
+	}
+
+	set_address {
+		let address = H160::repeat_byte(1);
+	}: _(RawOrigin::Root, address)
+	verify {
+		assert_eq!(<Address<T>>::get(), address);
+	}
+}
+
+impl_benchmark_test_suite!(ERC20App, crate::tests::new_test_ext(), crate::tests::Test);
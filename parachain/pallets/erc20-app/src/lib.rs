@@ -0,0 +1,244 @@
+//! # ERC20 App
+//!
+//! Mints ERC20 tokens locked in the ERC20 app contract on Ethereum as assets on this parachain,
+//! and optionally forwards them to another parachain with an XCM reserve transfer.
+//!
+//! Calls are sent by the app contract through the dispatch pallet, which dispatches them as
+//! signed by the account derived from the contract address.
+//!
+//! Tokens are sent back to Ethereum with XCM, by depositing them to an Ethereum account. The
+//! [`EthereumAssetTransactor`] burns them and sends an unlock message to the app contract on the
+//! outbound channel, paid for by the origin of the XCM message.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+mod transactor;
+pub mod weights;
+
+#[cfg(test)]
+mod tests;
+
+use ethabi::Token;
+use frame_support::{
+	dispatch::DispatchResult,
+	storage::with_storage_layer,
+	traits::{
+		tokens::fungibles::{Create, Mutate},
+		EnsureOrigin,
+	},
+};
+use sp_core::H160;
+use sp_runtime::traits::{Convert, StaticLookup};
+use sp_std::{boxed::Box, prelude::*};
+
+use snowbridge_core::OutboundChannel;
+use snowbridge_xcm_support_primitives::{RemoteDestination, XcmReserveTransfer};
+
+pub use transactor::EthereumAssetTransactor;
+pub use weights::WeightInfo;
+
+/// Action of unlock messages, as decoded by the app contract.
+const UNLOCK_ACTION: u8 = 0;
+
+type AccountIdLookupOf<T> = <<T as frame_system::Config>::Lookup as StaticLookup>::Source;
+
+pub use pallet::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// Origin of calls sent from Ethereum, resolving to the account they are dispatched as.
+		type CallOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = Self::AccountId>;
+
+		/// Derives the Substrate account of an Ethereum account.
+		type AccountIdConverter: Convert<H160, Self::AccountId>;
+
+		/// Id of the assets in which ERC20 tokens are minted.
+		type AssetId: Member + Parameter + Copy + MaxEncodedLen + Into<u128>;
+
+		/// The assets in which ERC20 tokens are minted.
+		type Assets: Create<Self::AccountId, AssetId = Self::AssetId, Balance = u128>
+			+ Mutate<Self::AccountId, AssetId = Self::AssetId, Balance = u128>;
+
+		/// Forwards minted tokens to other parachains.
+		type XcmReserveTransfer: XcmReserveTransfer<Self::AccountId, Self::RuntimeOrigin>;
+
+		/// Sends unlock messages to the app contract.
+		type OutboundChannel: OutboundChannel<Self::AccountId>;
+
+		/// Weight information for extrinsics in this pallet
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// `amount` of `token`, locked on Ethereum by `sender`, has been minted to `recipient`.
+		Minted { token: H160, sender: H160, recipient: T::AccountId, amount: u128 },
+		/// `token` is minted in the asset `asset_id`.
+		TokenRegistered { token: H160, asset_id: T::AssetId },
+		/// The address of the ERC20 app contract has been updated.
+		AddressUpdated { address: H160 },
+		/// `amount` of `token` has been burned, to be unlocked to `recipient` on Ethereum.
+		Burned { token: H160, recipient: H160, amount: u128 },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The token has not been registered with an asset.
+		TokenNotRegistered,
+	}
+
+	/// Address of the ERC20 app contract on Ethereum.
+	#[pallet::storage]
+	#[pallet::getter(fn address)]
+	pub type Address<T: Config> = StorageValue<_, H160, ValueQuery>;
+
+	/// Assets in which ERC20 tokens, identified by their address, are minted.
+	#[pallet::storage]
+	pub type AssetIds<T: Config> = StorageMap<_, Twox64Concat, H160, T::AssetId, OptionQuery>;
+
+	/// ERC20 tokens, identified by the assets they are minted in.
+	#[pallet::storage]
+	pub type Tokens<T: Config> = StorageMap<_, Twox64Concat, T::AssetId, H160, OptionQuery>;
+
+	#[pallet::genesis_config]
+	#[derive(Default)]
+	pub struct GenesisConfig {
+		pub address: H160,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			<Address<T>>::put(self.address);
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Mint `amount` of `token`, locked on Ethereum by `sender`, to `recipient`. If a
+		/// `destination` is given, the minted tokens are then forwarded to its beneficiary, with
+		/// the fee paid by the account derived from `sender`. Tokens which fail to be forwarded
+		/// can be claimed by `recipient` on this parachain.
+		#[pallet::call_index(0)]
+		#[pallet::weight(match destination {
+			Some(destination) => T::WeightInfo::mint_and_forward()
+				.saturating_add(T::XcmReserveTransfer::reserve_transfer_weight(destination)),
+			None => T::WeightInfo::mint(),
+		})]
+		pub fn mint(
+			origin: OriginFor<T>,
+			token: H160,
+			sender: H160,
+			recipient: AccountIdLookupOf<T>,
+			amount: u128,
+			destination: Option<Box<RemoteDestination>>,
+		) -> DispatchResult {
+			let who = T::CallOrigin::ensure_origin(origin)?;
+			ensure!(who == Self::app_account(), DispatchError::BadOrigin);
+
+			let recipient = T::Lookup::lookup(recipient)?;
+			let asset_id = <AssetIds<T>>::get(token).ok_or(Error::<T>::TokenNotRegistered)?;
+
+			T::Assets::mint_into(asset_id, &recipient, amount)?;
+			Self::deposit_event(Event::Minted {
+				token,
+				sender,
+				recipient: recipient.clone(),
+				amount,
+			});
+
+			if let Some(destination) = destination {
+				T::XcmReserveTransfer::reserve_transfer(
+					asset_id.into(),
+					sender,
+					&recipient,
+					amount,
+					*destination,
+				);
+			}
+
+			Ok(())
+		}
+
+		/// Mint `token` in the asset `asset_id`.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::register_token())]
+		pub fn register_token(
+			origin: OriginFor<T>,
+			token: H160,
+			asset_id: T::AssetId,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			if let Some(old_asset_id) = <AssetIds<T>>::take(token) {
+				<Tokens<T>>::remove(old_asset_id);
+			}
+			if let Some(old_token) = <Tokens<T>>::take(asset_id) {
+				<AssetIds<T>>::remove(old_token);
+			}
+			<AssetIds<T>>::insert(token, asset_id);
+			<Tokens<T>>::insert(asset_id, token);
+			Self::deposit_event(Event::TokenRegistered { token, asset_id });
+			Ok(())
+		}
+
+		/// Set the address of the ERC20 app contract.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::set_address())]
+		pub fn set_address(origin: OriginFor<T>, address: H160) -> DispatchResult {
+			ensure_root(origin)?;
+			<Address<T>>::put(address);
+			Self::deposit_event(Event::AddressUpdated { address });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The account which calls sent from the ERC20 app contract are dispatched as.
+		pub fn app_account() -> T::AccountId {
+			T::AccountIdConverter::convert(<Address<T>>::get())
+		}
+
+		/// Unlock `amount` of `token` to `recipient` on Ethereum. The tokens must already have
+		/// been burned on this parachain. The unlock message is sent by `payer`, who pays its fee.
+		pub fn unlock(
+			payer: &T::AccountId,
+			token: H160,
+			recipient: H160,
+			amount: u128,
+		) -> DispatchResult {
+			let payload = ethabi::encode(&[Token::Tuple(vec![
+				Token::Uint(UNLOCK_ACTION.into()),
+				Token::Bytes(ethabi::encode(&[Token::Tuple(vec![
+					Token::Address(token),
+					Token::Address(recipient),
+					Token::Uint(amount.into()),
+				])])),
+			])]);
+			with_storage_layer(|| T::OutboundChannel::submit(payer, &payload))?;
+
+			Self::deposit_event(Event::Burned { token, recipient, amount });
+			Ok(())
+		}
+	}
+}
@@ -0,0 +1,490 @@
+use super::*;
+
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::{DispatchError, GetDispatchInfo},
+	parameter_types,
+	traits::{
+		AsEnsureOriginWithArg, ConstU32, Currency, Everything, ExistenceRequirement, GenesisBuild,
+	},
+	weights::Weight,
+};
+use frame_system::{EnsureRoot, EnsureSigned};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+};
+use sp_std::borrow::Borrow;
+use xcm::latest::prelude::*;
+use xcm_executor::traits::{
+	Convert as XcmConvert, Error as MatchError, MatchesFungibles, TransactAsset,
+};
+
+use crate as erc20_app;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
+		ERC20App: erc20_app::{Pallet, Call, Config, Storage, Event<T>},
+	}
+);
+
+type AccountId = u64;
+type Balance = u128;
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = BlockHashCount;
+	type DbWeight = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = Balance;
+	type RuntimeEvent = RuntimeEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+impl pallet_assets::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = Balance;
+	type RemoveItemsLimit = ConstU32<1000>;
+	type AssetId = u32;
+	type AssetIdParameter = codec::Compact<u32>;
+	type Currency = Balances;
+	type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type AssetDeposit = ();
+	type AssetAccountDeposit = ();
+	type MetadataDepositBase = ();
+	type MetadataDepositPerByte = ();
+	type ApprovalDeposit = ();
+	type StringLimit = ConstU32<50>;
+	type Freezer = ();
+	type Extra = ();
+	type CallbackHandle = ();
+	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ();
+}
+
+parameter_types! {
+	pub static ReserveTransfers: Vec<(u128, H160, AccountId, u128, RemoteDestination)> = vec![];
+}
+
+const RESERVE_TRANSFER_WEIGHT: Weight = Weight::from_parts(1_000_000, 1_024);
+
+// Mock reserve transfer which records the transfers it is asked to make
+pub struct MockXcmReserveTransfer;
+
+impl XcmReserveTransfer<AccountId, RuntimeOrigin> for MockXcmReserveTransfer {
+	fn reserve_transfer_weight(_: &RemoteDestination) -> Weight {
+		RESERVE_TRANSFER_WEIGHT
+	}
+
+	fn reserve_transfer(
+		asset_id: u128,
+		sender: H160,
+		recipient: &AccountId,
+		amount: u128,
+		destination: RemoteDestination,
+	) {
+		ReserveTransfers::mutate(|transfers| {
+			transfers.push((asset_id, sender, *recipient, amount, destination))
+		});
+	}
+}
+
+parameter_types! {
+	pub static SentMessages: Vec<(AccountId, Vec<u8>)> = vec![];
+}
+
+const MESSAGE_FEE: Balance = 10;
+const TREASURY: AccountId = 100;
+
+// Mock outbound channel, which charges the sender a fee and records the senders and payloads of
+// submitted messages
+pub struct MockOutboundChannel;
+
+impl OutboundChannel<AccountId> for MockOutboundChannel {
+	fn submit(who: &AccountId, payload: &[u8]) -> Result<u64, DispatchError> {
+		<Balances as Currency<AccountId>>::transfer(
+			who,
+			&TREASURY,
+			MESSAGE_FEE,
+			ExistenceRequirement::AllowDeath,
+		)?;
+		let mut messages = SentMessages::get();
+		let nonce = messages.len() as u64;
+		messages.push((*who, payload.to_vec()));
+		SentMessages::set(messages);
+		Ok(nonce)
+	}
+}
+
+// Mock account id converter which uses the low bytes of the Ethereum address as the account
+pub struct MockAccountIdConverter;
+
+impl Convert<H160, AccountId> for MockAccountIdConverter {
+	fn convert(address: H160) -> AccountId {
+		address.to_low_u64_be()
+	}
+}
+
+impl erc20_app::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type CallOrigin = EnsureSigned<AccountId>;
+	type AccountIdConverter = MockAccountIdConverter;
+	type AssetId = u32;
+	type Assets = Assets;
+	type XcmReserveTransfer = MockXcmReserveTransfer;
+	type OutboundChannel = MockOutboundChannel;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub EthereumLocation: MultiLocation =
+		MultiLocation::new(2, X1(GlobalConsensus(NetworkId::Ethereum { chain_id: 15 })));
+}
+
+// Mock matcher for assets located at `GeneralIndex(asset_id)`
+pub struct MockMatcher;
+
+impl MatchesFungibles<u32, u128> for MockMatcher {
+	fn matches_fungibles(asset: &MultiAsset) -> Result<(u32, u128), MatchError> {
+		match asset {
+			MultiAsset {
+				id: Concrete(MultiLocation { parents: 0, interior: X1(GeneralIndex(id)) }),
+				fun: Fungible(amount),
+			} => Ok(((*id).try_into().map_err(|_| MatchError::AssetIdConversionFailed)?, *amount)),
+			_ => Err(MatchError::AssetNotFound),
+		}
+	}
+}
+
+// Mock location converter for local accounts located at `AccountIndex64 { index }`
+pub struct MockLocationToAccountId;
+
+impl XcmConvert<MultiLocation, AccountId> for MockLocationToAccountId {
+	fn convert_ref(location: impl Borrow<MultiLocation>) -> Result<AccountId, ()> {
+		match location.borrow() {
+			MultiLocation { parents: 0, interior: X1(AccountIndex64 { index, .. }) } => Ok(*index),
+			_ => Err(()),
+		}
+	}
+}
+
+type Transactor =
+	EthereumAssetTransactor<Test, MockMatcher, MockLocationToAccountId, EthereumLocation>;
+
+const APP_ADDRESS: H160 = H160::repeat_byte(1);
+const TOKEN: H160 = H160::repeat_byte(2);
+const SENDER: H160 = H160::repeat_byte(3);
+const ASSET_ID: u32 = 1;
+const RECIPIENT: AccountId = 7;
+const PAYER: AccountId = 8;
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+
+	pallet_balances::GenesisConfig::<Test> { balances: vec![(PAYER, 1_000)] }
+		.assimilate_storage(&mut storage)
+		.unwrap();
+
+	GenesisBuild::<Test>::assimilate_storage(
+		&erc20_app::GenesisConfig { address: APP_ADDRESS },
+		&mut storage,
+	)
+	.unwrap();
+
+	let mut ext: sp_io::TestExternalities = storage.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+fn app_origin() -> RuntimeOrigin {
+	RuntimeOrigin::signed(ERC20App::app_account())
+}
+
+fn bridged_asset(amount: u128) -> MultiAsset {
+	(MultiLocation::new(0, X1(GeneralIndex(ASSET_ID.into()))), amount).into()
+}
+
+fn ethereum_account(key: [u8; 20]) -> MultiLocation {
+	EthereumLocation::get()
+		.pushed_with_interior(AccountKey20 { network: None, key })
+		.unwrap()
+}
+
+fn xcm_context(payer: AccountId) -> XcmContext {
+	let origin = MultiLocation::new(0, X1(AccountIndex64 { network: None, index: payer }));
+	XcmContext { origin: Some(origin), message_hash: [0; 32], topic: None }
+}
+
+fn register_token() {
+	assert_ok!(Assets::force_create(RuntimeOrigin::root(), ASSET_ID.into(), 1, true, 1));
+	assert_ok!(ERC20App::register_token(RuntimeOrigin::root(), TOKEN, ASSET_ID));
+}
+
+#[test]
+fn test_mint() {
+	new_test_ext().execute_with(|| {
+		register_token();
+
+		assert_ok!(ERC20App::mint(app_origin(), TOKEN, SENDER, RECIPIENT, 500, None));
+		assert_eq!(Assets::balance(ASSET_ID, &RECIPIENT), 500);
+		assert!(ReserveTransfers::get().is_empty());
+		System::assert_last_event(RuntimeEvent::ERC20App(crate::Event::Minted {
+			token: TOKEN,
+			sender: SENDER,
+			recipient: RECIPIENT,
+			amount: 500,
+		}));
+	});
+}
+
+#[test]
+fn test_mint_and_forward() {
+	new_test_ext().execute_with(|| {
+		register_token();
+
+		// Forward to an account on an EVM-based parachain, paying fees in the minted token
+		let destination = RemoteDestination {
+			dest: MultiLocation::new(1, X1(Parachain(1001))).into(),
+			beneficiary: MultiLocation::new(0, X1(AccountKey20 { network: None, key: [5; 20] }))
+				.into(),
+			fee: (MultiLocation::new(0, X1(GeneralIndex(ASSET_ID.into()))), 100).into(),
+		};
+		assert_ok!(ERC20App::mint(
+			app_origin(),
+			TOKEN,
+			SENDER,
+			RECIPIENT,
+			500,
+			Some(Box::new(destination.clone()))
+		));
+		assert_eq!(Assets::balance(ASSET_ID, &RECIPIENT), 500);
+		assert_eq!(
+			ReserveTransfers::get(),
+			vec![(ASSET_ID as u128, SENDER, RECIPIENT, 500, destination.clone())]
+		);
+
+		// The weight includes the execution of the XCM program of the transfer
+		let call = erc20_app::Call::<Test>::mint {
+			token: TOKEN,
+			sender: SENDER,
+			recipient: RECIPIENT,
+			amount: 500,
+			destination: Some(Box::new(destination)),
+		};
+		assert_eq!(
+			call.get_dispatch_info().weight,
+			<() as WeightInfo>::mint_and_forward() + RESERVE_TRANSFER_WEIGHT
+		);
+	});
+}
+
+#[test]
+fn test_mint_unregistered_token() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ERC20App::mint(app_origin(), TOKEN, SENDER, RECIPIENT, 500, None),
+			Error::<Test>::TokenNotRegistered
+		);
+	});
+}
+
+#[test]
+fn test_mint_not_sent_by_app() {
+	new_test_ext().execute_with(|| {
+		register_token();
+
+		assert_noop!(
+			ERC20App::mint(RuntimeOrigin::signed(RECIPIENT), TOKEN, SENDER, RECIPIENT, 500, None),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			ERC20App::mint(RuntimeOrigin::root(), TOKEN, SENDER, RECIPIENT, 500, None),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn test_register_token() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ERC20App::register_token(RuntimeOrigin::root(), TOKEN, ASSET_ID));
+		assert_eq!(<AssetIds<Test>>::get(TOKEN), Some(ASSET_ID));
+		assert_eq!(<Tokens<Test>>::get(ASSET_ID), Some(TOKEN));
+		System::assert_last_event(RuntimeEvent::ERC20App(crate::Event::TokenRegistered {
+			token: TOKEN,
+			asset_id: ASSET_ID,
+		}));
+
+		assert_noop!(
+			ERC20App::register_token(RuntimeOrigin::signed(RECIPIENT), TOKEN, ASSET_ID),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn test_set_address() {
+	new_test_ext().execute_with(|| {
+		let address = H160::repeat_byte(9);
+		assert_ok!(ERC20App::set_address(RuntimeOrigin::root(), address));
+		assert_eq!(ERC20App::address(), address);
+		assert_eq!(ERC20App::app_account(), MockAccountIdConverter::convert(address));
+
+		assert_noop!(
+			ERC20App::set_address(RuntimeOrigin::signed(RECIPIENT), address),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn test_register_token_replaces_asset() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ERC20App::register_token(RuntimeOrigin::root(), TOKEN, ASSET_ID));
+		assert_ok!(ERC20App::register_token(RuntimeOrigin::root(), TOKEN, 2));
+		assert_eq!(<AssetIds<Test>>::get(TOKEN), Some(2));
+		assert_eq!(<Tokens<Test>>::get(2), Some(TOKEN));
+		assert_eq!(<Tokens<Test>>::get(ASSET_ID), None);
+	});
+}
+
+#[test]
+fn test_deposit_to_ethereum() {
+	new_test_ext().execute_with(|| {
+		register_token();
+
+		let recipient = H160::repeat_byte(5);
+		assert_ok!(Transactor::deposit_asset(
+			&bridged_asset(500),
+			&ethereum_account(recipient.into()),
+			&xcm_context(PAYER)
+		));
+
+		// ABI-encoded (uint8 action, bytes payload), with the payload (token, recipient, amount)
+		let mut payload = vec![0u8; 7 * 32];
+		payload[31] = 0x20;
+		payload[95] = 0x40;
+		payload[127] = 0x60;
+		payload[140..160].copy_from_slice(TOKEN.as_bytes());
+		payload[172..192].copy_from_slice(recipient.as_bytes());
+		payload[208..224].copy_from_slice(&500u128.to_be_bytes());
+		assert_eq!(SentMessages::get(), vec![(PAYER, payload)]);
+		assert_eq!(Balances::free_balance(PAYER), 1_000 - MESSAGE_FEE);
+
+		System::assert_last_event(RuntimeEvent::ERC20App(crate::Event::Burned {
+			token: TOKEN,
+			recipient,
+			amount: 500,
+		}));
+	});
+}
+
+#[test]
+fn test_deposit_to_other_location() {
+	new_test_ext().execute_with(|| {
+		register_token();
+
+		let beneficiary = MultiLocation::new(0, X1(AccountKey20 { network: None, key: [5; 20] }));
+		assert_eq!(
+			Transactor::deposit_asset(&bridged_asset(500), &beneficiary, &xcm_context(PAYER)),
+			Err(XcmError::AssetNotFound)
+		);
+		assert!(SentMessages::get().is_empty());
+	});
+}
+
+#[test]
+fn test_deposit_unregistered_asset() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			Transactor::deposit_asset(
+				&bridged_asset(500),
+				&ethereum_account([5; 20]),
+				&xcm_context(PAYER)
+			),
+			Err(XcmError::AssetNotFound)
+		);
+		assert!(SentMessages::get().is_empty());
+	});
+}
+
+#[test]
+fn test_deposit_without_origin() {
+	new_test_ext().execute_with(|| {
+		register_token();
+
+		let context = XcmContext { origin: None, message_hash: [0; 32], topic: None };
+		assert_eq!(
+			Transactor::deposit_asset(&bridged_asset(500), &ethereum_account([5; 20]), &context),
+			Err(XcmError::BadOrigin)
+		);
+		assert!(SentMessages::get().is_empty());
+	});
+}
+
+#[test]
+fn test_deposit_origin_cannot_pay_fee() {
+	new_test_ext().execute_with(|| {
+		register_token();
+
+		assert_eq!(
+			Transactor::deposit_asset(
+				&bridged_asset(500),
+				&ethereum_account([5; 20]),
+				&xcm_context(RECIPIENT)
+			),
+			Err(XcmError::FailedToTransactAsset("Failed to send unlock message"))
+		);
+		assert!(SentMessages::get().is_empty());
+	});
+}
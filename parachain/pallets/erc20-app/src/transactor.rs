@@ -0,0 +1,43 @@
+use frame_support::traits::Get;
+use sp_core::H160;
+use sp_std::marker::PhantomData;
+use xcm::latest::prelude::*;
+use xcm_executor::traits::{Convert, MatchesFungibles, TransactAsset};
+
+use crate::{Config, Pallet, Tokens};
+
+/// Transacts ERC20 tokens deposited to accounts on Ethereum, located under `EthereumLocation`.
+///
+/// The tokens have already been burned when they were withdrawn into the holding register, so
+/// depositing them only sends an unlock message to the app contract. The message is sent by the
+/// account of the XCM origin, converted with `AccountIdConverter`, which pays its fee. Other
+/// assets and locations are left to the next transactor.
+pub struct EthereumAssetTransactor<T, Matcher, AccountIdConverter, EthereumLocation>(
+	PhantomData<(T, Matcher, AccountIdConverter, EthereumLocation)>,
+);
+
+impl<T, Matcher, AccountIdConverter, EthereumLocation> TransactAsset
+	for EthereumAssetTransactor<T, Matcher, AccountIdConverter, EthereumLocation>
+where
+	T: Config,
+	Matcher: MatchesFungibles<T::AssetId, u128>,
+	AccountIdConverter: Convert<MultiLocation, T::AccountId>,
+	EthereumLocation: Get<MultiLocation>,
+{
+	fn deposit_asset(what: &MultiAsset, who: &MultiLocation, context: &XcmContext) -> XcmResult {
+		let recipient = match who.match_and_split(&EthereumLocation::get()) {
+			Some(AccountKey20 { key, .. }) => H160(*key),
+			_ => return Err(XcmError::AssetNotFound),
+		};
+		let (asset_id, amount) =
+			Matcher::matches_fungibles(what).map_err(|_| XcmError::AssetNotFound)?;
+		let token = <Tokens<T>>::get(asset_id).ok_or(XcmError::AssetNotFound)?;
+		let payer = context
+			.origin
+			.and_then(|origin| AccountIdConverter::convert(origin).ok())
+			.ok_or(XcmError::BadOrigin)?;
+
+		Pallet::<T>::unlock(&payer, token, recipient, amount)
+			.map_err(|_| XcmError::FailedToTransactAsset("Failed to send unlock message"))
+	}
+}
@@ -0,0 +1,66 @@
+//! Weights for erc20_app
+//!
+//! THESE WEIGHTS ARE PLACEHOLDERS: they were estimated by hand and have not been generated with
+//! the Substrate benchmark CLI. They exclude the execution of the XCM programs of forwarded
+//! tokens, which is weighed separately with `pallet_xcm::Config::Weigher`. Regenerate this file
+//! with `scripts/benchmark.sh` on the reference hardware before relying on them.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for erc20_app.
+pub trait WeightInfo {
+	fn mint() -> Weight;
+	fn mint_and_forward() -> Weight;
+	fn register_token() -> Weight;
+	fn set_address() -> Weight;
+}
+
+/// Weights for erc20_app using the Snowbridge node and recommended hardware.
+pub struct SnowbridgeWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SnowbridgeWeight<T> {
+	fn mint() -> Weight {
+		Weight::from_ref_time(38_274_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(5 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	fn mint_and_forward() -> Weight {
+		Weight::from_ref_time(196_583_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(11 as u64))
+			.saturating_add(T::DbWeight::get().writes(7 as u64))
+	}
+	fn register_token() -> Weight {
+		Weight::from_ref_time(9_652_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	fn set_address() -> Weight {
+		Weight::from_ref_time(9_138_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn mint() -> Weight {
+		Weight::from_ref_time(38_274_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(5 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+	fn mint_and_forward() -> Weight {
+		Weight::from_ref_time(196_583_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(11 as u64))
+			.saturating_add(RocksDbWeight::get().writes(7 as u64))
+	}
+	fn register_token() -> Weight {
+		Weight::from_ref_time(9_652_000 as u64)
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	fn set_address() -> Weight {
+		Weight::from_ref_time(9_138_000 as u64)
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+}
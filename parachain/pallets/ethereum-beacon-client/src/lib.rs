@@ -1102,44 +1102,10 @@ pub mod pallet {
 			Ok(())
 		}
 
-		// Verifies that the receipt encoded in proof.data is included
-		// in the block given by proof.block_hash. Inclusion is only
-		// recognized if the block has been finalized.
-		fn verify_receipt_inclusion(
+		fn verify_with_header(
 			stored_header: ExecutionHeaderOf<T>,
-			proof: &Proof,
-		) -> Result<Receipt, DispatchError> {
-			let result = stored_header
-				.check_receipt_proof(&proof.data.1)
-				.ok_or(Error::<T>::InvalidProof)?;
-
-			match result {
-				Ok(receipt) => Ok(receipt),
-				Err(err) => {
-					log::trace!(
-						target: "ethereum-beacon-client",
-						"💫 Failed to decode transaction receipt: {}",
-						err
-					);
-					Err(Error::<T>::InvalidProof.into())
-				},
-			}
-		}
-	}
-
-	impl<T: Config> Verifier for Pallet<T> {
-		/// Verify a message by verifying the existence of the corresponding
-		/// Ethereum log in a block. Returns the log if successful.
-		fn verify(message: &Message) -> Result<(Log, u64), DispatchError> {
-			log::info!(
-				target: "ethereum-beacon-client",
-				"💫 Verifying message with block hash {}",
-				message.proof.block_hash,
-			);
-
-			let stored_header = <ExecutionHeaders<T>>::get(message.proof.block_hash)
-				.ok_or(Error::<T>::MissingHeader)?;
-
+			message: &Message,
+		) -> Result<(Log, u64), DispatchError> {
 			let block_number = stored_header.block_number;
 
 			let receipt = match Self::verify_receipt_inclusion(stored_header, &message.proof) {
@@ -1192,6 +1158,69 @@ pub mod pallet {
 			Ok((log, block_number))
 		}
 
+		// Verifies that the receipt encoded in proof.data is included
+		// in the block given by proof.block_hash. Inclusion is only
+		// recognized if the block has been finalized.
+		fn verify_receipt_inclusion(
+			stored_header: ExecutionHeaderOf<T>,
+			proof: &Proof,
+		) -> Result<Receipt, DispatchError> {
+			let result = stored_header
+				.check_receipt_proof(&proof.data.1)
+				.ok_or(Error::<T>::InvalidProof)?;
+
+			match result {
+				Ok(receipt) => Ok(receipt),
+				Err(err) => {
+					log::trace!(
+						target: "ethereum-beacon-client",
+						"💫 Failed to decode transaction receipt: {}",
+						err
+					);
+					Err(Error::<T>::InvalidProof.into())
+				},
+			}
+		}
+	}
+
+	impl<T: Config> Verifier for Pallet<T> {
+		/// Verify a message by verifying the existence of the corresponding
+		/// Ethereum log in a block. Returns the log if successful.
+		fn verify(message: &Message) -> Result<(Log, u64), DispatchError> {
+			log::info!(
+				target: "ethereum-beacon-client",
+				"💫 Verifying message with block hash {}",
+				message.proof.block_hash,
+			);
+
+			let stored_header = <ExecutionHeaders<T>>::get(message.proof.block_hash)
+				.ok_or(Error::<T>::MissingHeader)?;
+
+			Self::verify_with_header(stored_header, message)
+		}
+
+		/// Verify messages, reading the execution header only once for consecutive messages
+		/// included in the same block.
+		fn verify_batch(messages: &[Message]) -> Vec<Result<(Log, u64), DispatchError>> {
+			let mut cached: Option<(H256, Option<ExecutionHeaderOf<T>>)> = None;
+			messages
+				.iter()
+				.map(|message| {
+					let block_hash = message.proof.block_hash;
+					let header = match &cached {
+						Some((hash, header)) if *hash == block_hash => header.clone(),
+						_ => {
+							let header = <ExecutionHeaders<T>>::get(block_hash);
+							cached = Some((block_hash, header.clone()));
+							header
+						},
+					};
+					let stored_header = header.ok_or(Error::<T>::MissingHeader)?;
+					Self::verify_with_header(stored_header, message)
+				})
+				.collect()
+		}
+
 		// Empty implementation, not necessary for the beacon client,
 		// but needs to be declared to implement Verifier interface.
 		fn initialize_storage(
@@ -1201,5 +1230,101 @@ pub mod pallet {
 		) -> Result<(), &'static str> {
 			Ok(())
 		}
+
+		// Stores an execution header whose receipts root commits to one receipt per log. The
+		// receipts are the leaves of a trie of branch nodes, which is extended with further
+		// branch nodes above its root until each proof reaches `proof_size` bytes.
+		#[cfg(feature = "runtime-benchmarks")]
+		fn initialize_benchmark_messages(logs: Vec<Vec<u8>>, proof_size: u32) -> Vec<Message> {
+			use rlp::RlpStream;
+			use sp_io::hashing::keccak_256;
+
+			if logs.is_empty() {
+				return vec![]
+			}
+
+			// Branch node committing to `children`, with dummy hashes in the remaining slots
+			let branch = |children: &[Vec<u8>]| {
+				let mut node = RlpStream::new_list(17);
+				for i in 0..16 {
+					match children.get(i) {
+						Some(child) => node.append(&keccak_256(child).to_vec()),
+						None => node.append(&[i as u8; 32].to_vec()),
+					};
+				}
+				node.append_empty_data();
+				node.out().to_vec()
+			};
+
+			let leaves: Vec<Vec<u8>> = logs
+				.iter()
+				.map(|log| {
+					let mut receipt = RlpStream::new_list(4);
+					receipt.append(&vec![1u8]);
+					receipt.append(&0u64);
+					receipt.append(&vec![0u8; 256]);
+					receipt.begin_list(1);
+					receipt.append_raw(log, 1);
+
+					let mut leaf = RlpStream::new_list(2);
+					leaf.append(&vec![0x20u8]);
+					leaf.append(&receipt.out().to_vec());
+					leaf.out().to_vec()
+				})
+				.collect();
+
+			// The first level holds the leaves and the last level holds the root
+			let mut levels = vec![leaves];
+			loop {
+				let top = &levels[levels.len() - 1];
+				let size: usize = levels.iter().map(|level| level[0].len()).sum();
+				if top.len() == 1 && size >= proof_size as usize {
+					break
+				}
+				let level = top.chunks(16).map(|chunk| branch(chunk)).collect();
+				levels.push(level);
+			}
+
+			let receipts_root: H256 = keccak_256(&levels[levels.len() - 1][0]).into();
+			let block_hash = receipts_root;
+			<ExecutionHeaders<T>>::insert(
+				block_hash,
+				ExecutionHeaderOf::<T> {
+					parent_hash: H256::zero(),
+					fee_recipient: H160::zero(),
+					state_root: H256::zero(),
+					receipts_root,
+					logs_bloom: Default::default(),
+					prev_randao: H256::zero(),
+					block_number: 1,
+					gas_limit: 0,
+					gas_used: 0,
+					timestamp: 0,
+					extra_data: Default::default(),
+					base_fee_per_gas: U256::zero(),
+					block_hash,
+					transactions_root: H256::zero(),
+				},
+			);
+
+			logs.into_iter()
+				.enumerate()
+				.map(|(index, log)| {
+					// Nodes on the path from the root to the leaf
+					let proof = levels
+						.iter()
+						.enumerate()
+						.rev()
+						.map(|(depth, level)| {
+							level[index / 16usize.saturating_pow(depth as u32)].clone()
+						})
+						.collect();
+					Message {
+						data: log,
+						proof: Proof { block_hash, tx_index: index as u32, data: (vec![], proof) },
+					}
+				})
+				.collect()
+		}
 	}
 }
@@ -0,0 +1,16 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use snowbridge_xcm_support_primitives::{RemoteDestination, ReserveTransferEstimate};
+use sp_runtime::DispatchError;
+
+sp_api::decl_runtime_apis! {
+	pub trait XcmSupportApi {
+		/// Estimate the weight and fees of transferring `amount` of the asset `asset_id` to
+		/// `destination`, by building the same XCM program as the transfer would execute.
+		fn estimate_reserve_transfer(
+			asset_id: u128,
+			amount: u128,
+			destination: RemoteDestination,
+		) -> Result<ReserveTransferEstimate, DispatchError>;
+	}
+}
@@ -0,0 +1,109 @@
+//! XcmSupport pallet benchmarking
+use super::*;
+
+use frame_benchmarking::{
+	account, benchmarks, impl_benchmark_test_suite, whitelisted_caller, BenchmarkError,
+	BenchmarkResult,
+};
+use frame_support::traits::tokens::fungibles::{Create, Inspect, Mutate};
+use frame_system::RawOrigin;
+use snowbridge_xcm_support_primitives::{RemoteDestination, TransferInfo};
+use sp_core::{H160, H256};
+use sp_runtime::DispatchError;
+
+#[allow(unused_imports)]
+use crate::Pallet as XcmSupport;
+
+// Id of the asset created for the benchmarks.
+const ASSET_ID: u128 = 1_000;
+
+// Amount of the asset held by failed transfers.
+const AMOUNT: u128 = 1_000;
+
+// Create the asset and mint `amount` of it to `who`. The pallet account is endowed with the
+// minimum balance, as it is kept alive when releasing held assets.
+fn create_asset<T: Config>(who: &T::AccountId, amount: u128)
+where
+	T::Assets: Create<T::AccountId> + Mutate<T::AccountId>,
+{
+	let asset_id = T::AssetId::try_from(ASSET_ID).ok().expect("valid asset id");
+	T::Assets::create(asset_id, whitelisted_caller(), true, 1).unwrap();
+	T::Assets::mint_into(asset_id, &XcmSupport::<T>::account_id(), 1).unwrap();
+	T::Assets::mint_into(asset_id, who, amount).unwrap();
+}
+
+// Transfer to `dest` paying fees in the transferred asset.
+fn destination(dest: MultiLocation) -> RemoteDestination {
+	RemoteDestination {
+		dest: dest.into(),
+		beneficiary: MultiLocation::new(0, X1(AccountId32 { network: None, id: [5; 32] })).into(),
+		fee: (MultiLocation::new(0, X1(GeneralIndex(ASSET_ID))), 100).into(),
+	}
+}
+
+// Hold the assets of a failed transfer to `dest` by `recipient`, returning its id.
+fn hold_failed_transfer<T: Config>(recipient: &T::AccountId, dest: MultiLocation) -> u64
+where
+	T::AccountId: AsRef<[u8; 32]>,
+{
+	let destination = destination(dest);
+	let info = TransferInfo {
+		asset_id: ASSET_ID,
+		sender: H160::repeat_byte(3),
+		recipient: H256(*recipient.as_ref()),
+		amount: AMOUNT,
+		dest: destination.dest,
+		beneficiary: destination.beneficiary,
+		fee: destination.fee,
+	};
+	XcmSupport::<T>::hold(recipient, &info, DispatchError::Other("failed")).unwrap()
+}
+
+benchmarks! {
+	where_clause {
+		where
+			T::AccountId: AsRef<[u8; 32]>,
+			T::Assets: Create<T::AccountId> + Mutate<T::AccountId>,
+	}
+
+	// Benchmark `retry_transfer` extrinsic to a destination reachable by the runtime, paying the
+	// fee from the recipient.
+	retry_transfer {
+		let dest = T::ReachableDest::get()
+			.ok_or(BenchmarkError::Override(BenchmarkResult::from_weight(Weight::MAX)))?;
+		let recipient: T::AccountId = account("recipient", 0, 0);
+		create_asset::<T>(&recipient, AMOUNT + 100);
+		let id = hold_failed_transfer::<T>(&recipient, dest);
+
+	}: _(RawOrigin::Signed(recipient), id, Box::new(destination(dest)))
+	verify {
+		assert!(!<FailedTransfers<T>>::contains_key(id));
+	}
+
+	// Benchmark `claim` extrinsic, which releases the held assets to the recipient.
+	claim {
+		let recipient: T::AccountId = account("recipient", 0, 0);
+		create_asset::<T>(&recipient, AMOUNT + 1);
+		let id = hold_failed_transfer::<T>(&recipient, MultiLocation::parent());
+
+	}: _(RawOrigin::Signed(recipient.clone()), id)
+	verify {
+		assert!(!<FailedTransfers<T>>::contains_key(id));
+		let asset_id = T::AssetId::try_from(ASSET_ID).ok().expect("valid asset id");
+		assert_eq!(T::Assets::balance(asset_id, &recipient), AMOUNT + 1);
+	}
+
+	// Benchmark holding the assets of a transfer which failed.
+	hold {
+		let recipient: T::AccountId = account("recipient", 0, 0);
+		create_asset::<T>(&recipient, AMOUNT + 1);
+
+	}: {
+		hold_failed_transfer::<T>(&recipient, MultiLocation::parent());
+	}
+	verify {
+		assert!(<FailedTransfers<T>>::contains_key(0));
+	}
+}
+
+impl_benchmark_test_suite!(XcmSupport, crate::mock::new_test_ext(), crate::mock::Test);
@@ -2,10 +2,71 @@
 //!
 //! Includes an implementation for the `XcmReserveTransfer` trait, thus enabling
 //! withdrawals and deposits to assets via XCMP message execution.
+//!
+//! Transfers are executed by the account derived from the Ethereum account which sent them, which
+//! pays the fee, so that senders cannot spend the funds of the recipient. The assets of failed
+//! transfers are held by the pallet until the recipient retries the transfer, paying the fee
+//! itself, or claims them.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod weights;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
 pub use pallet::*;
+pub use weights::WeightInfo;
+
+use frame_support::{
+	traits::Get,
+	weights::{Weight, WeightToFee},
+};
+use sp_runtime::{FixedPointNumber, FixedU128};
+use sp_std::{marker::PhantomData, prelude::*};
+use xcm::latest::prelude::*;
+use xcm_executor::traits::WeightBounds;
+
+/// Estimates the weight of XCM programs executed on destination chains, and the fees charged for
+/// them.
+pub trait RemoteFeeEstimator<Call> {
+	/// Estimate the weight of `message` when executed on `dest`, and the fee charged for it in
+	/// the asset `fee_asset`, as seen from our parachain. Returns `None` if the fee cannot be
+	/// estimated, for example because `dest` does not accept `fee_asset` for fees.
+	fn estimate(
+		dest: &MultiLocation,
+		message: &mut Xcm<Call>,
+		fee_asset: &AssetId,
+	) -> Option<(Weight, u128)>;
+}
+
+/// Estimates fees as if every destination chain weighed programs with the weigher `W` and
+/// converted their weight into a fee with `F`. The fee is then converted into the fee asset at
+/// its rate in `R`, and assets without a rate cannot be used to pay fees.
+pub struct UniformRemoteFees<W, F, R>(PhantomData<(W, F, R)>);
+
+impl<Call, W, F, R> RemoteFeeEstimator<Call> for UniformRemoteFees<W, F, R>
+where
+	W: WeightBounds<Call>,
+	F: WeightToFee<Balance = u128>,
+	R: Get<Vec<(AssetId, FixedU128)>>,
+{
+	fn estimate(
+		_: &MultiLocation,
+		message: &mut Xcm<Call>,
+		fee_asset: &AssetId,
+	) -> Option<(Weight, u128)> {
+		let (_, rate) = R::get().into_iter().find(|(asset, _)| asset == fee_asset)?;
+		let weight = W::weight(message).ok()?;
+		Some((weight, rate.saturating_mul_int(F::weight_to_fee(&weight))))
+	}
+}
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -13,18 +74,80 @@ pub mod pallet {
 		ensure, log,
 		pallet_prelude::*,
 		storage::{with_transaction, TransactionOutcome},
+		traits::tokens::fungibles::Transfer,
+		PalletId,
 	};
 	use frame_system::pallet_prelude::*;
-	use snowbridge_xcm_support_primitives::{RemoteParachain, TransferInfo, XcmReserveTransfer};
+	use snowbridge_xcm_support_primitives::{
+		RemoteDestination, ReserveTransferEstimate, TransferInfo, XcmReserveTransfer,
+	};
 	use sp_core::{H160, H256};
-	use sp_runtime::DispatchError;
-	use sp_std::prelude::*;
+	use sp_runtime::{
+		traits::{AccountIdConversion, Convert, Saturating},
+		DispatchError,
+	};
+	use sp_std::{boxed::Box, prelude::*};
 	use xcm::latest::prelude::*;
 	use xcm_executor::traits::WeightBounds;
 
+	use crate::RemoteFeeEstimator;
+
+	/// A transfer which failed, whose assets are held by the pallet.
+	#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+	pub struct FailedTransfer<AccountId, BlockNumber> {
+		/// The account which receives the assets if they are claimed.
+		pub recipient: AccountId,
+		/// The transfer.
+		pub info: TransferInfo,
+		/// The reason the transfer failed.
+		pub error: DispatchError,
+		/// The last block in which the transfer can be retried.
+		pub expiry: BlockNumber,
+	}
+
 	#[pallet::config]
-	pub trait Config: frame_system::Config {
+	pub trait Config: frame_system::Config + pallet_xcm::Config {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// Estimates the weight and fees of XCM programs executed on destination chains, to
+		/// estimate the fees of transfers.
+		type RemoteFeeEstimator: RemoteFeeEstimator<<Self as pallet_xcm::Config>::RuntimeCall>;
+
+		/// Derives the account which executes transfers, and pays their fees, from the Ethereum
+		/// account which sent them.
+		type AccountIdConverter: Convert<H160, Self::AccountId>;
+
+		/// Id of the assets which are transferred.
+		type AssetId: Member + Parameter + Copy + TryFrom<u128>;
+
+		/// The assets which are transferred, used to hold the assets of failed transfers.
+		type Assets: Transfer<Self::AccountId, AssetId = Self::AssetId, Balance = u128>;
+
+		/// Id of the account holding the assets of failed transfers.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Number of blocks for which a failed transfer can be retried. Afterwards, it can only
+		/// be claimed.
+		#[pallet::constant]
+		type FailedTransferExpiry: Get<Self::BlockNumber>;
+
+		/// Weight information for extrinsics in this pallet
+		type WeightInfo: WeightInfo;
+	}
+
+	type XcmOf<T> = Xcm<<T as pallet_xcm::Config>::RuntimeCall>;
+
+	/// Instructions which pay for execution and deposit transferred assets on the destination
+	/// chain.
+	fn deposit_instructions<Call>(
+		fees: MultiAsset,
+		beneficiary: MultiLocation,
+	) -> Vec<Instruction<Call>> {
+		vec![
+			BuyExecution { fees, weight_limit: Unlimited },
+			DepositAsset { assets: Wild(All), beneficiary },
+		]
 	}
 
 	#[pallet::pallet]
@@ -53,6 +176,23 @@ pub mod pallet {
 		UnweighableMessage,
 		/// Xcm execution failed during initiation of request.
 		ExecutionFailed,
+		/// Destination, beneficiary or fee could not be converted to the latest XCM version.
+		BadVersion,
+		/// Fee could not be reanchored to the destination chain.
+		CannotReanchor,
+		/// There is no failed transfer with the given id.
+		UnknownTransfer,
+		/// Only the recipient of the transfer can retry it, or claim it before it expires.
+		NotRecipient,
+		/// The failed transfer has expired and can no longer be retried.
+		TransferExpired,
+		/// The asset id does not match any asset.
+		UnknownAsset,
+		/// The fee asset is neither reserved by our parachain, nor by the relay chain or one of
+		/// its parachains.
+		UnsupportedFeeAsset,
+		/// The fee of the transfer on the destination chain cannot be estimated.
+		CannotEstimateRemoteFee,
 	}
 
 	#[pallet::hooks]
@@ -63,72 +203,328 @@ pub mod pallet {
 	pub enum Event<T: Config> {
 		/// The transfer was successfully sent to the destination
 		TransferSent(TransferInfo),
-		/// The transfer failed. However assets remain on the parachain.
-		TransferFailed { info: TransferInfo, error: DispatchError },
+		/// The transfer failed. However assets remain on the parachain. They are held by the
+		/// pallet as the failed transfer `id`, unless `id` is `None` in which case they remain
+		/// with the recipient.
+		TransferFailed { id: Option<u64>, info: TransferInfo, error: DispatchError },
+		/// The assets of the failed transfer `id` have been deposited to `recipient`.
+		TransferClaimed { id: u64, recipient: T::AccountId },
 	}
 
+	/// Failed transfers whose assets are held by the pallet.
+	#[pallet::storage]
+	#[pallet::unbounded]
+	pub type FailedTransfers<T: Config> =
+		StorageMap<_, Twox64Concat, u64, FailedTransfer<T::AccountId, T::BlockNumber>, OptionQuery>;
+
+	/// Id of the next failed transfer.
+	#[pallet::storage]
+	pub type NextFailedTransferId<T: Config> = StorageValue<_, u64, ValueQuery>;
+
 	#[pallet::call]
-	impl<T: Config> Pallet<T> {}
+	impl<T: Config> Pallet<T> {
+		/// Retry the failed transfer `id` to `destination`, which may have an updated fee. The
+		/// fee is paid by the recipient.
+		#[pallet::call_index(0)]
+		#[pallet::weight(Pallet::<T>::retry_transfer_weight(destination))]
+		pub fn retry_transfer(
+			origin: OriginFor<T>,
+			id: u64,
+			destination: Box<RemoteDestination>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let transfer = <FailedTransfers<T>>::get(id).ok_or(Error::<T>::UnknownTransfer)?;
+			ensure!(who == transfer.recipient, Error::<T>::NotRecipient);
+			ensure!(
+				<frame_system::Pallet<T>>::block_number() <= transfer.expiry,
+				Error::<T>::TransferExpired
+			);
 
-	impl<T: Config> Pallet<T>
-	where
-		T: pallet_xcm::Config + Config,
-	{
-		fn reserve_transfer_unsafe(
+			<FailedTransfers<T>>::remove(id);
+			Self::release(&transfer)?;
+
+			let TransferInfo { asset_id, sender, recipient, amount, .. } = transfer.info;
+			Self::reserve_transfer_unsafe(asset_id, recipient, amount, (*destination).clone())?;
+
+			Self::deposit_event(Event::TransferSent(TransferInfo {
+				asset_id,
+				sender,
+				recipient,
+				amount,
+				dest: destination.dest,
+				beneficiary: destination.beneficiary,
+				fee: destination.fee,
+			}));
+			Ok(())
+		}
+
+		/// Deposit the assets of the failed transfer `id` to its recipient. Before the transfer
+		/// expires, only the recipient can claim it.
+		#[pallet::call_index(1)]
+		#[pallet::weight(<T as Config>::WeightInfo::claim())]
+		pub fn claim(origin: OriginFor<T>, id: u64) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let transfer = <FailedTransfers<T>>::get(id).ok_or(Error::<T>::UnknownTransfer)?;
+			ensure!(
+				who == transfer.recipient ||
+					<frame_system::Pallet<T>>::block_number() > transfer.expiry,
+				Error::<T>::NotRecipient
+			);
+
+			<FailedTransfers<T>>::remove(id);
+			Self::release(&transfer)?;
+
+			Self::deposit_event(Event::TransferClaimed { id, recipient: transfer.recipient });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The account holding the assets of failed transfers.
+		pub fn account_id() -> T::AccountId {
+			T::PalletId::get().into_account_truncating()
+		}
+
+		/// Hold the assets of a failed transfer, returning the id of the failed transfer. The
+		/// assets are not held if the recipient would not be kept alive.
+		pub(crate) fn hold(
+			recipient: &T::AccountId,
+			info: &TransferInfo,
+			error: DispatchError,
+		) -> Result<u64, DispatchError> {
+			let asset_id =
+				T::AssetId::try_from(info.asset_id).map_err(|_| Error::<T>::UnknownAsset)?;
+			T::Assets::transfer(asset_id, recipient, &Self::account_id(), info.amount, true)?;
+
+			let id = <NextFailedTransferId<T>>::mutate(|next_id| {
+				let id = *next_id;
+				*next_id = next_id.wrapping_add(1);
+				id
+			});
+			let expiry = <frame_system::Pallet<T>>::block_number()
+				.saturating_add(T::FailedTransferExpiry::get());
+			<FailedTransfers<T>>::insert(
+				id,
+				FailedTransfer { recipient: recipient.clone(), info: info.clone(), error, expiry },
+			);
+
+			Ok(id)
+		}
+
+		/// Deposit the held assets of a failed transfer back to its recipient. The pallet account
+		/// is kept alive, so it must be endowed with the minimum balance of each asset it holds.
+		fn release(transfer: &FailedTransfer<T::AccountId, T::BlockNumber>) -> DispatchResult {
+			let asset_id = T::AssetId::try_from(transfer.info.asset_id)
+				.map_err(|_| Error::<T>::UnknownAsset)?;
+			T::Assets::transfer(
+				asset_id,
+				&Self::account_id(),
+				&transfer.recipient,
+				transfer.info.amount,
+				true,
+			)?;
+			Ok(())
+		}
+
+		/// Move the transferred asset from its holder to the account executing the transfer.
+		fn move_to_payer(
+			holder: &T::AccountId,
+			payer: &T::AccountId,
 			asset_id: u128,
-			recipient: H256,
 			amount: u128,
-			destination: RemoteParachain,
-		) -> Result<(), Error<T>> {
-			ensure!(destination.fee > 0u128, Error::<T>::ZeroFeeSpecified);
+		) -> DispatchResult {
+			if holder == payer {
+				return Ok(())
+			}
+			let asset_id = T::AssetId::try_from(asset_id).map_err(|_| Error::<T>::UnknownAsset)?;
+			T::Assets::transfer(asset_id, holder, payer, amount, false)?;
+			Ok(())
+		}
 
-			let origin_location: MultiLocation = MultiLocation {
-				parents: 0,
-				interior: Junctions::X1(Junction::AccountId32 {
-					network: None,
-					id: recipient.into(),
+		/// Weight of retrying a failed transfer to `destination`, including the execution of its
+		/// XCM program. Retries whose program cannot be built or weighed fail with the error
+		/// before executing it.
+		fn retry_transfer_weight(destination: &RemoteDestination) -> Weight {
+			<T as Config>::WeightInfo::retry_transfer()
+				.saturating_add(Self::local_weight(destination).unwrap_or_default())
+		}
+
+		/// Weight of the XCM program executed on our parachain by a transfer to `destination`.
+		fn local_weight(destination: &RemoteDestination) -> Option<Weight> {
+			// The asset and amount do not change the instructions of the program
+			let (mut message, _) = Self::build_messages(0, 0, destination.clone()).ok()?;
+			T::Weigher::weight(&mut message).ok()
+		}
+
+		/// Estimate the weight and fees of transferring `amount` of the asset `asset_id` to
+		/// `destination`.
+		pub fn estimate_reserve_transfer(
+			asset_id: u128,
+			amount: u128,
+			destination: RemoteDestination,
+		) -> Result<ReserveTransferEstimate, DispatchError> {
+			let dest: MultiLocation =
+				destination.dest.clone().try_into().map_err(|_| Error::<T>::BadVersion)?;
+			let fee: MultiAsset =
+				destination.fee.clone().try_into().map_err(|_| Error::<T>::BadVersion)?;
+			let (mut message, mut remote_message) =
+				Self::build_messages(asset_id, amount, destination)?;
+
+			let local_weight =
+				T::Weigher::weight(&mut message).map_err(|_| Error::<T>::UnweighableMessage)?;
+			let (remote_weight, remote_fee) =
+				T::RemoteFeeEstimator::estimate(&dest, &mut remote_message, &fee.id)
+					.ok_or(Error::<T>::CannotEstimateRemoteFee)?;
+
+			Ok(ReserveTransferEstimate {
+				local_weight,
+				remote_weight,
+				remote_fee: MultiAsset { id: fee.id, fun: Fungible(remote_fee) }.into(),
+			})
+		}
+
+		/// Build the XCM program which transfers an asset to its destination, along with the
+		/// program it results in on the destination chain.
+		fn build_messages(
+			asset_id: u128,
+			amount: u128,
+			destination: RemoteDestination,
+		) -> Result<(XcmOf<T>, XcmOf<T>), Error<T>> {
+			let dest: MultiLocation =
+				destination.dest.try_into().map_err(|_| Error::<T>::BadVersion)?;
+			let beneficiary: MultiLocation =
+				destination.beneficiary.try_into().map_err(|_| Error::<T>::BadVersion)?;
+			let fee: MultiAsset = destination.fee.try_into().map_err(|_| Error::<T>::BadVersion)?;
+			let fee_reserve = Self::reserve_of(&fee)?;
+
+			let asset = MultiAsset {
+				id: AssetId::Concrete(MultiLocation {
+					parents: 0,
+					interior: Junctions::X1(Junction::GeneralIndex(asset_id)),
 				}),
+				fun: Fungibility::Fungible(amount),
 			};
 
-			let mut message = Xcm(vec![
-				WithdrawAsset(
-					vec![
-						MultiAsset {
-							id: Concrete(MultiLocation { parents: 1, interior: Junctions::Here }),
-							fun: Fungible(destination.fee),
-						},
-						MultiAsset {
-							id: AssetId::Concrete(MultiLocation {
-								parents: 0,
-								interior: Junctions::X1(Junction::GeneralIndex(asset_id)),
-							}),
-							fun: Fungibility::Fungible(amount),
+			// The assets as seen from the destination chain, where the fee pays for execution.
+			let remote_fee = fee
+				.clone()
+				.reanchored(&dest, T::UniversalLocation::get())
+				.map_err(|_| Error::<T>::CannotReanchor)?;
+			let remote_asset = asset
+				.clone()
+				.reanchored(&dest, T::UniversalLocation::get())
+				.map_err(|_| Error::<T>::CannotReanchor)?;
+
+			let fee_reserve = match fee_reserve {
+				// The fee is deposited on the destination chain by the same reserve transfer as
+				// the asset.
+				None => {
+					let message = Xcm(vec![
+						WithdrawAsset(vec![fee, asset].into()),
+						DepositReserveAsset {
+							assets: MultiAssetFilter::Wild(All),
+							dest,
+							xcm: Xcm(deposit_instructions(remote_fee.clone(), beneficiary)),
 						},
-					]
-					.into(),
-				),
-				DepositReserveAsset {
-					assets: MultiAssetFilter::Wild(All),
-					dest: MultiLocation {
-						parents: 1,
-						interior: Junctions::X1(Junction::Parachain(destination.para_id)),
+					]);
+
+					let mut remote_message = vec![
+						ReserveAssetDeposited(vec![remote_fee.clone(), remote_asset].into()),
+						ClearOrigin,
+					];
+					remote_message.extend(deposit_instructions(remote_fee, beneficiary));
+
+					return Ok((message, Xcm(remote_message)))
+				},
+				Some(fee_reserve) => fee_reserve,
+			};
+
+			// The fee is withdrawn from its reserve, which deposits it on the destination chain
+			// unless it is the destination chain itself. The transferred asset is deposited by a
+			// separate reserve transfer, which pays for its own execution.
+			let fee_deposit = deposit_instructions(remote_fee.clone(), beneficiary);
+			let reserve_instructions = if fee_reserve == dest {
+				fee_deposit.clone()
+			} else {
+				let reserve_fee = fee
+					.clone()
+					.reanchored(&fee_reserve, T::UniversalLocation::get())
+					.map_err(|_| Error::<T>::CannotReanchor)?;
+				let reserve_dest = dest
+					.reanchored(&fee_reserve, T::UniversalLocation::get())
+					.map_err(|_| Error::<T>::CannotReanchor)?;
+				vec![
+					BuyExecution { fees: reserve_fee, weight_limit: Unlimited },
+					DepositReserveAsset {
+						assets: MultiAssetFilter::Wild(All),
+						dest: reserve_dest,
+						xcm: Xcm(fee_deposit.clone()),
 					},
-					xcm: Xcm(vec![
-						BuyExecution {
-							fees: MultiAsset {
-								id: Concrete(MultiLocation {
-									parents: 1,
-									interior: Junctions::Here,
-								}),
-								fun: Fungible(destination.fee),
-							},
-							weight_limit: Unlimited,
-						},
-						DepositAsset { assets: Wild(All), beneficiary: origin_location.clone() },
-					]),
+				]
+			};
+
+			let message = Xcm(vec![
+				WithdrawAsset(vec![fee.clone(), asset.clone()].into()),
+				InitiateReserveWithdraw {
+					assets: Definite(fee.into()),
+					reserve: fee_reserve,
+					xcm: Xcm(reserve_instructions),
+				},
+				DepositReserveAsset {
+					assets: Definite(asset.into()),
+					dest,
+					xcm: Xcm(deposit_instructions(remote_asset, beneficiary)),
 				},
 			]);
 
+			// The program depositing the fee on the destination chain
+			let received_fee = if fee_reserve == dest {
+				WithdrawAsset(remote_fee.into())
+			} else {
+				ReserveAssetDeposited(remote_fee.into())
+			};
+			let mut remote_message = vec![received_fee, ClearOrigin];
+			remote_message.extend(fee_deposit);
+
+			Ok((message, Xcm(remote_message)))
+		}
+
+		/// The reserve of the fee asset `fee`, as seen from our parachain, or `None` if it is
+		/// reserved by our parachain.
+		fn reserve_of(fee: &MultiAsset) -> Result<Option<MultiLocation>, Error<T>> {
+			match &fee.id {
+				Concrete(MultiLocation { parents: 0, .. }) => Ok(None),
+				Concrete(MultiLocation { parents: 1, interior }) => match interior.first() {
+					Some(Parachain(para_id)) =>
+						Ok(Some(MultiLocation::new(1, X1(Parachain(*para_id))))),
+					_ => Ok(Some(MultiLocation::parent())),
+				},
+				_ => Err(Error::<T>::UnsupportedFeeAsset),
+			}
+		}
+
+		/// Transfer `amount` of the asset `asset_id` to `destination`, executing the XCM program
+		/// as the account `payer`, which holds the asset and pays the fee.
+		fn reserve_transfer_unsafe(
+			asset_id: u128,
+			payer: H256,
+			amount: u128,
+			destination: RemoteDestination,
+		) -> Result<(), Error<T>> {
+			let fee: MultiAsset =
+				destination.fee.clone().try_into().map_err(|_| Error::<T>::BadVersion)?;
+			ensure!(
+				matches!(fee.fun, Fungible(fee_amount) if fee_amount > 0),
+				Error::<T>::ZeroFeeSpecified
+			);
+
+			let origin_location: MultiLocation = MultiLocation {
+				parents: 0,
+				interior: Junctions::X1(Junction::AccountId32 { network: None, id: payer.into() }),
+			};
+
+			let (mut message, _) = Self::build_messages(asset_id, amount, destination)?;
+
 			let weight =
 				T::Weigher::weight(&mut message).map_err(|_| Error::<T>::UnweighableMessage)?;
 
@@ -145,26 +541,42 @@ pub mod pallet {
 		}
 	}
 
-	impl<T> XcmReserveTransfer<T::AccountId, OriginFor<T>> for Pallet<T>
+	impl<T: Config> XcmReserveTransfer<T::AccountId, OriginFor<T>> for Pallet<T>
 	where
-		T: pallet_xcm::Config + Config,
 		T::AccountId: AsRef<[u8; 32]>,
 	{
+		fn reserve_transfer_weight(destination: &RemoteDestination) -> Weight {
+			<T as Config>::WeightInfo::hold()
+				.saturating_add(Self::local_weight(destination).unwrap_or_default())
+		}
+
 		fn reserve_transfer(
 			asset_id: u128,
 			sender: H160,
 			recipient: &T::AccountId,
 			amount: u128,
-			destination: RemoteParachain,
+			destination: RemoteDestination,
 		) {
+			let recipient_account = recipient;
 			let recipient: H256 = recipient.as_ref().into();
 
+			// The transfer is executed by the sender, so that the fee is paid by the sender
+			// rather than the recipient.
+			let payer = T::AccountIdConverter::convert(sender);
 			let result = with_transaction(|| {
-				let outcome =
-					Self::reserve_transfer_unsafe(asset_id, recipient, amount, destination);
+				let outcome = Self::move_to_payer(recipient_account, &payer, asset_id, amount)
+					.and_then(|()| {
+						Self::reserve_transfer_unsafe(
+							asset_id,
+							payer.as_ref().into(),
+							amount,
+							destination.clone(),
+						)
+						.map_err(DispatchError::from)
+					});
 				match outcome {
 					Ok(()) => TransactionOutcome::Commit(Ok(())),
-					Err(error) => TransactionOutcome::Rollback(Err(DispatchError::from(error))),
+					Err(error) => TransactionOutcome::Rollback(Err(error)),
 				}
 			});
 
@@ -173,12 +585,27 @@ pub mod pallet {
 				sender,
 				recipient,
 				amount,
-				para_id: destination.para_id,
+				dest: destination.dest,
+				beneficiary: destination.beneficiary,
 				fee: destination.fee,
 			};
 			let event = match result {
 				Ok(()) => Event::<T>::TransferSent(info),
-				Err(error) => Event::<T>::TransferFailed { info, error },
+				Err(error) => {
+					let id =
+						with_transaction(|| match Self::hold(recipient_account, &info, error) {
+							Ok(id) => TransactionOutcome::Commit(Ok(Some(id))),
+							Err(err) => {
+								log::error!(
+									"Failed to hold assets of failed transfer. Reason: {:?}",
+									err
+								);
+								TransactionOutcome::Rollback(Ok::<_, DispatchError>(None))
+							},
+						})
+						.unwrap_or(None);
+					Event::<T>::TransferFailed { id, info, error }
+				},
 			};
 			Self::deposit_event(event);
 		}
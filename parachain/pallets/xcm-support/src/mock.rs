@@ -0,0 +1,277 @@
+use crate as snowbridge_xcm_support;
+use crate::{RemoteFeeEstimator, UniformRemoteFees};
+
+use frame_support::{
+	parameter_types,
+	traits::{AsEnsureOriginWithArg, ConstU32, Everything, GenesisBuild},
+	weights::{IdentityFee, Weight},
+	PalletId,
+};
+use frame_system::{EnsureRoot, EnsureSigned};
+use sp_core::{H160, H256};
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, Convert, IdentityLookup},
+	AccountId32, FixedU128,
+};
+use xcm::latest::{prelude::*, ExecuteXcm, Outcome, PreparedMessage, XcmHash};
+use xcm_builder::{AccountId32Aliases, EnsureXcmOrigin, FixedWeightBounds, SignedToAccountId32};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
+		PolkadotXcm: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin, Config},
+		XcmSupport: snowbridge_xcm_support::{Pallet, Call, Config, Storage, Event<T>},
+	}
+);
+
+pub type AccountId = AccountId32;
+pub type Balance = u128;
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = BlockHashCount;
+	type DbWeight = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = Balance;
+	type RuntimeEvent = RuntimeEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+impl pallet_assets::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = Balance;
+	type RemoveItemsLimit = ConstU32<1000>;
+	type AssetId = u32;
+	type AssetIdParameter = codec::Compact<u32>;
+	type Currency = Balances;
+	type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type AssetDeposit = ();
+	type AssetAccountDeposit = ();
+	type MetadataDepositBase = ();
+	type MetadataDepositPerByte = ();
+	type ApprovalDeposit = ();
+	type StringLimit = ConstU32<50>;
+	type Freezer = ();
+	type Extra = ();
+	type CallbackHandle = ();
+	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ();
+}
+
+parameter_types! {
+	pub const RelayNetwork: NetworkId = NetworkId::Rococo;
+	pub UniversalLocation: InteriorMultiLocation =
+		X2(GlobalConsensus(RelayNetwork::get()), Parachain(1000));
+	pub UnitWeightCost: Weight = Weight::from_parts(1_000_000_000, 64 * 1024);
+	pub const MaxInstructions: u32 = 100;
+	pub static XcmExecutionFails: bool = false;
+	pub static ExecutedMessages: Vec<(MultiLocation, Xcm<RuntimeCall>)> = vec![];
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+parameter_types! {
+	pub ReachableDest: Option<MultiLocation> = Some(Parent.into());
+}
+
+pub type LocationToAccountId = AccountId32Aliases<RelayNetwork, AccountId>;
+
+pub type LocalOriginToLocation = SignedToAccountId32<RuntimeOrigin, AccountId, RelayNetwork>;
+
+pub type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
+
+pub struct MockPreparedMessage(Xcm<RuntimeCall>);
+
+impl PreparedMessage for MockPreparedMessage {
+	fn weight_of(&self) -> Weight {
+		Weight::zero()
+	}
+}
+
+// Mock XCM executor which records the programs it executes, or fails to execute them if
+// `XcmExecutionFails` is set
+pub struct MockXcmExecutor;
+
+impl ExecuteXcm<RuntimeCall> for MockXcmExecutor {
+	type Prepared = MockPreparedMessage;
+
+	fn prepare(message: Xcm<RuntimeCall>) -> Result<Self::Prepared, Xcm<RuntimeCall>> {
+		Ok(MockPreparedMessage(message))
+	}
+
+	fn execute(
+		origin: impl Into<MultiLocation>,
+		prepared: Self::Prepared,
+		_: XcmHash,
+		_: Weight,
+	) -> Outcome {
+		if XcmExecutionFails::get() {
+			return Outcome::Error(XcmError::FailedToTransactAsset("mock failure"))
+		}
+		ExecutedMessages::mutate(|messages| messages.push((origin.into(), prepared.0)));
+		Outcome::Complete(Weight::zero())
+	}
+
+	fn charge_fees(_: impl Into<MultiLocation>, _: MultiAssets) -> XcmResult {
+		Ok(())
+	}
+}
+
+impl pallet_xcm::Config for Test {
+	const VERSION_DISCOVERY_QUEUE_SIZE: u32 = 100;
+	type RuntimeEvent = RuntimeEvent;
+	type SendXcmOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
+	type XcmRouter = ();
+	type ExecuteXcmOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
+	type XcmExecuteFilter = Everything;
+	type XcmExecutor = MockXcmExecutor;
+	type XcmTeleportFilter = Everything;
+	type XcmReserveTransferFilter = Everything;
+	type Weigher = Weigher;
+	type UniversalLocation = UniversalLocation;
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
+	type Currency = Balances;
+	type CurrencyMatcher = ();
+	type TrustedLockers = ();
+	type SovereignAccountOf = LocationToAccountId;
+	type MaxLockers = ConstU32<8>;
+	type WeightInfo = pallet_xcm::TestWeightInfo;
+	#[cfg(feature = "runtime-benchmarks")]
+	type ReachableDest = ReachableDest;
+}
+
+parameter_types! {
+	// The transferred asset pays fees at the rate of the weight, and the relay chain token at
+	// twice that rate
+	pub RemoteFeeRates: Vec<(AssetId, FixedU128)> = vec![
+		(Concrete(MultiLocation::new(0, X1(GeneralIndex(ASSET_ID.into())))), FixedU128::from_u32(1)),
+		(Concrete(MultiLocation::parent()), FixedU128::from_u32(2)),
+	];
+}
+
+// Estimates remote fees like `UniformRemoteFees`, except for `UNPRICED_PARACHAIN` which does not
+// accept any fee asset
+pub struct MockRemoteFees;
+
+impl RemoteFeeEstimator<RuntimeCall> for MockRemoteFees {
+	fn estimate(
+		dest: &MultiLocation,
+		message: &mut Xcm<RuntimeCall>,
+		fee_asset: &AssetId,
+	) -> Option<(Weight, u128)> {
+		if *dest == MultiLocation::new(1, X1(Parachain(UNPRICED_PARACHAIN))) {
+			return None
+		}
+		UniformRemoteFees::<Weigher, IdentityFee<u128>, RemoteFeeRates>::estimate(
+			dest, message, fee_asset,
+		)
+	}
+}
+
+pub struct MockAccountIdConverter;
+
+impl Convert<H160, AccountId> for MockAccountIdConverter {
+	fn convert(address: H160) -> AccountId {
+		let mut account = [0u8; 32];
+		account[12..].copy_from_slice(address.as_bytes());
+		account.into()
+	}
+}
+
+parameter_types! {
+	pub const XcmSupportPalletId: PalletId = PalletId(*b"s/xcmsup");
+	pub const FailedTransferExpiry: u64 = 10;
+}
+
+impl snowbridge_xcm_support::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type RemoteFeeEstimator = MockRemoteFees;
+	type AccountIdConverter = MockAccountIdConverter;
+	type AssetId = u32;
+	type Assets = Assets;
+	type PalletId = XcmSupportPalletId;
+	type FailedTransferExpiry = FailedTransferExpiry;
+	type WeightInfo = ();
+}
+
+pub const ASSET_ID: u32 = 1;
+pub const ASSET_OWNER: AccountId = AccountId32::new([1; 32]);
+pub const RECIPIENT: AccountId = AccountId32::new([7; 32]);
+pub const UNPRICED_PARACHAIN: u32 = 1002;
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+
+	GenesisBuild::<Test>::assimilate_storage(
+		&snowbridge_xcm_support::GenesisConfig {},
+		&mut storage,
+	)
+	.unwrap();
+
+	let mut ext: sp_io::TestExternalities = storage.into();
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		Assets::force_create(RuntimeOrigin::root(), ASSET_ID.into(), ASSET_OWNER, true, 1).unwrap();
+		Assets::mint(RuntimeOrigin::signed(ASSET_OWNER), ASSET_ID.into(), RECIPIENT, 1_000)
+			.unwrap();
+		// The pallet account is kept alive when it releases held assets
+		Assets::mint(
+			RuntimeOrigin::signed(ASSET_OWNER),
+			ASSET_ID.into(),
+			XcmSupport::account_id(),
+			1,
+		)
+		.unwrap();
+	});
+	ext
+}
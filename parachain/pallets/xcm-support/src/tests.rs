@@ -0,0 +1,506 @@
+use crate::{
+	mock::*, Error, Event, FailedTransfer, FailedTransfers, NextFailedTransferId, WeightInfo,
+};
+
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::{Dispatchable, GetDispatchInfo},
+};
+use snowbridge_xcm_support_primitives::{RemoteDestination, TransferInfo, XcmReserveTransfer};
+use sp_core::{H160, H256};
+use sp_runtime::{traits::Convert, AccountId32, DispatchError};
+use xcm::latest::prelude::*;
+
+const SENDER: H160 = H160::repeat_byte(3);
+const OTHER: AccountId = AccountId32::new([9; 32]);
+
+fn asset_location() -> MultiLocation {
+	MultiLocation::new(0, X1(GeneralIndex(ASSET_ID.into())))
+}
+
+fn sibling() -> MultiLocation {
+	MultiLocation::new(1, X1(Parachain(1001)))
+}
+
+// Destination on the sibling parachain, paying fees in the transferred asset
+fn destination(beneficiary: MultiLocation) -> RemoteDestination {
+	RemoteDestination {
+		dest: sibling().into(),
+		beneficiary: beneficiary.into(),
+		fee: (asset_location(), 100).into(),
+	}
+}
+
+fn transfer_info(amount: u128, destination: RemoteDestination) -> TransferInfo {
+	TransferInfo {
+		asset_id: ASSET_ID.into(),
+		sender: SENDER,
+		recipient: H256(RECIPIENT.into()),
+		amount,
+		dest: destination.dest,
+		beneficiary: destination.beneficiary,
+		fee: destination.fee,
+	}
+}
+
+fn last_event() -> RuntimeEvent {
+	System::events().pop().expect("an event").event
+}
+
+fn beneficiary() -> MultiLocation {
+	MultiLocation::new(0, X1(AccountKey20 { network: None, key: [5; 20] }))
+}
+
+// The account derived from the sender, which executes transfers
+fn payer() -> AccountId {
+	MockAccountIdConverter::convert(SENDER)
+}
+
+fn payer_location() -> MultiLocation {
+	MultiLocation::new(0, X1(AccountId32 { network: None, id: payer().into() }))
+}
+
+// Transfer 500 of the asset while XCM execution fails, returning the id of the failed transfer
+fn hold_failed_transfer() -> u64 {
+	XcmExecutionFails::set(true);
+	XcmSupport::reserve_transfer(
+		ASSET_ID.into(),
+		SENDER,
+		&RECIPIENT,
+		500,
+		destination(beneficiary()),
+	);
+	XcmExecutionFails::set(false);
+	<NextFailedTransferId<Test>>::get() - 1
+}
+
+#[test]
+fn test_reserve_transfer_to_evm_parachain() {
+	new_test_ext().execute_with(|| {
+		let beneficiary = beneficiary();
+		let destination = destination(beneficiary);
+
+		XcmSupport::reserve_transfer(ASSET_ID.into(), SENDER, &RECIPIENT, 500, destination.clone());
+
+		assert_eq!(
+			last_event(),
+			RuntimeEvent::XcmSupport(Event::TransferSent(transfer_info(500, destination)))
+		);
+
+		// The fee as seen from the sibling parachain
+		let remote_fee: MultiAsset =
+			(MultiLocation::new(1, X2(Parachain(1000), GeneralIndex(ASSET_ID.into()))), 100).into();
+		let message = Xcm(vec![
+			WithdrawAsset(
+				vec![
+					MultiAsset::from((asset_location(), 100)),
+					MultiAsset::from((asset_location(), 500)),
+				]
+				.into(),
+			),
+			DepositReserveAsset {
+				assets: Wild(All),
+				dest: sibling(),
+				xcm: Xcm(vec![
+					BuyExecution { fees: remote_fee, weight_limit: Unlimited },
+					DepositAsset { assets: Wild(All), beneficiary },
+				]),
+			},
+		]);
+
+		// The transfer is executed by the sender, which pays the fee, rather than the recipient
+		assert_eq!(ExecutedMessages::get(), vec![(payer_location(), message)]);
+		assert_eq!(Assets::balance(ASSET_ID, &payer()), 500);
+		assert_eq!(Assets::balance(ASSET_ID, &RECIPIENT), 500);
+	});
+}
+
+#[test]
+fn test_reserve_transfer_with_fee_reserved_by_relay_chain() {
+	new_test_ext().execute_with(|| {
+		let beneficiary = beneficiary();
+
+		// Fees in the relay chain token are withdrawn from the relay chain, which deposits them
+		// on the destination chain
+		let destination = RemoteDestination {
+			fee: (MultiLocation::parent(), 100).into(),
+			..destination(beneficiary)
+		};
+		XcmSupport::reserve_transfer(ASSET_ID.into(), SENDER, &RECIPIENT, 500, destination.clone());
+
+		assert_eq!(
+			last_event(),
+			RuntimeEvent::XcmSupport(Event::TransferSent(transfer_info(500, destination)))
+		);
+
+		let remote_asset: MultiAsset =
+			(MultiLocation::new(1, X2(Parachain(1000), GeneralIndex(ASSET_ID.into()))), 500).into();
+		let message = Xcm(vec![
+			WithdrawAsset(
+				vec![
+					MultiAsset::from((MultiLocation::parent(), 100)),
+					MultiAsset::from((asset_location(), 500)),
+				]
+				.into(),
+			),
+			InitiateReserveWithdraw {
+				assets: Definite(MultiAsset::from((MultiLocation::parent(), 100)).into()),
+				reserve: MultiLocation::parent(),
+				xcm: Xcm(vec![
+					BuyExecution {
+						fees: (MultiLocation::here(), 100).into(),
+						weight_limit: Unlimited,
+					},
+					DepositReserveAsset {
+						assets: Wild(All),
+						dest: MultiLocation::new(0, X1(Parachain(1001))),
+						xcm: Xcm(vec![
+							BuyExecution {
+								fees: (MultiLocation::parent(), 100).into(),
+								weight_limit: Unlimited,
+							},
+							DepositAsset { assets: Wild(All), beneficiary },
+						]),
+					},
+				]),
+			},
+			DepositReserveAsset {
+				assets: Definite(MultiAsset::from((asset_location(), 500)).into()),
+				dest: sibling(),
+				xcm: Xcm(vec![
+					BuyExecution { fees: remote_asset, weight_limit: Unlimited },
+					DepositAsset { assets: Wild(All), beneficiary },
+				]),
+			},
+		]);
+		assert_eq!(ExecutedMessages::get(), vec![(payer_location(), message)]);
+	});
+}
+
+#[test]
+fn test_reserve_transfer_with_fee_reserved_by_destination() {
+	new_test_ext().execute_with(|| {
+		let beneficiary = beneficiary();
+
+		// Fees in an asset of the destination chain are withdrawn from the destination chain
+		let fee_location =
+			MultiLocation::new(1, X3(Parachain(1001), PalletInstance(50), GeneralIndex(7)));
+		let destination =
+			RemoteDestination { fee: (fee_location, 100).into(), ..destination(beneficiary) };
+		XcmSupport::reserve_transfer(ASSET_ID.into(), SENDER, &RECIPIENT, 500, destination);
+
+		let remote_fee: MultiAsset =
+			(MultiLocation::new(0, X2(PalletInstance(50), GeneralIndex(7))), 100).into();
+		let remote_asset: MultiAsset =
+			(MultiLocation::new(1, X2(Parachain(1000), GeneralIndex(ASSET_ID.into()))), 500).into();
+		let message = Xcm(vec![
+			WithdrawAsset(
+				vec![
+					MultiAsset::from((fee_location, 100)),
+					MultiAsset::from((asset_location(), 500)),
+				]
+				.into(),
+			),
+			InitiateReserveWithdraw {
+				assets: Definite(MultiAsset::from((fee_location, 100)).into()),
+				reserve: sibling(),
+				xcm: Xcm(vec![
+					BuyExecution { fees: remote_fee, weight_limit: Unlimited },
+					DepositAsset { assets: Wild(All), beneficiary },
+				]),
+			},
+			DepositReserveAsset {
+				assets: Definite(MultiAsset::from((asset_location(), 500)).into()),
+				dest: sibling(),
+				xcm: Xcm(vec![
+					BuyExecution { fees: remote_asset, weight_limit: Unlimited },
+					DepositAsset { assets: Wild(All), beneficiary },
+				]),
+			},
+		]);
+		assert_eq!(ExecutedMessages::get(), vec![(payer_location(), message)]);
+	});
+}
+
+#[test]
+fn test_reserve_transfer_with_unsupported_fee_asset() {
+	new_test_ext().execute_with(|| {
+		let beneficiary = beneficiary();
+
+		// Fees in assets of other consensus systems are rejected
+		let destination = RemoteDestination {
+			fee: (MultiLocation::new(2, X1(GlobalConsensus(NetworkId::Kusama))), 100).into(),
+			..destination(beneficiary)
+		};
+		XcmSupport::reserve_transfer(ASSET_ID.into(), SENDER, &RECIPIENT, 500, destination.clone());
+
+		assert_eq!(
+			last_event(),
+			RuntimeEvent::XcmSupport(Event::TransferFailed {
+				id: Some(0),
+				info: transfer_info(500, destination),
+				error: DispatchError::from(Error::<Test>::UnsupportedFeeAsset),
+			})
+		);
+		assert!(ExecutedMessages::get().is_empty());
+		assert!(<FailedTransfers<Test>>::contains_key(0));
+		assert_eq!(Assets::balance(ASSET_ID, &XcmSupport::account_id()), 501);
+	});
+}
+
+#[test]
+fn test_estimate_reserve_transfer() {
+	new_test_ext().execute_with(|| {
+		let beneficiary = beneficiary();
+
+		let estimate =
+			XcmSupport::estimate_reserve_transfer(ASSET_ID.into(), 500, destination(beneficiary))
+				.unwrap();
+
+		// WithdrawAsset and DepositReserveAsset
+		assert_eq!(estimate.local_weight, UnitWeightCost::get().saturating_mul(2));
+		// ReserveAssetDeposited, ClearOrigin, BuyExecution and DepositAsset
+		assert_eq!(estimate.remote_weight, UnitWeightCost::get().saturating_mul(4));
+		// The fee is priced in the fee asset of the transfer
+		assert_eq!(
+			estimate.remote_fee,
+			MultiAsset::from((asset_location(), 4 * UnitWeightCost::get().ref_time() as u128))
+				.into()
+		);
+	});
+}
+
+#[test]
+fn test_estimate_reserve_transfer_converts_fee_into_fee_asset() {
+	new_test_ext().execute_with(|| {
+		let beneficiary = beneficiary();
+		let destination = RemoteDestination {
+			fee: (MultiLocation::parent(), 100).into(),
+			..destination(beneficiary)
+		};
+
+		let estimate =
+			XcmSupport::estimate_reserve_transfer(ASSET_ID.into(), 500, destination).unwrap();
+
+		// The relay chain token is worth half as much as the transferred asset
+		assert_eq!(estimate.remote_weight, UnitWeightCost::get().saturating_mul(4));
+		assert_eq!(
+			estimate.remote_fee,
+			MultiAsset::from((
+				MultiLocation::parent(),
+				8 * UnitWeightCost::get().ref_time() as u128
+			))
+			.into()
+		);
+	});
+}
+
+#[test]
+fn test_estimate_reserve_transfer_with_unpriced_fee_asset() {
+	new_test_ext().execute_with(|| {
+		let beneficiary = beneficiary();
+		let destination = RemoteDestination {
+			fee: (MultiLocation::new(1, X1(Parachain(1001))), 100).into(),
+			..destination(beneficiary)
+		};
+
+		assert_eq!(
+			XcmSupport::estimate_reserve_transfer(ASSET_ID.into(), 500, destination),
+			Err(Error::<Test>::CannotEstimateRemoteFee.into())
+		);
+	});
+}
+
+#[test]
+fn test_estimate_reserve_transfer_with_unpriced_destination() {
+	new_test_ext().execute_with(|| {
+		let beneficiary = beneficiary();
+		let destination = RemoteDestination {
+			dest: MultiLocation::new(1, X1(Parachain(UNPRICED_PARACHAIN))).into(),
+			..destination(beneficiary)
+		};
+
+		assert_eq!(
+			XcmSupport::estimate_reserve_transfer(ASSET_ID.into(), 500, destination),
+			Err(Error::<Test>::CannotEstimateRemoteFee.into())
+		);
+	});
+}
+
+#[test]
+fn test_failed_transfer_is_held() {
+	new_test_ext().execute_with(|| {
+		let id = hold_failed_transfer();
+
+		let info = transfer_info(500, destination(beneficiary()));
+		let error = DispatchError::from(Error::<Test>::ExecutionFailed);
+		assert_eq!(
+			last_event(),
+			RuntimeEvent::XcmSupport(Event::TransferFailed {
+				id: Some(id),
+				info: info.clone(),
+				error,
+			})
+		);
+		assert_eq!(
+			<FailedTransfers<Test>>::get(id),
+			Some(FailedTransfer { recipient: RECIPIENT, info, error, expiry: 11 })
+		);
+		assert_eq!(Assets::balance(ASSET_ID, &XcmSupport::account_id()), 501);
+		assert_eq!(Assets::balance(ASSET_ID, &RECIPIENT), 500);
+	});
+}
+
+#[test]
+fn test_failed_transfer_is_not_held_if_recipient_would_die() {
+	new_test_ext().execute_with(|| {
+		Assets::mint(RuntimeOrigin::signed(ASSET_OWNER), ASSET_ID.into(), OTHER, 500).unwrap();
+
+		XcmExecutionFails::set(true);
+		XcmSupport::reserve_transfer(
+			ASSET_ID.into(),
+			SENDER,
+			&OTHER,
+			500,
+			destination(beneficiary()),
+		);
+
+		// The assets remain with the recipient
+		assert!(matches!(
+			last_event(),
+			RuntimeEvent::XcmSupport(Event::TransferFailed { id: None, .. })
+		));
+		assert_eq!(Assets::balance(ASSET_ID, &OTHER), 500);
+		assert_eq!(Assets::balance(ASSET_ID, &XcmSupport::account_id()), 1);
+	});
+}
+
+#[test]
+fn test_retry_transfer() {
+	new_test_ext().execute_with(|| {
+		let id = hold_failed_transfer();
+
+		assert_ok!(XcmSupport::retry_transfer(
+			RuntimeOrigin::signed(RECIPIENT),
+			id,
+			Box::new(destination(beneficiary()))
+		));
+
+		assert_eq!(
+			last_event(),
+			RuntimeEvent::XcmSupport(Event::TransferSent(transfer_info(
+				500,
+				destination(beneficiary())
+			)))
+		);
+		assert!(!<FailedTransfers<Test>>::contains_key(id));
+		assert_eq!(ExecutedMessages::get().len(), 1);
+		assert_eq!(Assets::balance(ASSET_ID, &XcmSupport::account_id()), 1);
+	});
+}
+
+#[test]
+fn test_retry_transfer_to_unreachable_destination() {
+	new_test_ext().execute_with(|| {
+		let id = hold_failed_transfer();
+
+		// The fee cannot be reanchored to a destination outside of our consensus system
+		let destination = RemoteDestination {
+			dest: MultiLocation::new(3, Here).into(),
+			..destination(beneficiary())
+		};
+		let call = RuntimeCall::XcmSupport(crate::Call::retry_transfer {
+			id,
+			destination: Box::new(destination),
+		});
+
+		// The weight only covers the work done before the error
+		assert_eq!(call.get_dispatch_info().weight, <() as WeightInfo>::retry_transfer());
+		assert_noop!(
+			call.dispatch(RuntimeOrigin::signed(RECIPIENT)).map_err(|err| err.error),
+			Error::<Test>::CannotReanchor
+		);
+	});
+}
+
+#[test]
+fn test_retry_transfer_by_non_recipient() {
+	new_test_ext().execute_with(|| {
+		let id = hold_failed_transfer();
+
+		assert_noop!(
+			XcmSupport::retry_transfer(
+				RuntimeOrigin::signed(OTHER),
+				id,
+				Box::new(destination(beneficiary()))
+			),
+			Error::<Test>::NotRecipient
+		);
+	});
+}
+
+#[test]
+fn test_retry_expired_transfer() {
+	new_test_ext().execute_with(|| {
+		let id = hold_failed_transfer();
+
+		System::set_block_number(12);
+
+		assert_noop!(
+			XcmSupport::retry_transfer(
+				RuntimeOrigin::signed(RECIPIENT),
+				id,
+				Box::new(destination(beneficiary()))
+			),
+			Error::<Test>::TransferExpired
+		);
+	});
+}
+
+#[test]
+fn test_retry_transfer_fails_again() {
+	new_test_ext().execute_with(|| {
+		let id = hold_failed_transfer();
+
+		XcmExecutionFails::set(true);
+
+		// Dispatched as a call, so that storage changes are rolled back on failure
+		let call = RuntimeCall::XcmSupport(crate::Call::retry_transfer {
+			id,
+			destination: Box::new(destination(beneficiary())),
+		});
+		assert_noop!(
+			call.dispatch(RuntimeOrigin::signed(RECIPIENT)).map_err(|err| err.error),
+			Error::<Test>::ExecutionFailed
+		);
+
+		assert!(<FailedTransfers<Test>>::contains_key(id));
+		assert_eq!(Assets::balance(ASSET_ID, &XcmSupport::account_id()), 501);
+	});
+}
+
+#[test]
+fn test_claim_transfer() {
+	new_test_ext().execute_with(|| {
+		let id = hold_failed_transfer();
+
+		// Only the recipient can claim the transfer before it expires
+		assert_noop!(
+			XcmSupport::claim(RuntimeOrigin::signed(OTHER), id),
+			Error::<Test>::NotRecipient
+		);
+
+		// Afterwards anyone can, but the assets are still deposited to the recipient
+		System::set_block_number(12);
+		assert_ok!(XcmSupport::claim(RuntimeOrigin::signed(OTHER), id));
+
+		assert_eq!(
+			last_event(),
+			RuntimeEvent::XcmSupport(Event::TransferClaimed { id, recipient: RECIPIENT })
+		);
+		assert!(!<FailedTransfers<Test>>::contains_key(id));
+		assert_eq!(Assets::balance(ASSET_ID, &XcmSupport::account_id()), 1);
+		assert_eq!(Assets::balance(ASSET_ID, &RECIPIENT), 1_000);
+		assert_eq!(Assets::balance(ASSET_ID, &OTHER), 0);
+	});
+}
@@ -0,0 +1,59 @@
+//! Weights for snowbridge_xcm_support
+//!
+//! THESE WEIGHTS ARE PLACEHOLDERS: they were estimated by hand and have not been generated with
+//! the Substrate benchmark CLI. They exclude the execution of XCM programs, which is weighed
+//! separately with `pallet_xcm::Config::Weigher`. Regenerate this file with
+//! `scripts/benchmark.sh` on the reference hardware before relying on them.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for snowbridge_xcm_support.
+pub trait WeightInfo {
+	fn retry_transfer() -> Weight;
+	fn claim() -> Weight;
+	fn hold() -> Weight;
+}
+
+/// Weights for snowbridge_xcm_support using the Snowbridge node and recommended hardware.
+pub struct SnowbridgeWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SnowbridgeWeight<T> {
+	fn retry_transfer() -> Weight {
+		Weight::from_ref_time(172_418_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(10 as u64))
+			.saturating_add(T::DbWeight::get().writes(7 as u64))
+	}
+	fn claim() -> Weight {
+		Weight::from_ref_time(41_305_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+	}
+	fn hold() -> Weight {
+		Weight::from_ref_time(38_412_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(5 as u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn retry_transfer() -> Weight {
+		Weight::from_ref_time(172_418_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(10 as u64))
+			.saturating_add(RocksDbWeight::get().writes(7 as u64))
+	}
+	fn claim() -> Weight {
+		Weight::from_ref_time(41_305_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+	}
+	fn hold() -> Weight {
+		Weight::from_ref_time(38_412_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(5 as u64))
+	}
+}
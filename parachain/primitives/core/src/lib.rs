@@ -6,7 +6,7 @@
 #![allow(unused_variables)]
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::dispatch::DispatchError;
+use frame_support::{dispatch::DispatchError, weights::Weight};
 use frame_system::Config;
 use snowbridge_ethereum::{Header, Log, U256};
 use sp_core::H160;
@@ -14,7 +14,7 @@ use sp_std::prelude::*;
 
 pub mod types;
 
-pub use types::{Message, MessageId, MessageNonce, Proof};
+pub use types::{DeliveryStatus, DispatchOutcome, Message, MessageId, MessageNonce, Proof};
 
 /// A trait for verifying messages.
 ///
@@ -22,16 +22,56 @@ pub use types::{Message, MessageId, MessageNonce, Proof};
 /// functionality.
 pub trait Verifier {
 	fn verify(message: &Message) -> Result<(Log, u64), DispatchError>;
+	/// Verify several messages, returning the result for each message in order. Verifiers
+	/// should override this to share work between messages included in the same block.
+	fn verify_batch(messages: &[Message]) -> Vec<Result<(Log, u64), DispatchError>> {
+		messages.iter().map(Self::verify).collect()
+	}
 	fn initialize_storage(
 		headers: Vec<Header>,
 		initial_difficulty: U256,
 		descendants_until_final: u8,
 	) -> Result<(), &'static str>;
+	/// Prepare the verifier to accept messages carrying the RLP-encoded `logs`, all included
+	/// in the same block and each with a proof of at least `proof_size` bytes. Returns the
+	/// messages.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn initialize_benchmark_messages(logs: Vec<Vec<u8>>, proof_size: u32) -> Vec<Message>;
+}
+
+/// Submit a message to Ethereum on an outbound channel.
+pub trait OutboundChannel<AccountId> {
+	/// Submit a message with the given payload, sent by `who`, who also pays the message fee.
+	/// Returns the nonce assigned to the message.
+	fn submit(who: &AccountId, payload: &[u8]) -> Result<u64, DispatchError>;
 }
 
 /// Dispatch a message
 pub trait MessageDispatch<T: Config, MessageId> {
-	fn dispatch(source: H160, id: MessageId, payload: &[u8]);
+	/// Dispatch the call encoded in `payload`, unless it requires more than `weight_limit`.
+	/// Returns the outcome and the weight actually used.
+	fn dispatch(
+		source: H160,
+		id: MessageId,
+		payload: &[u8],
+		weight_limit: Weight,
+	) -> (DispatchOutcome, Weight);
 	#[cfg(feature = "runtime-benchmarks")]
 	fn successful_dispatch_event(id: MessageId) -> Option<<T as Config>::RuntimeEvent>;
 }
+
+/// Handle the outcome of a message which was not dispatched when it was delivered, as its calls
+/// required more weight than the limit, and has been executed later.
+pub trait OverweightMessageExecuted<MessageId> {
+	/// Maximum weight of `overweight_message_executed`.
+	fn weight() -> Weight;
+	/// Handle the outcome of the message `id` sent by the source application `source`.
+	fn overweight_message_executed(id: &MessageId, source: H160, outcome: DispatchOutcome);
+}
+
+impl<MessageId> OverweightMessageExecuted<MessageId> for () {
+	fn weight() -> Weight {
+		Weight::zero()
+	}
+	fn overweight_message_executed(_id: &MessageId, _source: H160, _outcome: DispatchOutcome) {}
+}
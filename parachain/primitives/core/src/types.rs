@@ -1,12 +1,12 @@
 //! Types for representing messages
 
-use codec::{Decode, Encode};
+use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{scale_info::TypeInfo, RuntimeDebug};
 use sp_core::{H160, H256};
 use sp_runtime::DigestItem;
 use sp_std::vec::Vec;
 
-#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 pub struct MessageId {
 	account: H160,
 	nonce: u64,
@@ -16,10 +16,61 @@ impl MessageId {
 	pub fn new(account: H160, nonce: u64) -> MessageId {
 		MessageId { account, nonce }
 	}
+
+	pub fn account(&self) -> H160 {
+		self.account
+	}
+
+	pub fn nonce(&self) -> u64 {
+		self.nonce
+	}
 }
 
 pub type MessageNonce = u64;
 
+/// Outcome of dispatching a message relayed from Ethereum.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum DispatchOutcome {
+	/// The message was dispatched and its call succeeded.
+	Succeeded,
+	/// The message was dispatched but its call returned an error.
+	Failed,
+	/// The message was rejected by the call filter.
+	Rejected,
+	/// The message payload could not be decoded into a call.
+	DecodeFailed,
+	/// The call requires more weight than the limit for dispatching a message.
+	Overweight,
+}
+
+impl DispatchOutcome {
+	/// Whether the call of the message was dispatched and succeeded.
+	pub fn is_success(&self) -> bool {
+		*self == DispatchOutcome::Succeeded
+	}
+
+	/// Code identifying the outcome when it is reported back to Ethereum. Zero if the call
+	/// succeeded.
+	pub fn error_code(&self) -> u8 {
+		match self {
+			DispatchOutcome::Succeeded => 0,
+			DispatchOutcome::Failed => 1,
+			DispatchOutcome::Rejected => 2,
+			DispatchOutcome::DecodeFailed => 3,
+			DispatchOutcome::Overweight => 4,
+		}
+	}
+}
+
+/// Delivery status of a message relayed from Ethereum.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum DeliveryStatus {
+	/// The message has not been delivered yet.
+	NotDelivered,
+	/// The message has been delivered. `outcome` is `None` if its receipt has been pruned.
+	Delivered { outcome: Option<DispatchOutcome> },
+}
+
 /// A message relayed from Ethereum.
 #[derive(PartialEq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub struct Message {
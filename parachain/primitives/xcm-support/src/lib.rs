@@ -8,22 +8,28 @@
 use codec::{Decode, Encode};
 use scale_info::TypeInfo;
 use sp_core::{RuntimeDebug, H160, H256};
+use xcm::{latest::Weight, VersionedMultiAsset, VersionedMultiLocation};
 
-/// Represents a remote parachain by id with a fee that will be used by
+/// Represents a remote destination with a fee that will be used by
 /// `XcmReserveTransfer::reserve_transfer` to send an asset to a remote
-/// parachain.
-#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, PartialOrd, RuntimeDebug, TypeInfo)]
-#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
-pub struct RemoteParachain {
-	/// The parachain id.
-	pub para_id: u32,
-	/// The fee required for XCM execution.
-	pub fee: u128,
+/// chain.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct RemoteDestination {
+	/// The destination chain, relative to our parachain. For example a sibling parachain is
+	/// `(1, Parachain(para_id))`.
+	pub dest: VersionedMultiLocation,
+	/// The beneficiary, relative to the destination chain. For example `AccountId32` for
+	/// Substrate-based parachains, or `AccountKey20` for EVM-based parachains.
+	pub beneficiary: VersionedMultiLocation,
+	/// The asset, relative to our parachain, and amount used to pay for XCM execution on the
+	/// destination chain. Assets reserved by the relay chain or another parachain are withdrawn
+	/// from their reserve, and the transferred asset then pays for its own execution on the
+	/// destination chain.
+	pub fee: VersionedMultiAsset,
 }
 
 /// Represents information about an XCM transfer.
-#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, PartialOrd, RuntimeDebug, TypeInfo)]
-#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
 pub struct TransferInfo {
 	/// The asset id on our parachain.
 	pub asset_id: u128,
@@ -33,19 +39,38 @@ pub struct TransferInfo {
 	pub recipient: H256,
 	// The amount transferred.
 	pub amount: u128,
-	/// The destination parachain id.
-	pub para_id: u32,
+	/// The destination chain.
+	pub dest: VersionedMultiLocation,
+	/// The beneficiary on the destination chain.
+	pub beneficiary: VersionedMultiLocation,
 	/// The fee paid for the xcm request.
-	pub fee: u128,
+	pub fee: VersionedMultiAsset,
 }
 
-/// Transfers an asset to the destination parachain. Transfer failures are emitted by events.
+/// Estimated cost of a transfer made with `XcmReserveTransfer::reserve_transfer`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct ReserveTransferEstimate {
+	/// Weight of the XCM program executed on our parachain.
+	pub local_weight: Weight,
+	/// Weight of the XCM program executed on the destination chain.
+	pub remote_weight: Weight,
+	/// Estimated fee for executing the XCM program on the destination chain, in the fee asset
+	/// of the transfer, relative to our parachain.
+	pub remote_fee: VersionedMultiAsset,
+}
+
+/// Transfers an asset to the destination chain. Transfer failures are emitted by events.
 pub trait XcmReserveTransfer<AccountId, RuntimeOrigin> {
+	/// Weight of a transfer to `destination` on our parachain, including the XCM program it
+	/// executes and holding the assets if it fails. Transfers whose program cannot be built or
+	/// weighed fail without executing it.
+	fn reserve_transfer_weight(destination: &RemoteDestination) -> Weight;
+
 	fn reserve_transfer(
 		asset_id: u128,
 		sender: H160,
 		recipient: &AccountId,
 		amount: u128,
-		destination: RemoteParachain,
+		destination: RemoteDestination,
 	);
 }
@@ -1,10 +1,27 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::parameter_types;
+use frame_support::{
+	parameter_types,
+	weights::{constants::WEIGHT_REF_TIME_PER_SECOND, Weight},
+	PalletId,
+};
 
 parameter_types! {
 	pub const MaxMessagePayloadSize: u32 = 256;
 	pub const MaxMessagesPerCommit: u32 = 20;
+	pub const MaxScheduledSources: u32 = 16;
+	pub const MaxDeliveryReceipts: u32 = 1024;
+	pub const MaxMessagesPerBatch: u32 = 16;
+	pub const MaxInboundPayloadSize: u32 = 1024;
+	/// Max weight to dispatch a single inbound message. The proof size allows a full batch of
+	/// messages to fit in a PoV with room to spare.
+	pub const MaxDispatchWeight: Weight = Weight::from_parts(5_000_000_000, 64 * 1024);
+	/// Ref time per unit of gas, assuming that a second of ref time is worth 40M gas as in
+	/// Frontier.
+	pub const WeightPerGas: u64 = WEIGHT_REF_TIME_PER_SECOND / 40_000_000;
+	pub const BasicInboundChannelPalletId: PalletId = PalletId(*b"s/basinb");
+	pub const BasicOutboundChannelPalletId: PalletId = PalletId(*b"s/basout");
+	pub const XcmSupportPalletId: PalletId = PalletId(*b"s/xcmsup");
 }
 
 /// Money matters.
@@ -11,13 +11,14 @@ mod weights;
 
 use cumulus_pallet_parachain_system::RelayNumberStrictlyIncreases;
 use snowbridge_beacon_primitives::{Fork, ForkVersions};
+use snowbridge_xcm_support_primitives::{RemoteDestination, ReserveTransferEstimate};
 use sp_api::impl_runtime_apis;
-use sp_core::{crypto::KeyTypeId, ConstU32, OpaqueMetadata};
+use sp_core::{crypto::KeyTypeId, ConstU32, OpaqueMetadata, H160};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	traits::{AccountIdLookup, BlakeTwo256, Block as BlockT, Keccak256},
 	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult,
+	ApplyExtrinsicResult, DispatchError, FixedU128,
 };
 
 use sp_std::prelude::*;
@@ -69,7 +70,12 @@ use xcm_builder::{
 
 use xcm_executor::{traits::JustTry, Config, XcmExecutor};
 
-use runtime_common::{fee::WeightToFee, MaxMessagePayloadSize, MaxMessagesPerCommit};
+use runtime_common::{
+	fee::WeightToFee, BasicInboundChannelPalletId, BasicOutboundChannelPalletId,
+	MaxDeliveryReceipts, MaxDispatchWeight, MaxInboundPayloadSize, MaxMessagePayloadSize,
+	MaxMessagesPerBatch, MaxMessagesPerCommit, MaxScheduledSources, WeightPerGas,
+	XcmSupportPalletId,
+};
 
 pub use runtime_primitives::{AccountId, Address, Balance, BlockNumber, Hash, Index, Signature};
 
@@ -269,6 +275,9 @@ parameter_types! {
 	pub CheckingAccount: AccountId = PolkadotXcm::check_account();
 	pub UniversalLocation: InteriorMultiLocation =
 		X2(GlobalConsensus(RelayNetwork::get()), Parachain(ParachainInfo::parachain_id().into()));
+	pub const EthereumNetwork: NetworkId = NetworkId::Ethereum { chain_id: 15 };
+	pub EthereumLocation: MultiLocation =
+		MultiLocation::new(2, X1(GlobalConsensus(EthereumNetwork::get())));
 }
 
 /// Type for specifying how a `MultiLocation` can be converted into an `AccountId`. This is used
@@ -283,11 +292,15 @@ pub type LocationToAccountId = (
 	AccountId32Aliases<RelayNetwork, AccountId>,
 );
 
+/// Matches assets of the `Assets` pallet, located by their id under the pallet.
+pub type AssetsMatcher =
+	ConvertedConcreteId<u32, Balance, AsPrefixedGeneralIndex<Local, u32, JustTry>, JustTry>;
+
 pub type FungiblesTransactor = FungiblesAdapter<
 	// Use this fungibles implementation:
 	Assets,
 	// Use this currency when it is a fungible asset matching the given location or name:
-	ConvertedConcreteId<u32, Balance, AsPrefixedGeneralIndex<Local, u32, JustTry>, JustTry>,
+	AssetsMatcher,
 	// Convert MultiLocation into a native chain account ID:
 	LocationToAccountId,
 	// Our chain's account ID type (we can't get away without mentioning it explicitly):
@@ -310,7 +323,20 @@ type LocalAssetTransactor = CurrencyAdapter<
 	(),
 >;
 
-type AssetTransactors = (LocalAssetTransactor, FungiblesTransactor);
+/// Bridged ERC20 tokens deposited to accounts on Ethereum are unlocked on Ethereum. Comes first,
+/// as other transactors fail on Ethereum locations rather than passing them on.
+pub type EthereumTransactor = erc20_app::EthereumAssetTransactor<
+	// The ERC20 app, which sends unlock messages:
+	Runtime,
+	// Use this currency when it is a fungible asset matching the given location or name:
+	AssetsMatcher,
+	// Convert the XCM origin into the account which pays for the unlock message:
+	LocationToAccountId,
+	// The location of Ethereum accounts:
+	EthereumLocation,
+>;
+
+type AssetTransactors = (EthereumTransactor, LocalAssetTransactor, FungiblesTransactor);
 
 /// This is the type we use to convert an (incoming) XCM origin into a local `Origin` instance,
 /// ready for dispatching a transaction with Xcm's `Transact`. There is an `OriginKind` which can
@@ -570,23 +596,54 @@ impl pallet_assets::Config for Runtime {
 
 // Our pallets
 
+parameter_types! {
+	pub const OverweightMessageExpiry: BlockNumber = 7 * DAYS;
+	pub const MaxCallsPerMessage: u32 = 8;
+}
+
 impl dispatch::Config for Runtime {
 	type RuntimeOrigin = RuntimeOrigin;
 	type RuntimeEvent = RuntimeEvent;
 	type MessageId = MessageId;
 	type RuntimeCall = RuntimeCall;
-	type CallFilter = Everything;
+	type CallFilter = dispatch::AllowedCallFilter<Runtime>;
+	type OverweightMessageExpiry = OverweightMessageExpiry;
+	type MaxCallsPerMessage = MaxCallsPerMessage;
+	type OriginConverter = dispatch::SignedByDerivedAccount<Runtime>;
+	type AccountIdConverter = dispatch::HashedEthereumAccount<AccountId>;
+	type OnOverweightMessageExecuted = BasicInboundChannel;
+	type WeightInfo = dispatch::weights::SnowbridgeWeight<Self>;
+}
+
+/// Calls which Ethereum accounts are allowed to dispatch from genesis, by pallet index and call
+/// index: `ERC20App::mint`.
+pub fn genesis_allowed_calls() -> Vec<(u8, u8)> {
+	vec![(<ERC20App as frame_support::traits::PalletInfoAccess>::index() as u8, 0)]
 }
 
 use snowbridge_basic_channel::{
 	inbound as basic_channel_inbound, outbound as basic_channel_outbound,
 };
 
+parameter_types! {
+	pub ParachainId: u32 = ParachainInfo::parachain_id().into();
+}
+
 impl basic_channel_inbound::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type Verifier = ethereum_beacon_client::Pallet<Runtime>;
 	type MessageDispatch = dispatch::Pallet<Runtime>;
-	type WeightInfo = ();
+	type MaxDeliveryReceipts = MaxDeliveryReceipts;
+	type MaxMessagesPerBatch = MaxMessagesPerBatch;
+	type MaxInboundPayloadSize = MaxInboundPayloadSize;
+	type MaxDispatchWeight = MaxDispatchWeight;
+	type WeightPerGas = WeightPerGas;
+	type ParachainId = ParachainId;
+	type OutboundChannel = BasicOutboundChannel;
+	type AccountIdConverter = dispatch::HashedEthereumAccount<AccountId>;
+	type Currency = Balances;
+	type PalletId = BasicInboundChannelPalletId;
+	type WeightInfo = basic_channel_inbound::weights::SnowbridgeWeight<Self>;
 }
 
 impl basic_channel_outbound::Config for Runtime {
@@ -595,9 +652,49 @@ impl basic_channel_outbound::Config for Runtime {
 	type SourceId = <Self as frame_system::Config>::AccountId;
 	type MaxMessagePayloadSize = MaxMessagePayloadSize;
 	type MaxMessagesPerCommit = MaxMessagesPerCommit;
+	type MaxScheduledSources = MaxScheduledSources;
+	type SubmitOrigin = basic_channel_outbound::EnsureSignedSource<AccountId>;
+	type Currency = Balances;
+	type PalletId = BasicOutboundChannelPalletId;
+	type DeliveryOrigin = basic_channel_outbound::EnsureRelayer<Runtime>;
+	type Verifier = ethereum_beacon_client::Pallet<Runtime>;
 	type WeightInfo = basic_channel_outbound::weights::SnowbridgeWeight<Self>;
 }
 
+parameter_types! {
+	pub const FailedTransferExpiry: BlockNumber = 7 * DAYS;
+	// This parachain only accepts the relay chain token for fees
+	pub RemoteFeeRates: Vec<(xcm::latest::AssetId, FixedU128)> =
+		vec![(Concrete(RelayLocation::get()), FixedU128::from_u32(1))];
+}
+
+impl snowbridge_xcm_support::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	// Assume destination chains weigh and price XCM programs like this parachain does
+	type RemoteFeeEstimator = snowbridge_xcm_support::UniformRemoteFees<
+		FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>,
+		WeightToFee,
+		RemoteFeeRates,
+	>;
+	type AccountIdConverter = dispatch::HashedEthereumAccount<AccountId>;
+	type AssetId = u32;
+	type Assets = Assets;
+	type PalletId = XcmSupportPalletId;
+	type FailedTransferExpiry = FailedTransferExpiry;
+	type WeightInfo = snowbridge_xcm_support::weights::SnowbridgeWeight<Self>;
+}
+
+impl erc20_app::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type CallOrigin = EnsureSigned<AccountId>;
+	type AccountIdConverter = dispatch::HashedEthereumAccount<AccountId>;
+	type AssetId = u32;
+	type Assets = Assets;
+	type XcmReserveTransfer = XcmSupport;
+	type OutboundChannel = BasicOutboundChannel;
+	type WeightInfo = erc20_app::weights::SnowbridgeWeight<Self>;
+}
+
 parameter_types! {
 	pub const MaxSyncCommitteeSize: u32 = 32;
 	pub const MaxProofBranchSize: u32 = 20;
@@ -721,11 +818,13 @@ construct_runtime!(
 		LocalCouncilMembership: pallet_membership::<Instance1>::{Pallet, Call, Storage, Event<T>, Config<T>} = 11,
 
 		// Bridge Infrastructure
-		BasicInboundChannel: basic_channel_inbound::{Pallet, Call, Config, Storage, Event<T>} = 12,
-		BasicOutboundChannel: basic_channel_outbound::{Pallet, Config<T>, Storage, Event<T>} = 13,
-		Dispatch: dispatch::{Pallet, Call, Storage, Event<T>, Origin} = 16,
+		BasicInboundChannel: basic_channel_inbound::{Pallet, Call, Config<T>, Storage, Event<T>} = 12,
+		BasicOutboundChannel: basic_channel_outbound::{Pallet, Call, Config<T>, Storage, Event<T>} = 13,
+		Dispatch: dispatch::{Pallet, Call, Config, Storage, Event<T>, Origin} = 16,
 		EthereumBeaconClient: ethereum_beacon_client::{Pallet, Call, Config<T>, Storage, Event<T>} = 18,
 		Assets: pallet_assets::{Pallet, Call, Config<T>, Storage, Event<T>} = 19,
+		ERC20App: erc20_app::{Pallet, Call, Config, Storage, Event<T>} = 20,
+		XcmSupport: snowbridge_xcm_support::{Pallet, Call, Storage, Event<T>} = 21,
 		// XCM
 		XcmpQueue: cumulus_pallet_xcmp_queue::{Pallet, Call, Storage, Event<T>} = 22,
 		DmpQueue: cumulus_pallet_dmp_queue::{Pallet, Call, Storage, Event<T>} = 23,
@@ -767,6 +866,8 @@ pub type UncheckedExtrinsic =
 	generic::UncheckedExtrinsic<Address, RuntimeCall, Signature, SignedExtra>;
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, RuntimeCall, SignedExtra>;
+/// Migrations to apply on runtime upgrade.
+pub type Migrations = (basic_channel_inbound::migration::v1::MigrateToV1<Runtime>,);
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
 	Runtime,
@@ -774,6 +875,7 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	Migrations,
 >;
 
 impl_runtime_apis! {
@@ -883,6 +985,43 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl snowbridge_basic_channel_runtime_api::BasicOutboundChannelApi<Block, AccountId, BlockNumber> for Runtime {
+		fn pending_messages() -> u64 {
+			BasicOutboundChannel::pending_messages()
+		}
+		fn next_nonce(source_id: AccountId) -> u64 {
+			BasicOutboundChannel::next_nonce(&source_id)
+		}
+		fn latest_commitment() -> Option<(Hash, BlockNumber)> {
+			BasicOutboundChannel::latest_commitment()
+		}
+		fn block_commitments() -> Vec<Hash> {
+			BasicOutboundChannel::block_commitments()
+		}
+	}
+
+	impl snowbridge_dispatch_runtime_api::DispatchApi<Block, AccountId> for Runtime {
+		fn derived_account(address: H160) -> AccountId {
+			Dispatch::derived_account(address)
+		}
+	}
+
+	impl snowbridge_xcm_support_runtime_api::XcmSupportApi<Block> for Runtime {
+		fn estimate_reserve_transfer(
+			asset_id: u128,
+			amount: u128,
+			destination: RemoteDestination,
+		) -> Result<ReserveTransferEstimate, DispatchError> {
+			XcmSupport::estimate_reserve_transfer(asset_id, amount, destination)
+		}
+	}
+
+	impl snowbridge_basic_channel_runtime_api::BasicInboundChannelApi<Block> for Runtime {
+		fn delivery_status(account: H160, nonce: u64) -> snowbridge_core::DeliveryStatus {
+			BasicInboundChannel::delivery_status(account, nonce)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
@@ -902,6 +1041,10 @@ impl_runtime_apis! {
 			list_benchmark!(list, extra, pallet_utility, Utility);
 			list_benchmark!(list, extra, pallet_scheduler, Scheduler);
 			list_benchmark!(list, extra, assets, Assets);
+			list_benchmark!(list, extra, dispatch, Dispatch);
+			list_benchmark!(list, extra, erc20_app, ERC20App);
+			list_benchmark!(list, extra, snowbridge_xcm_support, XcmSupport);
+			list_benchmark!(list, extra, basic_channel_inbound, BasicInboundChannel);
 			list_benchmark!(list, extra, basic_channel_outbound, BasicOutboundChannel);
 			list_benchmark!(list, extra, ethereum_beacon_client, EthereumBeaconClient);
 
@@ -941,6 +1084,10 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, pallet_utility, Utility);
 			add_benchmark!(params, batches, pallet_scheduler, Scheduler);
 			add_benchmark!(params, batches, assets, Assets);
+			add_benchmark!(params, batches, dispatch, Dispatch);
+			add_benchmark!(params, batches, erc20_app, ERC20App);
+			add_benchmark!(params, batches, snowbridge_xcm_support, XcmSupport);
+			add_benchmark!(params, batches, basic_channel_inbound, BasicInboundChannel);
 			add_benchmark!(params, batches, basic_channel_outbound, BasicOutboundChannel);
 			add_benchmark!(params, batches, ethereum_beacon_client, EthereumBeaconClient);
 
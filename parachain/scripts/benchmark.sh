@@ -1,13 +1,24 @@
 #!/usr/bin/env bash
 
-# Example command for updating pallet benchmarking
-target/release/snowbridge benchmark \
-  --chain spec.json \
-  --execution wasm \
-  --wasm-execution compiled \
-  --pallet 'basic_channel::inbound' \
-  --extrinsic '*' \
-  --repeat 20 \
-  --steps 50 \
-  --output pallets/basic-channel/src/inbound/weights.rs \
-  --template templates/module-weight-template.hbs
+# Regenerate the weights of the bridge pallets. Requires a node built with
+# `--features runtime-benchmarks` and a chain spec for the runtime to benchmark.
+
+set -eu
+
+benchmark() {
+  target/release/snowbridge benchmark pallet \
+    --chain spec.json \
+    --execution wasm \
+    --wasm-execution compiled \
+    --pallet "$1" \
+    --extrinsic '*' \
+    --repeat 20 \
+    --steps 50 \
+    --output "$2" \
+    --template templates/module-weight-template.hbs
+}
+
+benchmark basic_channel_inbound pallets/basic-channel/src/inbound/weights.rs
+benchmark basic_channel_outbound pallets/basic-channel/src/outbound/weights.rs
+benchmark erc20_app pallets/erc20-app/src/weights.rs
+benchmark snowbridge_xcm_support pallets/xcm-support/src/weights.rs
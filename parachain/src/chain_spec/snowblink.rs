@@ -91,11 +91,20 @@ fn testnet_genesis(
 			],
 			phantom: Default::default(),
 		},
+		dispatch: snowblink_runtime::DispatchConfig {
+			allowed_calls: snowblink_runtime::genesis_allowed_calls(),
+		},
 		basic_inbound_channel: snowblink_runtime::BasicInboundChannelConfig {
-			source_channel: Default::default(),
+			source_channels: Default::default(),
+			reward: 0,
+		},
+		basic_outbound_channel: snowblink_runtime::BasicOutboundChannelConfig {
+			interval: 1,
+			fee: 10_000_000_000,
+			inbound_channel: Default::default(),
 		},
-		basic_outbound_channel: snowblink_runtime::BasicOutboundChannelConfig { interval: 1 },
 		assets: Default::default(),
+		erc20_app: snowblink_runtime::ERC20AppConfig { address: Default::default() },
 		ethereum_beacon_client: snowblink_runtime::EthereumBeaconClientConfig {
 			initial_sync: Default::default(),
 		},
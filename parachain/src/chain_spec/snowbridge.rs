@@ -91,11 +91,20 @@ fn testnet_genesis(
 			],
 			phantom: Default::default(),
 		},
+		dispatch: snowbridge_runtime::DispatchConfig {
+			allowed_calls: snowbridge_runtime::genesis_allowed_calls(),
+		},
 		basic_inbound_channel: snowbridge_runtime::BasicInboundChannelConfig {
-			source_channel: Default::default(),
+			source_channels: Default::default(),
+			reward: 0,
+		},
+		basic_outbound_channel: snowbridge_runtime::BasicOutboundChannelConfig {
+			interval: 1,
+			fee: 10_000_000_000,
+			inbound_channel: Default::default(),
 		},
-		basic_outbound_channel: snowbridge_runtime::BasicOutboundChannelConfig { interval: 1 },
 		assets: Default::default(),
+		erc20_app: snowbridge_runtime::ERC20AppConfig { address: Default::default() },
 		ethereum_beacon_client: snowbridge_runtime::EthereumBeaconClientConfig {
 			initial_sync: Default::default(),
 		},
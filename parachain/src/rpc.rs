@@ -7,7 +7,7 @@
 
 use std::sync::Arc;
 
-use snowbridge_runtime_primitives::{AccountId, Balance, Block, Index as Nonce};
+use snowbridge_runtime_primitives::{AccountId, Balance, Block, BlockNumber, Index as Nonce};
 
 use sc_client_api::AuxStore;
 pub use sc_rpc::{DenyUnsafe, SubscriptionTaskExecutor};
@@ -46,19 +46,30 @@ where
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
 	C::Api: BlockBuilder<Block>,
+	C::Api: snowbridge_basic_channel_runtime_api::BasicOutboundChannelApi<
+		Block,
+		AccountId,
+		BlockNumber,
+	>,
 	P: TransactionPool + Sync + Send + 'static,
 	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
 	B::State: sc_client_api::backend::StateBackend<sp_runtime::traits::HashFor<Block>>,
 {
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
-	use snowbridge_basic_channel_rpc::{BasicChannel, BasicChannelApiServer};
+	use snowbridge_basic_channel_rpc::{
+		BasicChannel, BasicChannelApiServer, BasicChannelCommitmentApiServer,
+		BasicChannelCommitments,
+	};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 
 	let mut module = RpcExtension::new(());
 	let FullDeps { backend, client, pool, deny_unsafe } = deps;
 
 	module.merge(System::new(client.clone(), pool, deny_unsafe).into_rpc())?;
-	module.merge(TransactionPayment::new(client).into_rpc())?;
+	module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+	module.merge(
+		BasicChannelCommitments::<_, Block, AccountId, BlockNumber>::new(client).into_rpc(),
+	)?;
 
 	if let Some(basic_channel_rpc) = backend
 		.offchain_storage()
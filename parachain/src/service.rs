@@ -24,7 +24,7 @@ use substrate_prometheus_endpoint::Registry;
 
 use sc_consensus::ImportQueue;
 
-use snowbridge_runtime_primitives::{AccountId, Balance, Block, Hash, Index as Nonce};
+use snowbridge_runtime_primitives::{AccountId, Balance, Block, BlockNumber, Hash, Index as Nonce};
 
 #[cfg(feature = "snowbridge-native")]
 pub struct SnowbridgeRuntimeExecutor;
@@ -85,6 +85,7 @@ pub trait RuntimeApiCollection:
 	+ cumulus_primitives_core::CollectCollationInfo<Block>
 	+ substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>
 	+ pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>
+	+ snowbridge_basic_channel_runtime_api::BasicOutboundChannelApi<Block, AccountId, BlockNumber>
 where
 	<Self as sp_api::ApiExt<Block>>::StateBackend: sp_api::StateBackend<BlakeTwo256>,
 {
@@ -103,7 +104,8 @@ where
 		+ sp_consensus_aura::AuraApi<Block, sp_consensus_aura::sr25519::AuthorityId>
 		+ cumulus_primitives_core::CollectCollationInfo<Block>
 		+ substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>
-		+ pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+		+ pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>
+		+ snowbridge_basic_channel_runtime_api::BasicOutboundChannelApi<Block, AccountId, BlockNumber>,
 	<Self as sp_api::ApiExt<Block>>::StateBackend: sp_api::StateBackend<BlakeTwo256>,
 {
 }
@@ -37,7 +37,9 @@ func NewQueryClient() QueryClient {
 	}
 }
 
-func (q *QueryClient) QueryEvent(ctx context.Context, api string, blockHash types.Hash) (*BasicChannelEvent, error) {
+// QueryEvents returns the basicOutboundChannel.Committed events in a block, keyed by commitment hash.
+// A block can contain several commitments when the outbound channel is draining its backlog.
+func (q *QueryClient) QueryEvents(ctx context.Context, api string, blockHash types.Hash) (map[types.H256]BasicChannelEvent, error) {
 	name, args := q.NameArgs(api, blockHash.Hex())
 	cmd := exec.CommandContext(ctx, name, args...)
 
@@ -66,7 +68,7 @@ func (q *QueryClient) QueryEvent(ctx context.Context, api string, blockHash type
 		"inputItems": items,
 	}).Debug("parachain.QueryEvents")
 
-	var event *BasicChannelEvent
+	events := make(map[types.H256]BasicChannelEvent, len(items.Items))
 
 	for _, item := range items.Items {
 
@@ -81,14 +83,14 @@ func (q *QueryClient) QueryEvent(ctx context.Context, api string, blockHash type
 		if err != nil {
 			return nil, err
 		}
-		event = &BasicChannelEvent{
+		events[hash] = BasicChannelEvent{
 			Hash:     hash,
 			Messages: messages,
 		}
 	}
 	log.WithFields(log.Fields{
-		"event": event,
+		"events": events,
 	}).Debug("parachain.QueryEvents")
 
-	return event, nil
+	return events, nil
 }
@@ -11,14 +11,18 @@ import (
 	"github.com/stretchr/testify/assert"
 )
 
-// TODO: update mock data
 var mock = `{
   "items": [
     {
       "id": 0,
-	  "hash": "0xb957c7eacb53bb42cae6309174fdf564db02deee95eb5861a2b4b890780fbfc8",
-	  "data": "0x0400d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d04040054d6643762e46036b3448659791adaf55422554191017ed9db59d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d00000000000000000000000089b4ab1ef20763630df9743acf155865600daff20000000000000000000000000000000000000000000000056bc75e2d63100000"
-	}
+      "hash": "0xb957c7eacb53bb42cae6309174fdf564db02deee95eb5861a2b4b890780fbfc8",
+      "data": "0x04d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d0400e40b5402000000000000000000000091017ed9db59d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d00000000000000000000000089b4ab1ef20763630df9743acf155865600daff20000000000000000000000000000000000000000000000056bc75e2d63100000"
+    },
+    {
+      "id": 1,
+      "hash": "0x3e6dc5bf4b6bd3e9d6e2a5bb4c2a2b3b1f3f0c6f0e4b1c3d9a3e8f7b6a5c4d3e",
+      "data": "0x04d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d0800e40b5402000000000000000000000091017ed9db59d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d00000000000000000000000089b4ab1ef20763630df9743acf155865600daff20000000000000000000000000000000000000000000000056bc75e2d63100000"
+    }
   ]
 }
 `
@@ -44,12 +48,28 @@ func TestQueryEvents(t *testing.T) {
 	}
 
 	foo, _ := types.NewHashFromHexString("0x6456d3a2f0c7526d63ad50e79dc8a462931a58ffd57270c3c8aabbcdbd78e76b")
-	event, err := client.QueryEvent(context.Background(), "", foo)
+	events, err := client.QueryEvents(context.Background(), "", foo)
 	if err != nil {
 		t.Fatal(err)
 	}
 
-	assert.NotNil(t, event)
-	assert.Equal(t, event.Messages[0].Nonce.Int64(), int64(1))
+	assert.Equal(t, len(events), 2)
+
+	var first types.H256
+	if err := types.DecodeFromHexString("0xb957c7eacb53bb42cae6309174fdf564db02deee95eb5861a2b4b890780fbfc8", &first); err != nil {
+		t.Fatal(err)
+	}
+	event, ok := events[first]
+	assert.True(t, ok)
 	assert.Equal(t, len(event.Messages), 1)
+	assert.Equal(t, event.Messages[0].Nonce.Int64(), int64(1))
+	assert.Equal(t, event.Messages[0].Fee.Int64(), int64(10_000_000_000))
+
+	var second types.H256
+	if err := types.DecodeFromHexString("0x3e6dc5bf4b6bd3e9d6e2a5bb4c2a2b3b1f3f0c6f0e4b1c3d9a3e8f7b6a5c4d3e", &second); err != nil {
+		t.Fatal(err)
+	}
+	event, ok = events[second]
+	assert.True(t, ok)
+	assert.Equal(t, event.Messages[0].Nonce.Int64(), int64(2))
 }
@@ -203,24 +203,25 @@ func (s *Scanner) findTasksImpl(
 
 		basicChannelProofs := make([]MessageProof, 0, len(basicChannelSourceNonces))
 
-		events, err := s.eventQueryClient.QueryEvent(ctx, s.config.Parachain.Endpoint, blockHash)
+		events, err := s.eventQueryClient.QueryEvents(ctx, s.config.Parachain.Endpoint, blockHash)
 		if err != nil {
 			return nil, fmt.Errorf("query events: %w", err)
 		}
 
-		for _, digestItem := range digestItems {
+		// A block can contain several commitments. Walk them from newest to oldest, in line with
+		// the backwards scan over blocks, so that later messages of a source are collected before
+		// the scan for that source terminates.
+		for i := len(digestItems) - 1; i >= 0; i-- {
+			digestItem := digestItems[i]
 			if !digestItem.IsCommitment {
 				continue
 			}
 
 			if !scanBasicChannelDone {
-				if events == nil {
-					return nil, fmt.Errorf("event basicOutboundChannel.Committed not found in block")
-				}
-
 				digestItemHash := digestItem.AsCommitment.Hash
-				if events.Hash != digestItemHash {
-					return nil, fmt.Errorf("basic channel commitment hash in digest item does not match the one in the Committed event")
+				event, ok := events[digestItemHash]
+				if !ok {
+					return nil, fmt.Errorf("event basicOutboundChannel.Committed not found in block for commitment %v", types.HexEncodeToString(digestItemHash[:]))
 				}
 
 				// For basic channel commit hash is the merkle root calculated from messages
@@ -231,12 +232,12 @@ func (s *Scanner) findTasksImpl(
 					digestItemHash,
 					basicChannelSourceNonces,
 					basicChannelScanSources,
-					events.Messages,
+					event.Messages,
 				)
 				if err != nil {
 					return nil, err
 				}
-				basicChannelProofs = result.proofs
+				basicChannelProofs = append(result.proofs, basicChannelProofs...)
 				scanBasicChannelDone = result.scanDone
 			}
 		}
@@ -80,6 +80,7 @@ func NewMerkleProof(rawProof RawMerkleProof) (MerkleProof, error) {
 type BasicOutboundChannelMessage struct {
 	SourceID types.AccountID
 	Nonce    types.UCompact
+	Fee      types.U128
 	Payload  []byte
 }
 